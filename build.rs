@@ -3,18 +3,27 @@ const COMMANDS: &[&str] = &[
     "load",
     "save",
     "patch",
+    "get_value",
+    "set_value",
+    "remove_value",
+    "load_layered",
+    "save_layered",
     "delete",
     "exists",
     "load_all",
     "save_all",
     "patch_all",
     "unlock",
+    "keyring_list",
+    "keyring_gc",
     "watch_file",
     "unwatch_file",
     "list_configs",
     "reset",
     "export_config",
+    "import_chunk",
     "import_config",
+    "cache_stats",
 ];
 
 fn main() {
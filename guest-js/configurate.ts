@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { Channel, invoke } from "@tauri-apps/api/core";
 import { listen as tauriListen } from "@tauri-apps/api/event";
 export { BaseDirectory } from "@tauri-apps/api/path";
 import type { BaseDirectory } from "@tauri-apps/api/path";
@@ -10,6 +10,9 @@ import {
   hasArrayKeyring,
   collectStaticKeyringPaths,
   collectKeyringReadEntries,
+  collectKeyringIds,
+  dotpathTouchesKeyring,
+  isKeyringIdTemplate,
   separateSecrets,
   assertDataMatchesSchema,
   assertPartialDataMatchesSchema,
@@ -38,6 +41,25 @@ export interface KeyringOptions {
   account: string;
 }
 
+/** Keyring id tracked in a config's keyring manifest. */
+export interface KeyringManifestEntry {
+  service: string;
+  account: string;
+  id: string;
+}
+
+/** Result entry of `config.keyringList()`. Secret values are never returned. */
+export interface KeyringListEntry extends KeyringManifestEntry {
+  /** Whether a secret is currently stored for this id. */
+  exists: boolean;
+}
+
+/** Result of `config.keyringGc()`. */
+export interface KeyringGcResult {
+  removed: KeyringManifestEntry[];
+  failed: (KeyringManifestEntry & { error: unknown })[];
+}
+
 function validateKeyringOptions(opts: KeyringOptions): void {
   if (!opts.service.trim()) {
     throw new Error('Configurate: keyring "service" must not be empty.');
@@ -66,6 +88,115 @@ export interface ConfiguratePathOptions {
   currentPath?: string;
 }
 
+/**
+ * Bundled template that `create` / `load` copy into place when the config
+ * does not exist yet. JSON / YAML / TOML templates are converted to the
+ * config's provider.
+ */
+export interface SeedFrom {
+  baseDir: BaseDirectory;
+  fileName: string;
+  dirName?: string;
+  /** Template provider. Inferred from the `fileName` extension when omitted. */
+  provider?: ConfigurateProvider;
+}
+
+export interface CreateOptions {
+  /** Seed a missing config from a template; `data` is deep-merged over it. */
+  seedFrom?: SeedFrom;
+}
+
+export interface LoadOptions<S extends SchemaObject> {
+  /** Seed a missing config from a template before loading it. */
+  seedFrom?: SeedFrom;
+  /** Deep-merged over the template when seeding. Keyring fields are not stored. */
+  data?: Partial<InferUnlocked<S>>;
+  /** Receive the response in chunks through a channel. For very large configs. */
+  stream?: boolean;
+}
+
+/** A piece of a streamed `load` / `exportAs` response. */
+export interface StreamChunk {
+  index: number;
+  data: string;
+  last: boolean;
+}
+
+export interface ExportStreamOptions {
+  /** Receive the exported text in chunks through a channel. */
+  stream?: boolean;
+  /** Called for every chunk, in order, when streaming. */
+  onChunk?: (chunk: StreamChunk) => void;
+}
+
+/**
+ * Formats for `exportAs` / `importFrom`. `cbor` and `msgpack` are exchanged
+ * as base64 text.
+ */
+export type ExchangeFormat =
+  | "json"
+  | "yml"
+  | "toml"
+  | "ini"
+  | "dotenv"
+  | "xml"
+  | "plist"
+  | "cbor"
+  | "msgpack";
+
+/** Content accepted by `importFrom`; iterables are uploaded piece by piece. */
+export type ImportContent = string | Iterable<string> | AsyncIterable<string>;
+
+/** Maximum `import_chunk` size, in UTF-16 code units. */
+const IMPORT_CHUNK_SIZE = 256 * 1024;
+
+/**
+ * Invokes `command` with a channel and resolves with the concatenated chunks
+ * once the last one has arrived.
+ */
+async function invokeStreamed(
+  command: string,
+  args: Record<string, unknown>,
+  onChunk?: (chunk: StreamChunk) => void,
+): Promise<string> {
+  const parts: string[] = [];
+  let finish!: () => void;
+  const finished = new Promise<void>((resolve) => {
+    finish = resolve;
+  });
+  const channel = new Channel<StreamChunk>();
+  channel.onmessage = (chunk) => {
+    parts.push(chunk.data);
+    onChunk?.(chunk);
+    if (chunk.last) finish();
+  };
+  await invoke(command, { ...args, channel });
+  await finished;
+  return parts.join("");
+}
+
+/**
+ * Splits import content into upload chunks of at most `IMPORT_CHUNK_SIZE`
+ * code units. A surrogate pair is never split, including across the pieces
+ * of an iterable.
+ */
+async function* importChunks(content: ImportContent): AsyncGenerator<string> {
+  const pieces = typeof content === "string" ? [content] : content;
+  let pending = "";
+  for await (const piece of pieces) {
+    pending += piece;
+    while (pending.length > IMPORT_CHUNK_SIZE) {
+      let end = IMPORT_CHUNK_SIZE;
+      const code = pending.charCodeAt(end - 1);
+      if (code >= 0xd800 && code <= 0xdbff) end -= 1;
+      yield pending.slice(0, end);
+      pending = pending.slice(end);
+    }
+  }
+  // Keep a trailing high surrogate for the next piece; flush at the end.
+  yield pending;
+}
+
 export interface SchemaValidationOptions {
   /** Validate payload data against schema before create/save. Default: false */
   validateOnWrite?: boolean;
@@ -187,7 +318,7 @@ function normalizeConfigurateInit<S extends SchemaObject>(
   const provider = input.provider;
   if (!isProvider(provider)) {
     throw new Error(
-      "Configurate: provider must be created by JsonProvider/YmlProvider/TomlProvider/BinaryProvider.",
+      "Configurate: provider must be created by JsonProvider/YmlProvider/TomlProvider/IniProvider/DotenvProvider/XmlProvider/PlistProvider/BinaryProvider.",
     );
   }
 
@@ -236,14 +367,50 @@ function buildChangeTargetId(
 // Result types
 // ---------------------------------------------------------------------------
 
+/** A key whose loaded value was replaced by an environment variable. */
+export interface EnvOverride {
+  /** Dotpath of the overridden key. */
+  path: string;
+  /** Name of the environment variable that supplied the value. */
+  variable: string;
+}
+
+/** Extra information returned with loaded data. */
+export interface LoadMetadata {
+  /** Keys set by environment variables (`Builder::env_overrides`). Saving never persists these values. */
+  envOverrides: EnvOverride[];
+  /** Dotpaths locked by the managed policy (`Builder::managed_policy`). */
+  lockedKeys: string[];
+}
+
+const EMPTY_LOAD_METADATA: LoadMetadata = { envOverrides: [], lockedKeys: [] };
+
 export type BatchRunEntryResult =
-  | { ok: true; data: unknown }
+  | { ok: true; data: unknown; metadata?: LoadMetadata }
   | { ok: false; error: { kind: string; message: string } };
 
 export interface BatchRunResult {
   results: Record<string, BatchRunEntryResult>;
 }
 
+/** Response of `load` when the payload sets `withMetadata`. */
+/** Counters of the Rust-side read cache (`Builder::cache`). */
+export interface CacheStats {
+  entries: number;
+  bytes: number;
+  maxEntries: number;
+  maxBytes: number;
+  hits: number;
+  misses: number;
+  invalidations: number;
+  evictions: number;
+}
+
+interface LoadResponse {
+  data: Record<string, unknown>;
+  metadata: LoadMetadata;
+}
+
 interface BatchConfigLike {
   _buildPayload(
     op: "create" | "load" | "save" | "patch" | "delete",
@@ -251,6 +418,7 @@ interface BatchConfigLike {
     keyringOpts: KeyringOptions | null,
     withUnlock: boolean,
     returnData?: boolean,
+    patchOptions?: PatchOptions,
   ): Record<string, unknown>;
   _attachFullReplaceKeyringDeletes(
     payload: Record<string, unknown>,
@@ -270,13 +438,20 @@ interface BatchConfigLike {
 
 export class LockedConfig<S extends SchemaObject> {
   readonly data: InferLocked<S>;
+  /** Keys set by environment variables. Empty unless returned by `load()`. */
+  readonly envOverrides: EnvOverride[];
+  /** Keys locked by the managed policy. Empty unless returned by `load()`. */
+  readonly lockedKeys: string[];
 
   /** @internal */
   constructor(
     data: InferLocked<S>,
     private readonly _configurate: Configurate<S>,
+    metadata: LoadMetadata = EMPTY_LOAD_METADATA,
   ) {
     this.data = data;
+    this.envOverrides = metadata.envOverrides;
+    this.lockedKeys = metadata.lockedKeys;
   }
 
   async unlock(opts: KeyringOptions): Promise<UnlockedConfig<S>> {
@@ -284,6 +459,7 @@ export class LockedConfig<S extends SchemaObject> {
     return this._configurate._unlockFromData(
       this.data as Record<string, unknown>,
       opts,
+      { envOverrides: this.envOverrides, lockedKeys: this.lockedKeys },
     );
   }
 }
@@ -299,10 +475,19 @@ export class PatchedConfig<S extends SchemaObject> {
 
 export class UnlockedConfig<S extends SchemaObject> {
   private _data: InferUnlocked<S> | null;
+  /** Keys set by environment variables. Empty unless returned by `load()`. */
+  readonly envOverrides: EnvOverride[];
+  /** Keys locked by the managed policy. Empty unless returned by `load()`. */
+  readonly lockedKeys: string[];
 
   /** @internal */
-  constructor(data: InferUnlocked<S>) {
+  constructor(
+    data: InferUnlocked<S>,
+    metadata: LoadMetadata = EMPTY_LOAD_METADATA,
+  ) {
     this._data = data;
+    this.envOverrides = metadata.envOverrides;
+    this.lockedKeys = metadata.lockedKeys;
   }
 
   get data(): InferUnlocked<S> {
@@ -334,7 +519,9 @@ export class LazyConfigEntry<S extends SchemaObject> {
   constructor(
     private readonly _configurate: Configurate<S>,
     private readonly _op: "create" | "load" | "save",
-    private readonly _data?: InferUnlocked<S>,
+    private readonly _data?: Partial<InferUnlocked<S>>,
+    private readonly _seedFrom?: SeedFrom,
+    private readonly _stream = false,
   ) {}
 
   lock(opts: KeyringOptions): this {
@@ -348,15 +535,58 @@ export class LazyConfigEntry<S extends SchemaObject> {
       this._op,
       this._data,
       this._keyringOpts,
+      this._seedFrom,
+      this._stream,
     );
   }
 
   unlock(opts: KeyringOptions): Promise<UnlockedConfig<S>> {
     validateKeyringOptions(opts);
-    return this._configurate._executeUnlock(this._op, this._data, opts);
+    return this._configurate._executeUnlock(
+      this._op,
+      this._data,
+      opts,
+      this._seedFrom,
+      this._stream,
+    );
   }
 }
 
+/**
+ * How `patch` applies its data: `deepMerge` (default) merges objects and
+ * stores `null` as a value, `mergePatch` (RFC 7396) deletes keys set to
+ * `null`, and `jsonPatch` (RFC 6902) applies an operation array.
+ */
+export type PatchFormat = "deepMerge" | "mergePatch" | "jsonPatch";
+
+/**
+ * How a `deepMerge` patch combines an array with the stored one. Arrays
+ * without a strategy are replaced.
+ */
+export type ArrayMergeStrategy =
+  | { strategy: "replace" }
+  | { strategy: "append" }
+  | { strategy: "unionBy"; key: string }
+  | { strategy: "byIndex" };
+
+export interface PatchOptions {
+  format?: PatchFormat;
+  /**
+   * Array strategies keyed by dotpath (`"plugins"`, `"servers.*.tags"`).
+   * Only valid with the `deepMerge` format.
+   */
+  arrayMerge?: Record<string, ArrayMergeStrategy>;
+}
+
+/** RFC 6902 JSON Patch operation. Paths are RFC 6901 JSON Pointers. */
+export type JsonPatchOperation =
+  | { op: "add"; path: string; value: unknown }
+  | { op: "remove"; path: string }
+  | { op: "replace"; path: string; value: unknown }
+  | { op: "move"; from: string; path: string }
+  | { op: "copy"; from: string; path: string }
+  | { op: "test"; path: string; value: unknown };
+
 export class LazyPatchEntry<S extends SchemaObject> {
   private _keyringOpts: KeyringOptions | null = null;
   private _createIfMissing = false;
@@ -364,7 +594,8 @@ export class LazyPatchEntry<S extends SchemaObject> {
   /** @internal */
   constructor(
     private readonly _configurate: Configurate<S>,
-    private readonly _data: Partial<InferUnlocked<S>>,
+    private readonly _data: Partial<InferUnlocked<S>> | JsonPatchOperation[],
+    private readonly _patchOptions: PatchOptions = {},
   ) {}
 
   lock(opts: KeyringOptions): this {
@@ -390,6 +621,7 @@ export class LazyPatchEntry<S extends SchemaObject> {
       this._data,
       this._keyringOpts,
       this._createIfMissing,
+      this._patchOptions,
     );
   }
 
@@ -399,6 +631,7 @@ export class LazyPatchEntry<S extends SchemaObject> {
       this._data,
       opts,
       this._createIfMissing,
+      this._patchOptions,
     );
   }
 }
@@ -552,7 +785,7 @@ class LoadAllBuilder {
             result.data,
             unlockOpts,
           );
-          batchResult.results[entry.id] = { ok: true, data: unlockedData };
+          batchResult.results[entry.id] = { ...result, data: unlockedData };
         } catch (error) {
           batchResult.results[entry.id] = {
             ok: false,
@@ -652,7 +885,10 @@ class SaveAllBuilder {
 export interface PatchAllEntry {
   id: string;
   config: BatchConfigLike;
+  /** Partial data, or an operation array when `format` is `"jsonPatch"`. */
   data: unknown;
+  format?: PatchFormat;
+  arrayMerge?: Record<string, ArrayMergeStrategy>;
 }
 
 export interface PatchAllRunner {
@@ -710,6 +946,7 @@ class PatchAllBuilder {
             lockOpts,
             false,
             false,
+            { format: entry.format, arrayMerge: entry.arrayMerge },
           ),
         });
       } catch (error) {
@@ -738,6 +975,104 @@ class PatchAllBuilder {
   }
 }
 
+// ---------------------------------------------------------------------------
+// Layered configs
+// ---------------------------------------------------------------------------
+
+export interface LayerEntry {
+  id: string;
+  config: BatchConfigLike;
+  /** Fail instead of skipping the layer when its file does not exist. */
+  required?: boolean;
+}
+
+export interface LayeredOptions {
+  /** Layers in priority order: later layers override earlier ones. */
+  layers: LayerEntry[];
+  /** Id of the layer `save()` writes to. */
+  writeLayer?: string;
+}
+
+export interface LayerStatus {
+  id: string;
+  /** `false` when an optional layer's file does not exist. */
+  loaded: boolean;
+}
+
+export interface LayeredResult<T = unknown> {
+  data: T;
+  /** Dotpath of every leaf value → id of the layer that supplied it. */
+  provenance: Record<string, string>;
+  layers: LayerStatus[];
+  writeLayer: string | null;
+  /** Dotpaths locked by the managed policy; their values come from the policy. */
+  lockedKeys: string[];
+}
+
+export class LayeredConfig<T = unknown> {
+  private readonly _opts: LayeredOptions;
+
+  constructor(opts: LayeredOptions) {
+    if (opts.layers.length === 0) {
+      throw new Error("Configurate.layered requires at least one layer.");
+    }
+    const ids = new Set<string>();
+    for (const layer of opts.layers) {
+      assertNonEmptyId(ids, layer.id);
+    }
+    if (opts.writeLayer !== undefined && !ids.has(opts.writeLayer)) {
+      throw new Error(`Unknown writeLayer '${opts.writeLayer}'.`);
+    }
+    this._opts = opts;
+  }
+
+  private _payload(data?: unknown): Record<string, unknown> {
+    const payload: Record<string, unknown> = {
+      layers: this._opts.layers.map((layer) => ({
+        id: layer.id,
+        source: layer.config._buildPayload("load", undefined, null, false),
+        required: layer.required === true,
+      })),
+      writeLayer: this._opts.writeLayer ?? null,
+    };
+    if (data !== undefined) {
+      payload.data = data;
+    }
+    return payload;
+  }
+
+  /** Loads and merges every layer. */
+  async load(): Promise<LayeredResult<T>> {
+    return invoke<LayeredResult<T>>("plugin:configurate|load_layered", {
+      payload: this._payload(),
+    });
+  }
+
+  /**
+   * Stores `data` (the full desired config) in the write layer, keeping only
+   * the values that differ from the layers below it.  Values that still
+   * equal those of the layers above it are not written.
+   */
+  async save(data: T): Promise<LayeredResult<T>> {
+    if (this._opts.writeLayer === undefined) {
+      throw new Error("LayeredConfig.save() requires a writeLayer.");
+    }
+    return invoke<LayeredResult<T>>("plugin:configurate|save_layered", {
+      payload: this._payload(data),
+    });
+  }
+}
+
+/** Deep copy of `data` without `null` object members (merge-patch deletions). */
+function withoutNulls(data: unknown): unknown {
+  if (!isPlainObject(data)) return data;
+  const out: Record<string, unknown> = {};
+  for (const [key, value] of Object.entries(data)) {
+    if (value !== null) out[key] = withoutNulls(value);
+  }
+  return out;
+}
+
 // ---------------------------------------------------------------------------
 // Configurate class
 // ---------------------------------------------------------------------------
@@ -747,6 +1082,8 @@ export interface ConfigChangeEvent {
   fileName: string;
   operation: string;
   targetId: string;
+  /** Dotpath changed by `setValue` / `removeValue` (`operation` "set" / "remove"). */
+  path?: string;
 }
 
 export class Configurate<S extends SchemaObject> {
@@ -779,6 +1116,15 @@ export class Configurate<S extends SchemaObject> {
     const p = this._opts.provider;
     if (p.kind === "binary") {
       const out: Record<string, unknown> = { kind: "binary", kdf: p.kdf };
+      if (p.reuseSalt) {
+        out.reuseSalt = true;
+      }
+      if (p.compression !== undefined) {
+        out.compression = p.compression;
+      }
+      if (p.encoding !== undefined) {
+        out.encoding = p.encoding;
+      }
       if (includeEncryptionKey && p.encryptionKey !== undefined) {
         out.encryptionKey = p.encryptionKey;
       }
@@ -787,6 +1133,45 @@ export class Configurate<S extends SchemaObject> {
     return { kind: p.kind };
   }
 
+  /**
+   * Adds `seedFrom` to a create / load payload. For `load`, `overlay` (plus
+   * the schema version) becomes the data merged over the template.
+   */
+  private _attachSeed(
+    payload: Record<string, unknown>,
+    seedFrom: SeedFrom,
+    overlay?: unknown,
+  ): void {
+    const seed: Record<string, unknown> = {
+      baseDir: seedFrom.baseDir as number,
+      fileName: seedFrom.fileName,
+    };
+    if (seedFrom.dirName !== undefined) {
+      seed.options = { dirName: seedFrom.dirName };
+    }
+    if (seedFrom.provider !== undefined) {
+      const p = seedFrom.provider;
+      seed.provider =
+        p.kind === "binary"
+          ? { kind: p.kind, encryptionKey: p.encryptionKey, kdf: p.kdf }
+          : { kind: p.kind };
+    }
+    payload.seedFrom = seed;
+
+    if (payload.data !== undefined) return;
+    let data: Record<string, unknown> = {};
+    if (overlay !== undefined) {
+      this._validatePatchData(overlay);
+      data = this._hasKeyringFields
+        ? separateSecrets(this._schema, overlay as Record<string, unknown>).plain
+        : (overlay as Record<string, unknown>);
+    }
+    if (this._opts.version !== undefined) {
+      data = { ...data, [CONFIGURATE_VERSION_KEY]: this._opts.version };
+    }
+    payload.data = data;
+  }
+
   /** Builds common base fields shared by all payloads. */
   private _buildBasePayload(options?: { includeEncryptionKey?: boolean }): Record<string, unknown> {
     const includeEncryptionKey = options?.includeEncryptionKey ?? false;
@@ -819,12 +1204,40 @@ export class Configurate<S extends SchemaObject> {
     return new PatchAllBuilder(entries);
   }
 
-  create(data: InferUnlocked<S>): LazyConfigEntry<S> {
-    return new LazyConfigEntry(this, "create", data);
+  static layered<T = unknown>(opts: LayeredOptions): LayeredConfig<T> {
+    return new LayeredConfig<T>(opts);
+  }
+
+  /**
+   * Read cache counters for debugging, or `null` when the cache is not
+   * enabled with `Builder::cache`. Requires `configurate:allow-cache-stats`.
+   */
+  static cacheStats(): Promise<CacheStats | null> {
+    return invoke<CacheStats | null>("plugin:configurate|cache_stats");
   }
 
-  load(): LazyConfigEntry<S> {
-    return new LazyConfigEntry(this, "load");
+  /**
+   * Writes `data` as the whole config. With `seedFrom`, a missing config is
+   * created from the template with `data` merged over it, and an existing
+   * config is left untouched and returned as stored.
+   */
+  create(data: InferUnlocked<S>, opts?: CreateOptions): LazyConfigEntry<S> {
+    return new LazyConfigEntry(this, "create", data, opts?.seedFrom);
+  }
+
+  /**
+   * Loads the config. With `seedFrom`, a missing config is first created
+   * from the template (plus `data`) in one locked step, so concurrent
+   * windows seed it exactly once.
+   */
+  load(opts?: LoadOptions<S>): LazyConfigEntry<S> {
+    return new LazyConfigEntry(
+      this,
+      "load",
+      opts?.data,
+      opts?.seedFrom,
+      opts?.stream ?? false,
+    );
   }
 
   save(data: InferUnlocked<S>): LazyConfigEntry<S> {
@@ -847,6 +1260,117 @@ export class Configurate<S extends SchemaObject> {
     return invoke<string[]>("plugin:configurate|list_configs", { payload });
   }
 
+  /**
+   * Lists keyring ids the plugin has written for this config (from the
+   * keyring manifest stored beside the file) and whether each still exists.
+   * Pass `opts` to restrict the listing to one service/account.
+   */
+  async keyringList(opts?: KeyringOptions | null): Promise<KeyringListEntry[]> {
+    const payload = this._buildPayload("exists", undefined, null, false, false);
+    if (opts) {
+      validateKeyringOptions(opts);
+      payload.keyringOptions = opts;
+    }
+    return invoke<KeyringListEntry[]>("plugin:configurate|keyring_list", {
+      payload,
+    });
+  }
+
+  /**
+   * Deletes keyring entries tracked for this config whose id is no longer
+   * declared in the schema. Pass `opts` to restrict collection to one
+   * service/account, and `{ dryRun: true }` to only report what would go.
+   * When the schema declares no keyring ids, deleting every tracked secret
+   * must be confirmed with `{ all: true }`.
+   */
+  async keyringGc(
+    opts?: KeyringOptions | null,
+    gcOpts?: { dryRun?: boolean; all?: boolean },
+  ): Promise<KeyringGcResult> {
+    const target = this._buildPayload("exists", undefined, null, false, false);
+    if (opts) {
+      validateKeyringOptions(opts);
+      target.keyringOptions = opts;
+    }
+    const keepIds = new Set(this._keyringPaths.map(({ id }) => id));
+    // Id templates keep every id Rust rendered from them.
+    for (const id of collectKeyringIds(this._schema)) {
+      if (isKeyringIdTemplate(id)) keepIds.add(id);
+    }
+    if (this._hasArrayKeyring) {
+      const plainData = await this._loadExistingPlainData();
+      if (plainData !== null) {
+        for (const entry of collectKeyringReadEntries(this._schema, plainData)) {
+          keepIds.add(entry.id);
+        }
+      }
+    }
+    return invoke<KeyringGcResult>("plugin:configurate|keyring_gc", {
+      payload: {
+        target,
+        keepIds: [...keepIds],
+        all: gcOpts?.all ?? false,
+        dryRun: gcOpts?.dryRun ?? false,
+      },
+    });
+  }
+
+  /**
+   * Reads one value by dotpath (`"db.host"`, `'plugins["a.b"]'`,
+   * `"/db/host"`) without loading the whole config over IPC. The value is
+   * the one `load()` would return, with environment overrides and managed
+   * policy values applied. Resolves to `null` when the path does not exist.
+   * Keyring fields cannot be read here;
+   * use `load().unlock(opts)`.
+   */
+  async getValue<T = unknown>(dotpath: string): Promise<T | null> {
+    this._assertPlainDotpath("getValue", dotpath);
+    return invoke<T | null>("plugin:configurate|get_value", {
+      payload: { target: this._valueTarget(), dotpath },
+    });
+  }
+
+  /**
+   * Writes one value by dotpath under the file lock, creating intermediate
+   * objects/arrays. Fails when the config does not exist unless
+   * `createIfMissing` is set. Emits a change event with `path`.
+   */
+  async setValue(
+    dotpath: string,
+    value: unknown,
+    opts?: { createIfMissing?: boolean },
+  ): Promise<void> {
+    this._assertPlainDotpath("setValue", dotpath);
+    const target = this._valueTarget();
+    if (opts?.createIfMissing) target.createIfMissing = true;
+    await invoke("plugin:configurate|set_value", {
+      payload: { target, dotpath, value: value === undefined ? null : value },
+    });
+  }
+
+  /**
+   * Removes one value by dotpath. Resolves to `false` (and writes nothing)
+   * when the path does not exist.
+   */
+  async removeValue(dotpath: string): Promise<boolean> {
+    this._assertPlainDotpath("removeValue", dotpath);
+    return invoke<boolean>("plugin:configurate|remove_value", {
+      payload: { target: this._valueTarget(), dotpath },
+    });
+  }
+
+  private _valueTarget(): Record<string, unknown> {
+    return this._buildPayload("patch", undefined, null, false, false);
+  }
+
+  private _assertPlainDotpath(method: string, dotpath: string): void {
+    if (this._hasKeyringFields && dotpathTouchesKeyring(this._schema, dotpath)) {
+      throw new Error(
+        `Configurate: ${method}("${dotpath}") touches a keyring field — use load().unlock(opts) or patch().lock(opts) instead.`,
+      );
+    }
+  }
+
   /**
    * Resets the config by deleting existing data and re-creating it with
    * the provided default data.
@@ -858,19 +1382,22 @@ export class Configurate<S extends SchemaObject> {
   /**
    * Exports the config data as a string in the specified format.
    *
-   * @param format - Target format: "json", "yml", or "toml"
+   * @param format - Target format: "json", "yml", "toml", "ini", "dotenv", "xml", "plist", or base64 "cbor" / "msgpack"
+   * @param streamOpts - Stream the config and the exported text in chunks
    * @returns The serialized config string
    */
   async exportAs(
-    format: "json" | "yml" | "toml",
+    format: ExchangeFormat,
     opts?: KeyringOptions | null,
+    streamOpts?: ExportStreamOptions,
   ): Promise<string> {
     if (opts) {
       validateKeyringOptions(opts);
     }
+    const stream = streamOpts?.stream ?? false;
     const loaded = opts
-      ? await this.load().unlock(opts)
-      : await this.load().run();
+      ? await this.load({ stream }).unlock(opts)
+      : await this.load({ stream }).run();
     const payload = {
       source: {
         ...this._buildBasePayload({ includeEncryptionKey: false }),
@@ -880,6 +1407,13 @@ export class Configurate<S extends SchemaObject> {
       },
       targetFormat: format,
     };
+    if (stream) {
+      return invokeStreamed(
+        "plugin:configurate|export_config",
+        { payload },
+        streamOpts?.onChunk,
+      );
+    }
     return invoke<string>("plugin:configurate|export_config", { payload });
   }
 
@@ -887,22 +1421,29 @@ export class Configurate<S extends SchemaObject> {
    * Imports config data from a string in the specified format, replacing
    * the current stored config.
    *
-   * @param content - The serialized config string
-   * @param format  - Source format: "json", "yml", or "toml"
+   * Large strings and iterables are uploaded in chunks, and the upload is
+   * rejected as soon as it exceeds the plugin's `maxReadBytes`.
+   *
+   * @param content - The serialized config string, or its pieces in order
+   * @param format  - Source format: "json", "yml", "toml", "ini", "dotenv", "xml", "plist", or base64 "cbor" / "msgpack"
    */
   async importFrom(
-    content: string,
-    format: "json" | "yml" | "toml",
+    content: ImportContent,
+    format: ExchangeFormat,
     opts?: KeyringOptions | null,
   ): Promise<void> {
     if (opts) {
       validateKeyringOptions(opts);
     }
+    const source =
+      typeof content === "string" && content.length <= IMPORT_CHUNK_SIZE
+        ? { content }
+        : { uploadId: await this._uploadImport(content) };
     const parsed = await invoke<unknown>("plugin:configurate|import_config", {
       payload: {
         target: this._buildLocationPayload(),
         sourceFormat: format,
-        content,
+        ...source,
         parseOnly: true,
       },
     });
@@ -954,10 +1495,24 @@ export class Configurate<S extends SchemaObject> {
    * stored data.  Only provided keys are updated; omitted keys are left
    * unchanged.
    *
-   * **Null semantics (JSON Merge Patch — RFC 7396)**
-   * Setting a key to `null` in `partial` overwrites the stored value with
-   * `null`.  To leave a value unchanged, omit the key entirely.  This differs
-   * from a full `save()`, which always replaces all keys.
+   * **Null semantics**
+   * By default (`format: "deepMerge"`) setting a key to `null` in `partial`
+   * overwrites the stored value with `null`.  With `format: "mergePatch"`
+   * (JSON Merge Patch — RFC 7396) a `null` deletes the key instead.  To leave
+   * a value unchanged, omit the key entirely.  This differs from a full
+   * `save()`, which always replaces all keys.
+   *
+   * **Arrays**
+   * Arrays are replaced wholesale unless `arrayMerge` names a strategy for
+   * their dotpath:
+   * ```ts
+   * await config
+   *   .patch(
+   *     { plugins: [{ id: "lint", enabled: false }] },
+   *     { arrayMerge: { plugins: { strategy: "unionBy", key: "id" } } },
+   *   )
+   *   .run();
+   * ```
    *
    * **Error behaviour**
    * By default, patching a config that does not yet exist throws an error.
@@ -966,8 +1521,31 @@ export class Configurate<S extends SchemaObject> {
    * await config.patch({ theme: "dark" }).createIfMissing().run();
    * ```
    */
-  patch(partial: Partial<InferUnlocked<S>>): LazyPatchEntry<S> {
-    return new LazyPatchEntry(this, partial);
+  patch(
+    partial: Partial<InferUnlocked<S>>,
+    opts?: PatchOptions & { format?: "deepMerge" | "mergePatch" },
+  ): LazyPatchEntry<S> {
+    return new LazyPatchEntry(this, partial, opts ?? {});
+  }
+
+  /**
+   * Applies an RFC 6902 JSON Patch to the stored config. Operations run in
+   * order and all-or-nothing; a failing op (including `test`) rejects with
+   * `kind: "patch"` and a message naming the op index. Operations may not
+   * touch keyring fields.
+   *
+   * ```ts
+   * await config
+   *   .jsonPatch([
+   *     { op: "test", path: "/version", value: 2 },
+   *     { op: "add", path: "/plugins/-", value: { id: "new" } },
+   *     { op: "remove", path: "/legacy" },
+   *   ])
+   *   .run();
+   * ```
+   */
+  jsonPatch(ops: JsonPatchOperation[]): LazyPatchEntry<S> {
+    return new LazyPatchEntry(this, ops, { format: "jsonPatch" });
   }
 
   /**
@@ -1047,7 +1625,7 @@ export class Configurate<S extends SchemaObject> {
         ({ id, dotpath, isOptional }) => ({
           id,
           dotpath,
-          value: "",
+          value: null,
           ...(isOptional ? { isOptional: true } : {}),
         }),
       );
@@ -1189,29 +1767,63 @@ export class Configurate<S extends SchemaObject> {
     await invoke("plugin:configurate|save", { payload });
   }
 
+  /** Uploads import content through `import_chunk` and returns its id. */
+  private async _uploadImport(content: ImportContent): Promise<string> {
+    let uploadId: string | undefined;
+    for await (const chunk of importChunks(content)) {
+      uploadId = await invoke<string>("plugin:configurate|import_chunk", {
+        payload: { uploadId, chunk },
+      });
+    }
+    return uploadId as string;
+  }
+
+  /** Invokes `load` for a `withMetadata` payload, optionally streamed. */
+  private async _invokeLoad(
+    payload: Record<string, unknown>,
+    stream: boolean,
+  ): Promise<LoadResponse> {
+    if (!stream) {
+      return invoke<LoadResponse>("plugin:configurate|load", { payload });
+    }
+    const text = await invokeStreamed("plugin:configurate|load", { payload });
+    return JSON.parse(text) as LoadResponse;
+  }
+
   /** @internal */
   async _executeLocked(
     op: "create" | "load" | "save",
-    data: InferUnlocked<S> | undefined,
+    data: Partial<InferUnlocked<S>> | undefined,
     keyringOpts: KeyringOptions | null,
+    seedFrom?: SeedFrom,
+    stream = false,
   ): Promise<LockedConfig<S>> {
     if (op === "load") {
       const payload = this._buildPayload(op, data, keyringOpts, false);
-      const rawResult = await invoke<Record<string, unknown>>(
-        `plugin:configurate|${op}`,
-        {
-          payload,
-        },
-      );
+      payload.withMetadata = true;
+      if (seedFrom !== undefined) this._attachSeed(payload, seedFrom, data);
+      const loaded = await this._invokeLoad(payload, stream);
       const result = (await this._postProcessLoadedData(
-        rawResult,
+        loaded.data,
       )) as Record<string, unknown>;
       this._validateLoadedData(result);
-      return new LockedConfig(result as InferLocked<S>, this);
+      return new LockedConfig(
+        result as InferLocked<S>,
+        this,
+        loaded.metadata,
+      );
     }
 
     const payload = this._buildPayload(op, data, keyringOpts, false, true);
     await this._attachFullReplaceKeyringDeletes(payload, keyringOpts);
+    if (seedFrom !== undefined) {
+      this._attachSeed(payload, seedFrom);
+      // The stored data is the template plus `data`, or the existing config.
+      const seeded = await invoke<InferLocked<S>>(`plugin:configurate|${op}`, {
+        payload,
+      });
+      return new LockedConfig(seeded, this);
+    }
     await invoke(`plugin:configurate|${op}`, {
       payload,
     });
@@ -1223,25 +1835,29 @@ export class Configurate<S extends SchemaObject> {
   /** @internal */
   async _executeUnlock(
     op: "create" | "load" | "save",
-    data: InferUnlocked<S> | undefined,
+    data: Partial<InferUnlocked<S>> | undefined,
     keyringOpts: KeyringOptions,
+    seedFrom?: SeedFrom,
+    stream = false,
   ): Promise<UnlockedConfig<S>> {
     if (op === "load") {
       const payload = this._buildPayload("load", data, null, false);
-      const rawPlain = await invoke<Record<string, unknown>>(
-        "plugin:configurate|load",
-        {
-          payload,
-        },
-      );
+      payload.withMetadata = true;
+      if (seedFrom !== undefined) this._attachSeed(payload, seedFrom, data);
+      const loaded = await this._invokeLoad(payload, stream);
       const result = (await this._postProcessLoadedData(
-        rawPlain,
+        loaded.data,
       )) as Record<string, unknown>;
-      return this._unlockFromData(result, keyringOpts);
+      return this._unlockFromData(
+        result,
+        keyringOpts,
+        loaded.metadata,
+      );
     }
 
     const payload = this._buildPayload(op, data, keyringOpts, true);
     await this._attachFullReplaceKeyringDeletes(payload, keyringOpts);
+    if (seedFrom !== undefined) this._attachSeed(payload, seedFrom);
     const result = await invoke<InferUnlocked<S>>(`plugin:configurate|${op}`, {
       payload,
     });
@@ -1250,9 +1866,10 @@ export class Configurate<S extends SchemaObject> {
 
   /** @internal */
   async _executePatchLocked(
-    data: Partial<InferUnlocked<S>>,
+    data: Partial<InferUnlocked<S>> | JsonPatchOperation[],
     keyringOpts: KeyringOptions | null,
     createIfMissing = false,
+    patchOptions: PatchOptions = {},
   ): Promise<PatchedConfig<S>> {
     const payload = this._buildPayload(
       "patch",
@@ -1260,20 +1877,31 @@ export class Configurate<S extends SchemaObject> {
       keyringOpts,
       false,
       false,
+      patchOptions,
     );
     if (createIfMissing) payload.createIfMissing = true;
     await invoke("plugin:configurate|patch", { payload });
-    const plain = (payload.data ?? {}) as Partial<InferLocked<S>>;
+    const plain = (
+      patchOptions.format === "jsonPatch" ? {} : (payload.data ?? {})
+    ) as Partial<InferLocked<S>>;
     return new PatchedConfig(plain);
   }
 
   /** @internal */
   async _executePatchUnlock(
-    data: Partial<InferUnlocked<S>>,
+    data: Partial<InferUnlocked<S>> | JsonPatchOperation[],
     keyringOpts: KeyringOptions,
     createIfMissing = false,
+    patchOptions: PatchOptions = {},
   ): Promise<UnlockedConfig<S>> {
-    const payload = this._buildPayload("patch", data, keyringOpts, true);
+    const payload = this._buildPayload(
+      "patch",
+      data,
+      keyringOpts,
+      true,
+      true,
+      patchOptions,
+    );
     if (createIfMissing) payload.createIfMissing = true;
     const result = await invoke<InferUnlocked<S>>("plugin:configurate|patch", {
       payload,
@@ -1317,6 +1945,23 @@ export class Configurate<S extends SchemaObject> {
     );
   }
 
+  private _assertJsonPatchAvoidsKeyring(ops: unknown): void {
+    if (!Array.isArray(ops)) {
+      throw new Error("Configurate: jsonPatch expects an array of operations.");
+    }
+    if (!this._hasKeyringFields) return;
+    for (const op of ops as Array<{ path?: unknown; from?: unknown }>) {
+      for (const path of [op.path, op.from]) {
+        if (typeof path !== "string") continue;
+        if (path === "" || dotpathTouchesKeyring(this._schema, path)) {
+          throw new Error(
+            `Configurate: jsonPatch path "${path}" touches a keyring field — use patch().lock(opts) instead.`,
+          );
+        }
+      }
+    }
+  }
+
   private _validatePatchData(data: unknown): void {
     if (!this._opts.validation.validateOnWrite) {
       return;
@@ -1332,20 +1977,22 @@ export class Configurate<S extends SchemaObject> {
   async _unlockFromData(
     plainData: Record<string, unknown>,
     opts: KeyringOptions,
+    metadata: LoadMetadata = EMPTY_LOAD_METADATA,
   ): Promise<UnlockedConfig<S>> {
     if (!this._hasKeyringFields) {
       this._validateLoadedData(plainData);
-      return new UnlockedConfig(plainData as InferUnlocked<S>);
+      return new UnlockedConfig(plainData as InferUnlocked<S>, metadata);
     }
 
     const keyringEntries = collectKeyringReadEntries(this._schema, plainData);
 
     if (keyringEntries.length === 0) {
       this._validateLoadedData(plainData);
-      return new UnlockedConfig(plainData as InferUnlocked<S>);
+      return new UnlockedConfig(plainData as InferUnlocked<S>, metadata);
     }
 
     const payload = {
+      target: this._buildPayload("exists", undefined, null, false, false),
       data: plainData,
       keyringEntries,
       keyringOptions: opts,
@@ -1355,7 +2002,7 @@ export class Configurate<S extends SchemaObject> {
       payload,
     });
     this._validateLoadedData(result);
-    return new UnlockedConfig(result);
+    return new UnlockedConfig(result, metadata);
   }
 
   /** @internal */
@@ -1391,7 +2038,9 @@ export class Configurate<S extends SchemaObject> {
     keyringOpts: KeyringOptions | null,
     withUnlock: boolean,
     returnData = true,
+    patchOptions: PatchOptions = {},
   ): Record<string, unknown> {
+    const patchFormat = patchOptions.format ?? "deepMerge";
     const base: Record<string, unknown> = {
       ...this._buildBasePayload({
         includeEncryptionKey:
@@ -1415,7 +2064,7 @@ export class Configurate<S extends SchemaObject> {
           ({ id, dotpath, isOptional }) => ({
             id,
             dotpath,
-            value: "",
+            value: null,
             ...(isOptional ? { isOptional: true } : {}),
           }),
         );
@@ -1424,9 +2073,30 @@ export class Configurate<S extends SchemaObject> {
       return base;
     }
 
+    if (op === "patch" && patchFormat !== "deepMerge") {
+      base.patchFormat = patchFormat;
+    }
+    if (op === "patch" && patchOptions.arrayMerge !== undefined) {
+      if (patchFormat !== "deepMerge") {
+        throw new Error(
+          "Configurate: arrayMerge is only supported with the deepMerge patch format.",
+        );
+      }
+      base.arrayMerge = patchOptions.arrayMerge;
+    }
+
+    if (op === "patch" && patchFormat === "jsonPatch") {
+      this._assertJsonPatchAvoidsKeyring(data);
+      base.data = data;
+      return base;
+    }
+
     if (data !== undefined) {
       if (op === "patch") {
-        this._validatePatchData(data);
+        // Merge-patch nulls are deletions, not values to validate.
+        this._validatePatchData(
+          patchFormat === "mergePatch" ? withoutNulls(data) : data,
+        );
       } else {
         this._validateWriteData(data);
       }
@@ -1455,6 +2125,16 @@ export class Configurate<S extends SchemaObject> {
               "Configurate: schema contains keyring fields — use .lock(opts) before .run(), or .unlock(opts), for create/save operations.",
             );
           }
+          if (
+            base.arrayMerge !== undefined &&
+            keyringEntries.some(({ dotpath }) => /(^|\.)\d+(\.|$)/.test(dotpath))
+          ) {
+            // Indices come from the patch array and would not match the
+            // merged array; templated ids are resolved by Rust instead.
+            throw new Error(
+              "Configurate: arrayMerge cannot be combined with keyring fields inside arrays unless their ids are templates (e.g. {field:id}).",
+            );
+          }
           base.keyringEntries = keyringEntries;
           base.keyringOptions = keyringOpts;
         } else if (keyringOpts === null && op !== "patch") {
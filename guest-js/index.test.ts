@@ -3,13 +3,51 @@ import { afterEach, describe, expect, it, mock } from "bun:test";
 type InvokeArgs = Record<string, unknown> | undefined;
 type InvokeHandler = (command: string, args: InvokeArgs) => Promise<unknown>;
 
-async function loadApi(invokeHandler: InvokeHandler) {
-  const invokeMock = mock((command: string, args?: Record<string, unknown>) => {
-    return invokeHandler(command, args);
+/**
+ * Mirrors the backend contract for `load` payloads with `withMetadata`: bare
+ * mock results are wrapped as `{ data, metadata }` so handlers only need to
+ * return the wrapped shape when a test cares about the metadata.
+ */
+function wrapLoadResult(command: string, args: InvokeArgs, result: unknown) {
+  const payload = args?.payload as Record<string, unknown> | undefined;
+  if (command !== "plugin:configurate|load" || payload?.withMetadata !== true) {
+    return result;
+  }
+  if (
+    result !== null &&
+    typeof result === "object" &&
+    "data" in result &&
+    "metadata" in result
+  ) {
+    return result;
+  }
+  return { data: result, metadata: { envOverrides: [], lockedKeys: [] } };
+}
+
+/** Stands in for the Tauri channel; handlers call `onmessage` directly. */
+class ChannelMock<T> {
+  onmessage: (message: T) => void = () => {};
+}
+
+/** Sends `parts` through the channel passed to a streamed command. */
+function streamThrough(args: InvokeArgs, parts: string[]) {
+  const channel = args?.channel as ChannelMock<unknown>;
+  parts.forEach((data, index) => {
+    channel.onmessage({ index, data, last: index === parts.length - 1 });
   });
+  return null;
+}
+
+async function loadApi(invokeHandler: InvokeHandler) {
+  const invokeMock = mock(
+    async (command: string, args?: Record<string, unknown>) => {
+      const result = await invokeHandler(command, args);
+      return wrapLoadResult(command, args, result);
+    },
+  );
 
   mock.module("@tauri-apps/api/core", () => {
-    return { invoke: invokeMock };
+    return { invoke: invokeMock, Channel: ChannelMock };
   });
 
   mock.module("@tauri-apps/api/event", () => ({
@@ -351,6 +389,110 @@ describe("Provider creation", () => {
     expect(provider.kdf).toBe("argon2");
   });
 
+  it("BinaryProvider should forward reuseSalt", async () => {
+    const { Configurate, BinaryProvider, defineConfig, invokeMock } =
+      await loadApi(async () => null);
+    const config = new Configurate({
+      schema: defineConfig({ theme: String }),
+      fileName: "vault.bin",
+      baseDir: 13 as never,
+      provider: BinaryProvider({ encryptionKey: "pw", kdf: "argon2", reuseSalt: true }),
+    });
+
+    await config.save({ theme: "dark" } as never).run();
+
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    expect((args.payload as Record<string, unknown>).provider).toEqual({
+      kind: "binary",
+      kdf: "argon2",
+      encryptionKey: "pw",
+      reuseSalt: true,
+    });
+  });
+
+  it("BinaryProvider should forward compression", async () => {
+    const { Configurate, BinaryProvider, defineConfig, invokeMock } =
+      await loadApi(async () => null);
+    const config = new Configurate({
+      schema: defineConfig({ theme: String }),
+      fileName: "app.bin",
+      baseDir: 13 as never,
+      provider: BinaryProvider({ compression: "deflate" }),
+    });
+
+    await config.save({ theme: "dark" } as never).run();
+
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    expect((args.payload as Record<string, unknown>).provider).toMatchObject({
+      kind: "binary",
+      compression: "deflate",
+    });
+  });
+
+  it("BinaryProvider should forward encoding and reject unknown encodings", async () => {
+    const { Configurate, BinaryProvider, defineConfig, isProvider, invokeMock } =
+      await loadApi(async () => null);
+    const config = new Configurate({
+      schema: defineConfig({ theme: String }),
+      fileName: "app.bin",
+      baseDir: 13 as never,
+      provider: BinaryProvider({ encoding: "cbor" }),
+    });
+
+    await config.save({ theme: "dark" } as never).run();
+
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    expect((args.payload as Record<string, unknown>).provider).toMatchObject({
+      kind: "binary",
+      encoding: "cbor",
+    });
+    expect(
+      isProvider({ ...BinaryProvider(), encoding: "bson" }),
+    ).toBe(false);
+  });
+
+  it("IniProvider, DotenvProvider, XmlProvider and PlistProvider should send their kind", async () => {
+    const {
+      Configurate,
+      IniProvider,
+      DotenvProvider,
+      XmlProvider,
+      PlistProvider,
+      defineConfig,
+      isProvider,
+      invokeMock,
+    } = await loadApi(async () => null);
+    for (const [provider, kind, fileName] of [
+      [IniProvider(), "ini", "tool.ini"],
+      [DotenvProvider(), "dotenv", ".env"],
+      [XmlProvider(), "xml", "tool.xml"],
+      [PlistProvider(), "plist", "com.example.app.plist"],
+    ] as const) {
+      expect(isProvider(provider)).toBe(true);
+      const config = new Configurate({
+        schema: defineConfig({ PORT: Number }),
+        fileName,
+        baseDir: 13 as never,
+        provider,
+      });
+      await config.save({ PORT: 8080 } as never).run();
+      const [, args] = invokeMock.mock.calls.at(-1) as [
+        string,
+        Record<string, unknown>,
+      ];
+      expect((args.payload as Record<string, unknown>).provider).toEqual({ kind });
+    }
+  });
+
   it("BinaryProvider without options should have undefined fields", async () => {
     const { BinaryProvider } = await loadApi(async () => null);
     const provider = BinaryProvider();
@@ -424,6 +566,83 @@ describe("Single config operations", () => {
     expect(locked.data).toEqual({ theme: "light", count: 10 });
   });
 
+  it("create() with seedFrom should send the template and return the stored data", async () => {
+    const { Configurate, JsonProvider, TomlProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|create")
+          return { theme: "dark", count: 3 };
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({ theme: String, count: Number });
+    const config = new Configurate({
+      schema,
+      fileName: "app.toml",
+      baseDir: 13 as never,
+      provider: TomlProvider(),
+    });
+
+    const locked = await config
+      .create({ theme: "dark" } as never, {
+        seedFrom: {
+          baseDir: 11 as never,
+          fileName: "defaults.json",
+          provider: JsonProvider(),
+        },
+      })
+      .run();
+
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    const payload = args.payload as Record<string, unknown>;
+    expect(payload.seedFrom).toEqual({
+      baseDir: 11,
+      fileName: "defaults.json",
+      provider: { kind: "json" },
+    });
+    expect(payload.data).toEqual({ theme: "dark" });
+    expect(locked.data).toEqual({ theme: "dark", count: 3 });
+  });
+
+  it("load() with seedFrom should send the template and overlay data", async () => {
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|load")
+          return { theme: "light", count: 1 };
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({ theme: String, count: Number });
+    const config = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const locked = await config
+      .load({
+        seedFrom: { baseDir: 11 as never, fileName: "defaults.json", dirName: "templates" },
+        data: { count: 1 } as never,
+      })
+      .run();
+
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    const payload = args.payload as Record<string, unknown>;
+    expect(payload.seedFrom).toEqual({
+      baseDir: 11,
+      fileName: "defaults.json",
+      options: { dirName: "templates" },
+    });
+    expect(payload.data).toEqual({ count: 1 });
+    expect(locked.data).toEqual({ theme: "light", count: 1 });
+  });
+
   it("save().run() should invoke plugin:configurate|save", async () => {
     const { Configurate, JsonProvider, defineConfig, invokeMock } =
       await loadApi(async (command) => {
@@ -587,13 +806,13 @@ describe("Single config operations", () => {
           expect((target.data as Record<string, unknown>).token).toBeNull();
           const entries = target.keyringEntries as Array<{
             id: string;
-            value: string;
+            value: unknown;
           }>;
           expect(entries).toHaveLength(1);
           expect(entries[0]).toMatchObject({
             id: "tok",
             dotpath: "token",
-            value: '"secret"',
+            value: "secret",
           });
           const opts = target.keyringOptions as Record<string, unknown>;
           expect(opts.service).toBe("svc");
@@ -623,6 +842,102 @@ describe("Single config operations", () => {
     });
     expect(invokeMock).toHaveBeenCalledTimes(3);
   });
+
+  it("load({ stream: true }) should reassemble the streamed response", async () => {
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command, args) => {
+        if (command === "plugin:configurate|load") {
+          const text = JSON.stringify({
+            data: { theme: "dark", count: 3 },
+            metadata: { envOverrides: [], lockedKeys: [] },
+          });
+          return streamThrough(args, [text.slice(0, 10), text.slice(10)]);
+        }
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({ theme: String, count: Number });
+    const config = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const loaded = await config.load({ stream: true }).run();
+    expect(loaded.data).toEqual({ theme: "dark", count: 3 });
+    const [, args] = invokeMock.mock.calls[0] as [string, Record<string, unknown>];
+    expect(args.channel).toBeInstanceOf(ChannelMock);
+  });
+
+  it("exportAs should stream the exported text when asked to", async () => {
+    const { Configurate, JsonProvider, defineConfig } = await loadApi(
+      async (command, args) => {
+        if (command === "plugin:configurate|load") {
+          return { theme: "dark" };
+        }
+        if (command === "plugin:configurate|export_config") {
+          return streamThrough(args, ["theme = ", '"dark"\n']);
+        }
+        throw new Error(`unexpected command: ${command}`);
+      },
+    );
+
+    const schema = defineConfig({ theme: String });
+    const config = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const chunks: number[] = [];
+    const result = await config.exportAs("toml", null, {
+      stream: true,
+      onChunk: (chunk: { index: number }) => chunks.push(chunk.index),
+    });
+    expect(result).toBe('theme = "dark"\n');
+    expect(chunks).toEqual([0, 1]);
+  });
+
+  it("importFrom should upload iterable content in chunks", async () => {
+    const uploaded: string[] = [];
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command, args) => {
+        const payload = (args?.payload ?? {}) as Record<string, unknown>;
+        if (command === "plugin:configurate|import_chunk") {
+          expect(payload.uploadId).toBe(uploaded.length ? "up-1" : undefined);
+          uploaded.push(payload.chunk as string);
+          return "up-1";
+        }
+        if (command === "plugin:configurate|import_config") {
+          if (payload.parseOnly) {
+            expect(payload.uploadId).toBe("up-1");
+            expect(payload.content).toBeUndefined();
+            return JSON.parse(uploaded.join(""));
+          }
+          expect(payload.target).toMatchObject({ data: { theme: "dark" } });
+          return null;
+        }
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({ theme: String });
+    const config = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    async function* pieces() {
+      yield '{"theme":';
+      yield '"dark"}';
+    }
+    await config.importFrom(pieces(), "json");
+    expect(uploaded.join("")).toBe('{"theme":"dark"}');
+    expect(invokeMock).toHaveBeenCalledTimes(3);
+  });
 });
 
 // ---------------------------------------------------------------------------
@@ -692,6 +1007,94 @@ describe("Patch operation", () => {
     expect(unlocked.data).toEqual({ theme: "dark", count: 5 });
   });
 
+  it("should send patchFormat for mergePatch and jsonPatch", async () => {
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|patch") return null;
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const config = new Configurate({
+      schema: defineConfig({ theme: String, count: Number }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    // null is a deletion in merge patch, so it passes schema validation.
+    await config
+      .patch({ theme: null } as never, { format: "mergePatch" })
+      .run();
+    const ops = [{ op: "replace" as const, path: "/count", value: 2 }];
+    const patched = await config.jsonPatch(ops).run();
+    expect(patched.data).toEqual({});
+
+    const payloads = invokeMock.mock.calls.map(
+      ([, args]) => (args as Record<string, unknown>).payload as Record<string, unknown>,
+    );
+    expect(payloads[0]?.patchFormat).toBe("mergePatch");
+    expect(payloads[0]?.data).toEqual({ theme: null });
+    expect(payloads[1]?.patchFormat).toBe("jsonPatch");
+    expect(payloads[1]?.data).toEqual(ops);
+  });
+
+  it("should send arrayMerge strategies with deepMerge patches", async () => {
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|patch") return null;
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const config = new Configurate({
+      schema: defineConfig({ plugins: [{ id: String, enabled: Boolean }] }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const arrayMerge = { plugins: { strategy: "unionBy" as const, key: "id" } };
+    await config
+      .patch({ plugins: [{ id: "lint", enabled: false }] } as never, {
+        arrayMerge,
+      })
+      .run();
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    const payload = args.payload as Record<string, unknown>;
+    expect(payload.arrayMerge).toEqual(arrayMerge);
+    expect(payload.patchFormat).toBeUndefined();
+
+    await expect(
+      config
+        .patch({} as never, { format: "mergePatch", arrayMerge })
+        .run(),
+    ).rejects.toThrow("deepMerge");
+  });
+
+  it("jsonPatch() should reject ops touching keyring fields", async () => {
+    const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
+      await loadApi(async (command) => {
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const config = new Configurate({
+      schema: defineConfig({
+        name: String,
+        token: keyring(String, { id: "tok" }),
+      }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    await expect(
+      config.jsonPatch([{ op: "copy", from: "/token", path: "/name" }]).run(),
+    ).rejects.toThrow("keyring");
+    expect(invokeMock).not.toHaveBeenCalled();
+  });
+
   it("patch().run() should not require lock when payload has no keyring fields", async () => {
     const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
       await loadApi(async (command) => {
@@ -1653,7 +2056,7 @@ describe("Keyring field handling", () => {
     expect(entries).toHaveLength(1);
     expect(entries[0].id).toBe("api-key");
     expect(entries[0].dotpath).toBe("apiKey");
-    expect(entries[0].value).toBe('"secret-123"');
+    expect(entries[0].value).toBe("secret-123");
 
     // Keyring options should be set
     const kOpts = payload.keyringOptions as Record<string, unknown>;
@@ -1661,6 +2064,31 @@ describe("Keyring field handling", () => {
     expect(kOpts.account).toBe("acc");
   });
 
+  it("create should send non-string keyring values with their JSON type", async () => {
+    const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
+      await loadApi(async () => null);
+
+    const schema = defineConfig({ pin: keyring(Number, { id: "pin" }) });
+    const config = new Configurate({
+      schema,
+      fileName: "pin.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    await config
+      .create({ pin: 1234 })
+      .lock({ service: "svc", account: "acc" })
+      .run();
+
+    const createCall = invokeMock.mock.calls.find(
+      ([cmd]) => cmd === "plugin:configurate|create",
+    ) as [string, Record<string, unknown>];
+    const payload = createCall[1].payload as Record<string, unknown>;
+    const entries = payload.keyringEntries as Array<{ value: unknown }>;
+    expect(entries[0].value).toBe(1234);
+  });
+
   it("collectReadEntries should produce entries for unlock", async () => {
     const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
       await loadApi(async (command) => {
@@ -1831,6 +2259,90 @@ describe("Keyring field handling", () => {
     expect(entries[0]?.isOptional).toBe(true);
   });
 
+  it("should send templated array keyring ids as wildcard entries", async () => {
+    const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|load") return {};
+        if (command === "plugin:configurate|create") return null;
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({
+      servers: [
+        { name: String, password: keyring(String, { id: "srv-{field:name}" }) },
+      ],
+    });
+
+    const config = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    await config
+      .create({
+        servers: [
+          { name: "a", password: "pa" },
+          { name: "b", password: "pb" },
+        ],
+      } as never)
+      .lock({ service: "svc", account: "acc" })
+      .run();
+
+    const createCall = invokeMock.mock.calls.find(
+      ([cmd]) => cmd === "plugin:configurate|create",
+    ) as [string, Record<string, unknown>];
+    const payload = createCall[1].payload as Record<string, unknown>;
+    // Secrets stay inline; Rust extracts and nulls them.
+    expect(
+      (payload.data as { servers: Array<{ password: unknown }> }).servers[0]
+        .password,
+    ).toBe("pa");
+    expect(payload.keyringEntries).toEqual([
+      {
+        id: "srv-{field:name}",
+        dotpath: "servers.*.password",
+        value: null,
+        isOptional: undefined,
+      },
+    ]);
+  });
+
+  it("should use bracket notation for keys containing dots", async () => {
+    const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|load") return {};
+        if (command === "plugin:configurate|create") return null;
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({
+      plugins: {
+        "com.example": { token: keyring(String, { id: "plugin-token" }) },
+      },
+    });
+
+    const config = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    await config
+      .create({ plugins: { "com.example": { token: "t" } } } as never)
+      .lock({ service: "svc", account: "acc" })
+      .run();
+
+    const createCall = invokeMock.mock.calls.find(
+      ([cmd]) => cmd === "plugin:configurate|create",
+    ) as [string, Record<string, unknown>];
+    const payload = createCall[1].payload as Record<string, unknown>;
+    const entries = payload.keyringEntries as Array<{ dotpath: string }>;
+    expect(entries[0]?.dotpath).toBe('plugins["com.example"].token');
+  });
+
   it("should handle keyring in array schema (write entries)", async () => {
     const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
       await loadApi(async (command) => {
@@ -1871,8 +2383,8 @@ describe("Keyring field handling", () => {
       value: string;
     }>;
     expect(entries).toHaveLength(2);
-    expect(entries[0].value).toBe('"secret1"');
-    expect(entries[1].value).toBe('"secret2"');
+    expect(entries[0].value).toBe("secret1");
+    expect(entries[1].value).toBe("secret2");
     // Array keyring entries should have encoded dotpath in their id
     expect(entries[0].dotpath).toBe("tokens.0");
     expect(entries[1].dotpath).toBe("tokens.1");
@@ -2204,6 +2716,218 @@ describe("Default values edge cases", () => {
   });
 });
 
+describe("Path-scoped values", () => {
+  it("should send dotpath payloads for getValue / setValue / removeValue", async () => {
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|get_value") return "localhost";
+        if (command === "plugin:configurate|set_value") return null;
+        if (command === "plugin:configurate|remove_value") return true;
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const config = new Configurate({
+      schema: defineConfig({ db: { host: String } }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    expect(await config.getValue("db.host")).toBe("localhost");
+    await config.setValue("db.host", "example.com", { createIfMissing: true });
+    expect(await config.removeValue("db.host")).toBe(true);
+
+    const [, setArgs] = invokeMock.mock.calls[1] as [
+      string,
+      Record<string, unknown>,
+    ];
+    const payload = setArgs.payload as Record<string, unknown>;
+    expect(payload.dotpath).toBe("db.host");
+    expect(payload.value).toBe("example.com");
+    expect((payload.target as Record<string, unknown>).createIfMissing).toBe(true);
+  });
+
+  it("should reject dotpaths that touch keyring fields", async () => {
+    const { Configurate, JsonProvider, defineConfig, keyring, invokeMock } =
+      await loadApi(async (command) => {
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const config = new Configurate({
+      schema: defineConfig({
+        db: { host: String, password: keyring(String, { id: "db-pw" }) },
+      }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    await expect(config.getValue("db.password")).rejects.toThrow("keyring");
+    await expect(config.setValue("db", {})).rejects.toThrow("keyring");
+    await expect(config.removeValue("/db/password")).rejects.toThrow("keyring");
+    expect(invokeMock).not.toHaveBeenCalled();
+  });
+});
+
+describe("Environment overrides", () => {
+  it("should request load metadata and expose envOverrides", async () => {
+    const envOverrides = [
+      { path: "database.host", variable: "MYAPP__DATABASE__HOST" },
+    ];
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|load") {
+          return {
+            data: { database: { host: "db.internal" } },
+            metadata: { envOverrides, lockedKeys: [] },
+          };
+        }
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const config = new Configurate({
+      schema: defineConfig({ database: { host: String } }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const locked = await config.load().run();
+    expect(locked.data).toEqual({ database: { host: "db.internal" } });
+    expect(locked.envOverrides).toEqual(envOverrides);
+
+    const [, args] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    expect((args.payload as Record<string, unknown>).withMetadata).toBe(true);
+
+    const unlocked = await locked.unlock({ service: "app", account: "default" });
+    expect(unlocked.envOverrides).toEqual(envOverrides);
+  });
+});
+
+describe("Managed policy", () => {
+  it("should expose locked keys from load metadata", async () => {
+    const { Configurate, JsonProvider, defineConfig } = await loadApi(
+      async (command) => {
+        if (command === "plugin:configurate|load") {
+          return {
+            data: { telemetry: false, theme: "dark" },
+            metadata: { envOverrides: [], lockedKeys: ["telemetry"] },
+          };
+        }
+        throw new Error(`unexpected command: ${command}`);
+      },
+    );
+
+    const config = new Configurate({
+      schema: defineConfig({ telemetry: Boolean, theme: String }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const locked = await config.load().run();
+    expect(locked.lockedKeys).toEqual(["telemetry"]);
+    const unlocked = await config
+      .load()
+      .unlock({ service: "app", account: "default" });
+    expect(unlocked.lockedKeys).toEqual(["telemetry"]);
+    expect(unlocked.envOverrides).toEqual([]);
+  });
+});
+
+describe("Layered configs", () => {
+  it("should send every layer and the write layer to load_layered / save_layered", async () => {
+    const result = {
+      data: { theme: "dark" },
+      provenance: { theme: "user" },
+      layers: [
+        { id: "defaults", loaded: true },
+        { id: "user", loaded: true },
+      ],
+      writeLayer: "user",
+      lockedKeys: [],
+    };
+    const { Configurate, JsonProvider, defineConfig, invokeMock } =
+      await loadApi(async (command) => {
+        if (command === "plugin:configurate|load_layered") return result;
+        if (command === "plugin:configurate|save_layered") return result;
+        throw new Error(`unexpected command: ${command}`);
+      });
+
+    const schema = defineConfig({ theme: String });
+    const defaults = new Configurate({
+      schema,
+      fileName: "defaults.json",
+      baseDir: 11 as never,
+      provider: JsonProvider(),
+    });
+    const user = new Configurate({
+      schema,
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    const layered = Configurate.layered({
+      layers: [
+        { id: "defaults", config: defaults, required: true },
+        { id: "user", config: user },
+      ],
+      writeLayer: "user",
+    });
+    expect(await layered.load()).toEqual(result);
+    await layered.save({ theme: "dark" });
+
+    const [, loadArgs] = invokeMock.mock.calls[0] as [
+      string,
+      Record<string, unknown>,
+    ];
+    const loadPayload = loadArgs.payload as Record<string, unknown>;
+    const layers = loadPayload.layers as Array<Record<string, unknown>>;
+    expect(layers.map((layer) => layer.id)).toEqual(["defaults", "user"]);
+    expect(layers[0].required).toBe(true);
+    expect(layers[1].required).toBe(false);
+    expect((layers[1].source as Record<string, unknown>).fileName).toBe("app.json");
+    expect(loadPayload.writeLayer).toBe("user");
+    expect("data" in loadPayload).toBe(false);
+
+    const [, saveArgs] = invokeMock.mock.calls[1] as [
+      string,
+      Record<string, unknown>,
+    ];
+    expect((saveArgs.payload as Record<string, unknown>).data).toEqual({
+      theme: "dark",
+    });
+  });
+
+  it("should reject unknown write layers and saves without one", async () => {
+    const { Configurate, JsonProvider, defineConfig } = await loadApi(
+      async (command) => {
+        throw new Error(`unexpected command: ${command}`);
+      },
+    );
+    const config = new Configurate({
+      schema: defineConfig({ theme: String }),
+      fileName: "app.json",
+      baseDir: 13 as never,
+      provider: JsonProvider(),
+    });
+
+    expect(() =>
+      Configurate.layered({
+        layers: [{ id: "user", config }],
+        writeLayer: "system",
+      }),
+    ).toThrow("Unknown writeLayer");
+    await expect(
+      Configurate.layered({ layers: [{ id: "user", config }] }).save({}),
+    ).rejects.toThrow("writeLayer");
+  });
+});
+
 describe("configDiff", () => {
   it("throws when nesting exceeds maximum depth", async () => {
     const { configDiff } = await loadApi(async () => null);
@@ -6,11 +6,26 @@ type ProviderBrand = { readonly __configurateProviderBrand: true };
 
 export type KeyDerivation = "sha256" | "argon2";
 
+export type BinaryCompression = "deflate";
+
+export type BinaryEncoding = "json" | "cbor" | "msgpack";
+
 type ProviderPayload =
   | { kind: "json" }
   | { kind: "yml" }
   | { kind: "toml" }
-  | { kind: "binary"; encryptionKey?: string; kdf?: KeyDerivation };
+  | { kind: "ini" }
+  | { kind: "dotenv" }
+  | { kind: "xml" }
+  | { kind: "plist" }
+  | {
+      kind: "binary";
+      encryptionKey?: string;
+      kdf?: KeyDerivation;
+      reuseSalt?: boolean;
+      compression?: BinaryCompression;
+      encoding?: BinaryEncoding;
+    };
 
 export type ConfigurateProvider = ProviderBrand & Readonly<ProviderPayload>;
 
@@ -27,6 +42,16 @@ function createProvider(payload: ProviderPayload): ConfigurateProvider {
 function isBinaryProvider(value: Record<string, unknown>): boolean {
   if (value.encryptionKey !== undefined && typeof value.encryptionKey !== "string") return false;
   if (value.kdf !== undefined && typeof value.kdf !== "string") return false;
+  if (value.reuseSalt !== undefined && typeof value.reuseSalt !== "boolean") return false;
+  if (value.compression !== undefined && value.compression !== "deflate") return false;
+  if (
+    value.encoding !== undefined &&
+    value.encoding !== "json" &&
+    value.encoding !== "cbor" &&
+    value.encoding !== "msgpack"
+  ) {
+    return false;
+  }
   return true;
 }
 
@@ -43,6 +68,10 @@ export function isProvider(input: unknown): input is ConfigurateProvider {
     case "json":
     case "yml":
     case "toml":
+    case "ini":
+    case "dotenv":
+    case "xml":
+    case "plist":
       return true;
     case "binary":
       return isBinaryProvider(value);
@@ -71,11 +100,64 @@ export function YmlProvider(): ConfigurateProvider {
  * `defaults` option in `Configurate` to supply fallback values on read.
  * Setting a non-optional field to `null` and saving it will cause that field
  * to disappear on the next load.
+ *
+ * Datetimes (including local dates and times) are exposed as
+ * `{ "$datetime": "1979-05-27T07:32:00Z" }`, integers beyond
+ * `Number.MAX_SAFE_INTEGER` as `{ "$integer": "<decimal>" }` and `inf` / `nan`
+ * as `{ "$float": "inf" }`. Numbers saved where the file holds a float stay
+ * floats.
  */
 export function TomlProvider(): ConfigurateProvider {
   return createProvider({ kind: "toml" });
 }
 
+/**
+ * Creates an INI file storage provider.
+ *
+ * Top-level keys come before the first `[section]`; each section is an
+ * object of scalar values and sections do not nest. Unquoted `true` / `false`
+ * and plain decimal numbers are typed; quoted values are always strings.
+ * `null` values are omitted and arrays are rejected. Saves and patches keep
+ * the file's comments and the text of unchanged entries.
+ */
+export function IniProvider(): ConfigurateProvider {
+  return createProvider({ kind: "ini" });
+}
+
+/**
+ * Creates a `.env` file storage provider.
+ *
+ * The config is a flat object of scalar values with the same typing rules
+ * as `IniProvider`; `export KEY=value` lines are accepted and kept. Saves and
+ * patches keep the file's comments.
+ */
+export function DotenvProvider(): ConfigurateProvider {
+  return createProvider({ kind: "dotenv" });
+}
+
+/**
+ * Creates a simple XML file storage provider.
+ *
+ * The config is the content of the root element: attributes become `"@name"`
+ * keys, child elements become keys (repeated ones an array) and text next to
+ * them becomes `"#text"`. Element text is typed like `IniProvider` values;
+ * CDATA text is always a string. Comments are not kept on write.
+ */
+export function XmlProvider(): ConfigurateProvider {
+  return createProvider({ kind: "xml" });
+}
+
+/**
+ * Creates an Apple property list (`.plist`) storage provider.
+ *
+ * Reads XML and binary plists and keeps the binary form on write. Dates are
+ * exposed as `{ "$date": "2024-01-31T12:00:00Z" }` and data as
+ * `{ "$bytes": "<base64>" }`; `null` values are omitted.
+ */
+export function PlistProvider(): ConfigurateProvider {
+  return createProvider({ kind: "plist" });
+}
+
 /**
  * Encrypted binary file provider (XChaCha20-Poly1305).
  *
@@ -83,14 +165,32 @@ export function TomlProvider(): ConfigurateProvider {
  * - Default (`kdf` omitted): SHA-256 of `encryptionKey` — use only with a
  *   high-entropy random key (e.g. from the OS keyring), not a user password.
  * - `kdf: "argon2"`: Argon2id with per-file salt — use for password-based encryption.
+ *   Derived keys are cached in memory for the session. With `reuseSalt: true`,
+ *   saves keep the file's existing salt once its key has been derived, so
+ *   repeated saves skip Argon2 entirely.
+ *
+ * **Compression**
+ * - `compression: "deflate"` compresses the JSON before encryption. Reads
+ *   detect compressed files from their header, so enabling it later is safe.
+ *
+ * **Encoding**
+ * - `encoding: "cbor" | "msgpack"` stores CBOR / MessagePack instead of
+ *   compact JSON (default `"json"`). Byte strings appear in config data as
+ *   `{ $bytes: "<base64>" }`. Reads detect the encoding from the file header.
  */
 export function BinaryProvider(opts?: {
   encryptionKey?: string;
   kdf?: KeyDerivation;
+  reuseSalt?: boolean;
+  compression?: BinaryCompression;
+  encoding?: BinaryEncoding;
 }): ConfigurateProvider {
   return createProvider({
     kind: "binary",
     encryptionKey: opts?.encryptionKey,
     kdf: opts?.kdf,
+    reuseSalt: opts?.reuseSalt,
+    compression: opts?.compression,
+    encoding: opts?.encoding,
   });
 }
@@ -46,7 +46,7 @@ type OptionalFieldRuntime = {
 export type KeyringPayloadEntry = {
   id: string;
   dotpath: string;
-  value: string;
+  value: unknown;
   /** When true a "not found" keyring error is treated as absent, not an error. */
   isOptional?: boolean;
 };
@@ -177,19 +177,118 @@ export function hasArrayKeyring(schema: SchemaObject): boolean {
 // Keyring entry collection
 // ---------------------------------------------------------------------------
 
+/** Object keys that must be written in bracket notation (`["a.b"]`). */
+function needsBrackets(key: string, first: boolean): boolean {
+  return (
+    key === "" ||
+    key === "*" ||
+    /^\d+$/.test(key) ||
+    /[.[\]"]/.test(key) ||
+    (first && key.startsWith("/"))
+  );
+}
+
+/** One dotpath segment including its leading separator. */
+function formatSegment(segment: string | number, first: boolean): string {
+  if (typeof segment === "string" && needsBrackets(segment, first)) {
+    return `[${JSON.stringify(segment)}]`;
+  }
+  return `${first ? "" : "."}${segment}`;
+}
+
+/**
+ * Formats a path as a dotpath Rust can parse back unambiguously. Array
+ * indices and plain keys use dot notation; keys containing `.`, brackets,
+ * quotes or consisting only of digits use `["key"]`.
+ */
 function dotpathFromPath(path: KeyringPath): string {
-  return path.map((segment) => segment.toString()).join(".");
+  return path.map((segment, i) => formatSegment(segment, i === 0)).join("");
 }
 
 function keyringEntryId(baseId: string, path: KeyringPath): string {
-  const dotpath = dotpathFromPath(path);
   if (!path.some((segment) => typeof segment === "number")) return baseId;
-  return `${baseId}::${encodeURIComponent(dotpath)}`;
+  // Ids stay derived from the plain dot-joined path so existing entries keep
+  // resolving regardless of how the dotpath itself is formatted.
+  const legacyPath = path.map((segment) => segment.toString()).join(".");
+  return `${baseId}::${encodeURIComponent(legacyPath)}`;
+}
+
+/**
+ * Keyring ids containing `{index}`, `{key}` or `{field:name}` placeholders are
+ * id templates. Inside arrays they are sent as a single wildcard entry
+ * (`servers.*.password`) that Rust expands against the data, rendering one
+ * id per element and cleaning up ids of removed elements.
+ */
+const KEYRING_ID_TEMPLATE = /\{(?:index\d*|key\d*|field:[^{}]+)\}/;
+
+export function isKeyringIdTemplate(id: string): boolean {
+  return KEYRING_ID_TEMPLATE.test(id);
+}
+
+function isTemplatedArrayKeyring(id: string, path: KeyringPath): boolean {
+  return (
+    isKeyringIdTemplate(id) &&
+    path.some((segment) => typeof segment === "number")
+  );
+}
+
+/**
+ * Pushes one wildcard entry per templated keyring field reachable from the
+ * elements of the array at `arrayPath`. Emitted even for empty arrays so Rust
+ * can clean up ids of elements that were removed.
+ */
+function pushWildcardEntries(
+  elementSchema: SchemaArrayElement,
+  arrayPath: KeyringPath,
+  entries: KeyringPayloadEntry[],
+  optional: boolean,
+): void {
+  const prefix = arrayPath
+    .map((segment, i) =>
+      typeof segment === "number"
+        ? `${i === 0 ? "" : "."}*`
+        : formatSegment(segment, i === 0),
+    )
+    .join("");
+  const visit = (schema: SchemaValue, dotpath: string, isOptional: boolean) => {
+    if (isKeyringField(schema)) {
+      if (!isKeyringIdTemplate(schema._id)) return;
+      if (entries.some((e) => e.id === schema._id && e.dotpath === dotpath)) {
+        return;
+      }
+      entries.push({
+        id: schema._id,
+        dotpath,
+        value: null,
+        isOptional: isOptional || undefined,
+      });
+      return;
+    }
+    if (isSchemaObject(schema)) {
+      for (const [key, rawValueSchema] of Object.entries(schema)) {
+        const { schema: valueSchema, isOptional: fieldOptional } =
+          unwrapOptional(rawValueSchema);
+        visit(
+          valueSchema,
+          `${dotpath}${formatSegment(key, false)}`,
+          isOptional || fieldOptional,
+        );
+      }
+      return;
+    }
+    if (isSchemaArray(schema)) {
+      visit(schema[0] as SchemaValue, `${dotpath}.*`, isOptional);
+    }
+  };
+  visit(elementSchema as SchemaValue, `${prefix}.*`, optional);
 }
 
-function serializeKeyringValue(secret: unknown): string {
-  const s = JSON.stringify(secret);
-  return s ?? "null";
+/**
+ * Keyring values are sent as-is; Rust stores strings verbatim and tags other
+ * JSON types so `unlock` restores them with their original type.
+ */
+function serializeKeyringValue(secret: unknown): unknown {
+  return secret === undefined ? null : secret;
 }
 
 function collectReadEntriesInArray(
@@ -200,14 +299,16 @@ function collectReadEntriesInArray(
   missingOptionalAncestor: boolean,
 ): void {
   if (!Array.isArray(node)) return;
+  pushWildcardEntries(elementSchema, path, entries, missingOptionalAncestor);
   for (let idx = 0; idx < node.length; idx++) {
     const elementPath = [...path, idx];
     const elementNode = node[idx];
     if (isKeyringField(elementSchema)) {
+      if (isTemplatedArrayKeyring(elementSchema._id, elementPath)) continue;
       entries.push({
         id: keyringEntryId(elementSchema._id, elementPath),
         dotpath: dotpathFromPath(elementPath),
-        value: "",
+        value: null,
         isOptional: missingOptionalAncestor || undefined,
       });
       continue;
@@ -245,10 +346,11 @@ function collectReadEntriesInObject(
       (isOptional && (childNode === undefined || childNode === null));
 
     if (isKeyringField(valueSchema)) {
+      if (isTemplatedArrayKeyring(valueSchema._id, keyPath)) continue;
       entries.push({
         id: keyringEntryId(valueSchema._id, keyPath),
         dotpath: dotpathFromPath(keyPath),
-        value: "",
+        value: null,
         isOptional: keyringIsOptional || undefined,
       });
       continue;
@@ -283,9 +385,12 @@ function collectWriteEntriesInArray(
   entries: KeyringPayloadEntry[],
 ): void {
   if (!Array.isArray(node)) return;
+  // Templated secrets stay inline; Rust extracts and nulls them.
+  pushWildcardEntries(elementSchema, path, entries, false);
   for (let idx = 0; idx < node.length; idx++) {
     const elementPath = [...path, idx];
     if (isKeyringField(elementSchema)) {
+      if (isTemplatedArrayKeyring(elementSchema._id, elementPath)) continue;
       const secret = node[idx];
       entries.push({
         id: keyringEntryId(elementSchema._id, elementPath),
@@ -319,6 +424,7 @@ function collectWriteEntriesInObject(
 
     if (isKeyringField(valueSchema)) {
       if (!(key in node)) continue;
+      if (isTemplatedArrayKeyring(valueSchema._id, keyPath)) continue;
       const secret = node[key];
       entries.push({
         id: keyringEntryId(valueSchema._id, keyPath),
@@ -338,6 +444,90 @@ function collectWriteEntriesInObject(
   }
 }
 
+/**
+ * Splits a dotpath (dot / bracket notation or JSON Pointer) into raw
+ * segments. Mirrors the Rust parser closely enough for schema lookups;
+ * malformed paths return `null` and are rejected by Rust.
+ */
+function splitDotpath(path: string): string[] | null {
+  if (path.startsWith("/")) {
+    return path
+      .slice(1)
+      .split("/")
+      .map((s) => s.replace(/~1/g, "/").replace(/~0/g, "~"));
+  }
+  const segments: string[] = [];
+  let i = 0;
+  let current = "";
+  let hasCurrent = false;
+  while (i < path.length) {
+    const ch = path[i];
+    if (ch === ".") {
+      if (hasCurrent) segments.push(current);
+      current = "";
+      hasCurrent = false;
+      i += 1;
+    } else if (ch === "[") {
+      if (hasCurrent) segments.push(current);
+      current = "";
+      hasCurrent = false;
+      if (path[i + 1] === '"') {
+        const match = /^"(?:[^"\\]|\\.)*"/.exec(path.slice(i + 1));
+        if (!match) return null;
+        try {
+          segments.push(JSON.parse(match[0]) as string);
+        } catch {
+          return null;
+        }
+        i += 1 + match[0].length + 1;
+      } else {
+        const close = path.indexOf("]", i);
+        if (close < 0) return null;
+        segments.push(path.slice(i + 1, close));
+        i = close + 1;
+      }
+    } else {
+      current += ch;
+      hasCurrent = true;
+      i += 1;
+    }
+  }
+  if (hasCurrent) segments.push(current);
+  return segments;
+}
+
+/**
+ * Returns true when `path` points at a keyring field, inside one, or at a
+ * parent containing one. Unparseable paths count as touching the keyring.
+ */
+export function dotpathTouchesKeyring(schema: SchemaObject, path: string): boolean {
+  const segments = splitDotpath(path);
+  if (segments === null) return true;
+  let node: SchemaValue = schema;
+  for (const segment of segments) {
+    if (isKeyringField(node)) return true;
+    if (isSchemaArray(node)) {
+      node = node[0] as SchemaValue;
+    } else if (isSchemaObject(node)) {
+      if (
+        segment === "*" ||
+        !Object.prototype.hasOwnProperty.call(node, segment)
+      ) {
+        return segment === "*" && hasAnyKeyring(node);
+      }
+      node = unwrapOptional(node[segment] as SchemaValue).schema;
+    } else {
+      return false;
+    }
+  }
+  if (isKeyringField(node)) return true;
+  if (isSchemaObject(node)) return hasAnyKeyring(node);
+  if (isSchemaArray(node)) {
+    return hasAnyKeyring({ element: node } as unknown as SchemaObject);
+  }
+  return false;
+}
+
 export function collectKeyringIds(schema: SchemaObject): string[] {
   const ids: string[] = [];
   for (const rawVal of Object.values(schema)) {
@@ -0,0 +1,323 @@
+/// Opt-in in-memory cache of parsed config files (`Builder::cache`).
+///
+/// Entries are keyed by resolved path and hold the parsed `Value` together
+/// with the file's modification time and length when it was read.  An entry
+/// is dropped when the plugin writes or deletes the file, when the file
+/// watcher reports a change, and — as a fallback for external writers that
+/// are not watched — whenever the file's mtime or length no longer match.
+/// Entries are also bound to the provider they were read with, so a request
+/// with a different encryption key never sees another key's plaintext.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::models::NormalizedProvider;
+
+/// Cache counters, returned by `cache_stats` for debugging.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStats {
+    /// Cached files.
+    pub entries: usize,
+    /// Sum of the on-disk sizes of the cached files.
+    pub bytes: usize,
+    pub max_entries: usize,
+    pub max_bytes: usize,
+    /// Reads served from the cache.
+    pub hits: u64,
+    /// Reads that went to disk (not cached, stale or read with another provider).
+    pub misses: u64,
+    /// Entries dropped by writes, deletes, watcher events or a changed mtime / length.
+    pub invalidations: u64,
+    /// Entries dropped to stay within `max_entries` / `max_bytes`.
+    pub evictions: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+struct CacheEntry {
+    value: Value,
+    provider: String,
+    fingerprint: Fingerprint,
+    size: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<PathBuf, CacheEntry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// Bounded LRU cache of parsed config files stored in Tauri state.
+pub(crate) struct ReadCache {
+    inner: Mutex<CacheInner>,
+}
+
+impl ReadCache {
+    pub(crate) fn new(max_entries: usize, max_bytes: usize) -> Self {
+        let inner = CacheInner {
+            stats: CacheStats {
+                max_entries,
+                max_bytes,
+                ..CacheStats::default()
+            },
+            ..CacheInner::default()
+        };
+        Self {
+            inner: Mutex::new(inner),
+        }
+    }
+
+    /// Returns the cached value for `path` when it is still current, otherwise
+    /// calls `read` and caches its result.
+    pub(crate) fn read(
+        &self,
+        path: &Path,
+        provider: &NormalizedProvider,
+        read: impl FnOnce() -> Result<Value>,
+    ) -> Result<Value> {
+        let provider = provider_key(provider);
+        let fingerprint = match fingerprint(path) {
+            Ok(fingerprint) => fingerprint,
+            Err(_) => {
+                // Missing or unreadable: let the backend report the error.
+                self.invalidate(path);
+                return read();
+            }
+        };
+
+        {
+            let mut inner = self.lock();
+            inner.clock += 1;
+            let clock = inner.clock;
+            let current = match inner.entries.get_mut(path) {
+                Some(entry) if entry.fingerprint == fingerprint && entry.provider == provider => {
+                    entry.last_used = clock;
+                    Some(entry.value.clone())
+                }
+                Some(_) => {
+                    inner.remove(path);
+                    inner.stats.invalidations += 1;
+                    None
+                }
+                None => None,
+            };
+            if let Some(value) = current {
+                inner.stats.hits += 1;
+                return Ok(value);
+            }
+            inner.stats.misses += 1;
+        }
+
+        let value = read()?;
+        self.insert(path, provider, fingerprint, &value);
+        Ok(value)
+    }
+
+    fn insert(&self, path: &Path, provider: String, fingerprint: Fingerprint, value: &Value) {
+        let size = usize::try_from(fingerprint.len).unwrap_or(usize::MAX);
+        let mut inner = self.lock();
+        if size > inner.stats.max_bytes || inner.stats.max_entries == 0 {
+            return;
+        }
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.remove(path);
+        inner.stats.bytes += size;
+        inner.entries.insert(
+            path.to_path_buf(),
+            CacheEntry {
+                value: value.clone(),
+                provider,
+                fingerprint,
+                size,
+                last_used,
+            },
+        );
+        inner.stats.entries = inner.entries.len();
+        while inner.entries.len() > inner.stats.max_entries
+            || inner.stats.bytes > inner.stats.max_bytes
+        {
+            let Some(oldest) = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(path, _)| path.clone())
+            else {
+                break;
+            };
+            inner.remove(&oldest);
+            inner.stats.evictions += 1;
+        }
+    }
+
+    /// Drops the entry for `path`, if any.
+    pub(crate) fn invalidate(&self, path: &Path) {
+        let mut inner = self.lock();
+        if inner.remove(path) {
+            inner.stats.invalidations += 1;
+        }
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        self.lock().stats.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheInner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CacheInner {
+    fn remove(&mut self, path: &Path) -> bool {
+        let Some(entry) = self.entries.remove(path) else {
+            return false;
+        };
+        self.stats.bytes -= entry.size;
+        self.stats.entries = self.entries.len();
+        true
+    }
+}
+
+fn fingerprint(path: &Path) -> std::io::Result<Fingerprint> {
+    let metadata = std::fs::metadata(path)?;
+    Ok(Fingerprint {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+    })
+}
+
+/// Identifies the provider a value was parsed with.  Encryption keys are
+/// only stored as a SHA-256 digest.
+fn provider_key(provider: &NormalizedProvider) -> String {
+    match provider {
+        NormalizedProvider::Json => "json".to_string(),
+        NormalizedProvider::Yml => "yml".to_string(),
+        NormalizedProvider::Toml => "toml".to_string(),
+        NormalizedProvider::Ini => "ini".to_string(),
+        NormalizedProvider::Dotenv => "dotenv".to_string(),
+        NormalizedProvider::Xml => "xml".to_string(),
+        NormalizedProvider::Plist => "plist".to_string(),
+        NormalizedProvider::Binary {
+            encryption_key: None,
+            ..
+        } => "binary".to_string(),
+        NormalizedProvider::Binary {
+            encryption_key: Some(key),
+            kdf,
+            ..
+        } => {
+            use sha2::{Digest, Sha256};
+            format!("binary:{:?}:{:x}", kdf, Sha256::digest(key.as_bytes()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::KeyDerivation;
+    use serde_json::json;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+    use zeroize::Zeroizing;
+
+    fn write(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn cached_read(cache: &ReadCache, path: &Path, provider: &NormalizedProvider) -> (Value, bool) {
+        let loaded = Cell::new(false);
+        let value = cache
+            .read(path, provider, || {
+                loaded.set(true);
+                let raw = std::fs::read_to_string(path)?;
+                Ok(serde_json::from_str(&raw)?)
+            })
+            .unwrap();
+        (value, loaded.get())
+    }
+
+    #[test]
+    fn hits_until_invalidated_or_changed_on_disk() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "app.json", r#"{"a":1}"#);
+        let cache = ReadCache::new(8, 1024);
+
+        assert_eq!(
+            cached_read(&cache, &path, &NormalizedProvider::Json),
+            (json!({"a": 1}), true)
+        );
+        assert_eq!(
+            cached_read(&cache, &path, &NormalizedProvider::Json),
+            (json!({"a": 1}), false)
+        );
+
+        cache.invalidate(&path);
+        assert!(cached_read(&cache, &path, &NormalizedProvider::Json).1);
+
+        // An external write that changes the length is detected without an event.
+        std::fs::write(&path, r#"{"a":10}"#).unwrap();
+        assert_eq!(
+            cached_read(&cache, &path, &NormalizedProvider::Json),
+            (json!({"a": 10}), true)
+        );
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.invalidations), (1, 3, 2));
+        assert_eq!((stats.entries, stats.bytes), (1, 8));
+    }
+
+    #[test]
+    fn entries_are_bound_to_the_provider() {
+        let dir = TempDir::new().unwrap();
+        let path = write(&dir, "app.bin", r#"{"a":1}"#);
+        let cache = ReadCache::new(8, 1024);
+        let binary = |key: &str| NormalizedProvider::Binary {
+            encryption_key: Some(Zeroizing::new(key.to_string())),
+            kdf: KeyDerivation::Sha256,
+            reuse_salt: false,
+            compression: None,
+            encoding: Default::default(),
+        };
+
+        assert!(cached_read(&cache, &path, &binary("right")).1);
+        assert!(!cached_read(&cache, &path, &binary("right")).1);
+        assert!(cached_read(&cache, &path, &binary("wrong")).1);
+    }
+
+    #[test]
+    fn evicts_least_recently_used_entries_within_limits() {
+        let dir = TempDir::new().unwrap();
+        let a = write(&dir, "a.json", "1");
+        let b = write(&dir, "b.json", "2");
+        let c = write(&dir, "c.json", "3");
+        let big = write(&dir, "big.json", &format!("\"{}\"", "x".repeat(64)));
+        let cache = ReadCache::new(2, 32);
+
+        cached_read(&cache, &a, &NormalizedProvider::Json);
+        cached_read(&cache, &b, &NormalizedProvider::Json);
+        cached_read(&cache, &a, &NormalizedProvider::Json);
+        cached_read(&cache, &c, &NormalizedProvider::Json);
+        assert!(!cached_read(&cache, &a, &NormalizedProvider::Json).1);
+        assert!(cached_read(&cache, &b, &NormalizedProvider::Json).1);
+
+        // Files larger than `max_bytes` are never cached.
+        cached_read(&cache, &big, &NormalizedProvider::Json);
+        assert!(cached_read(&cache, &big, &NormalizedProvider::Json).1);
+        assert_eq!(cache.stats().entries, 2);
+    }
+}
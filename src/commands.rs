@@ -1,21 +1,40 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tauri::{command, path::BaseDirectory, AppHandle, Emitter, Manager, Runtime};
+use tauri::ipc::JavaScriptChannelId;
+use tauri::{command, path::BaseDirectory, AppHandle, Emitter, Manager, Runtime, Webview};
 
+use crate::cache::CacheStats;
 use crate::config;
 use crate::dotpath;
+use crate::encoding;
+use crate::env_overrides::{EnvOverride, EnvOverrides};
 use crate::error::{Error, Result};
+use crate::keyring_manifest::{self, KeyringManifest, KeyringManifestEntry};
 use crate::keyring_store;
+use crate::keyvalue::{self, Dialect};
+use crate::layered::{LayeredData, LayeredPayload, PreparedLayers};
+use crate::locker::FileLock;
 use crate::models::{
     BatchEntryFailure, BatchEntryResult, BatchEntrySuccess, BatchPayload, BatchRunResult,
-    ConfiguratePayload, KeyringEntry, KeyringOptions, NormalizedConfiguratePayload,
-    NormalizedProvider, UnlockPayload,
+    ConfiguratePayload, KeyringEntry, KeyringGcFailure, KeyringGcPayload, KeyringGcResult,
+    KeyringListEntry, KeyringOptions, LoadMetadata, NormalizedConfiguratePayload,
+    NormalizedProvider, UnlockPayload, ValuePayload,
 };
+use crate::patch;
+use crate::policy;
+use crate::property_list;
+use crate::scope::{Access, Scope};
+use crate::secret_store::SecretStore;
 use crate::storage;
+use crate::streaming::{self, ImportUploads};
+use crate::tasks::{self, CancelToken};
+use crate::toml_value;
+use crate::xml;
 
 /// Event payload emitted after configuration changes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +43,9 @@ pub struct ConfigChangeEvent {
     pub file_name: String,
     pub operation: String,
     pub target_id: String,
+    /// Dotpath written by `set_value` / `remove_value`; absent for whole-file operations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 pub(crate) const CHANGE_EVENT: &str = "configurate://change";
@@ -77,8 +99,10 @@ fn validate_file_name(name: &str) -> Result<()> {
     validate_path_component(name)
 }
 
+/// Splits a relative path on both `/` and `\`.  Empty segments are kept so
+/// validation rejects them.
 fn split_path_segments(path: &str) -> impl Iterator<Item = &str> {
-    path.split(|c| c == '/' || c == '\\')
+    path.split(['/', '\\'])
 }
 
 /// Validates a `dir_name` value (forward- or backslash-separated relative path).
@@ -198,11 +222,14 @@ fn resolve_root<R: Runtime>(
     Ok(resolve_root_paths(app, base_dir, dir_name, current_path)?.1)
 }
 
-fn resolve_file_path<R: Runtime>(
+pub(crate) fn resolve_file_path<R: Runtime>(
     app: &AppHandle<R>,
     payload: &NormalizedConfiguratePayload,
 ) -> Result<PathBuf> {
     validate_file_name(&payload.file_name)?;
+    if let Some(grant) = &payload.scope {
+        grant.check(payload)?;
+    }
     let (base, root) = resolve_root_paths(
         app,
         payload.base_dir,
@@ -214,25 +241,20 @@ fn resolve_file_path<R: Runtime>(
     Ok(path)
 }
 
-fn keyring_secret_as_value(secret: String) -> Value {
-    Value::String(secret)
-}
-
 /// Reads keyring entries and inlines the plaintext values back into `data`
 /// at the correct dotpath location.
 fn apply_keyring_reads(
+    store: &dyn SecretStore,
     data: &mut Value,
     entries: &[KeyringEntry],
     opts: &KeyringOptions,
 ) -> Result<()> {
-    for entry in entries {
+    let entries = expand_keyring_reads(data, entries)?;
+    for entry in &entries {
         let val = if entry.is_optional {
-            match keyring_store::get_optional(opts, &entry.id)? {
-                Some(secret) => keyring_secret_as_value(secret),
-                None => Value::Null,
-            }
+            keyring_store::get_optional(store, opts, &entry.id)?.unwrap_or(Value::Null)
         } else {
-            keyring_secret_as_value(keyring_store::get(opts, &entry.id)?)
+            keyring_store::get(store, opts, &entry.id)?
         };
         dotpath::set(data, &entry.dotpath, val)?;
     }
@@ -249,7 +271,19 @@ fn validate_keyring_entries(entries: &[KeyringEntry], use_: KeyringEntryUse) ->
     for entry in entries {
         keyring_store::validate_entry_id(&entry.id)?;
         dotpath::validate_path(&entry.dotpath)?;
-        if matches!(use_, KeyringEntryUse::Read) && !entry.value.is_empty() {
+        if dotpath::has_wildcard(&entry.dotpath) {
+            keyring_store::validate_id_template(&entry.id)?;
+            if matches!(use_, KeyringEntryUse::Write) && !entry.value.is_null() {
+                return Err(Error::InvalidPayload(format!(
+                    "keyring entry '{}' has a wildcard dotpath; its values are taken from the \
+                     config data and must not be sent separately",
+                    entry.id
+                )));
+            }
+        }
+        let has_value = !matches!(&entry.value, Value::Null)
+            && entry.value.as_str().is_none_or(|s| !s.is_empty());
+        if matches!(use_, KeyringEntryUse::Read) && has_value {
             return Err(Error::InvalidPayload(format!(
                 "keyring entry '{}' must not include a value on read operations",
                 entry.id
@@ -266,16 +300,22 @@ fn validate_keyring_delete_ids(ids: &[String]) -> Result<()> {
     Ok(())
 }
 
-fn write_keyring_entries(opts: &KeyringOptions, entries: &[KeyringEntry]) -> Result<()> {
+fn write_keyring_entries(
+    store: &dyn SecretStore,
+    opts: &KeyringOptions,
+    entries: &[KeyringEntry],
+) -> Result<()> {
     for entry in entries {
-        keyring_store::set(opts, &entry.id, &entry.value)?;
+        keyring_store::set(store, opts, &entry.id, &entry.value)?;
     }
     Ok(())
 }
 
 fn cleanup_stale_keyring_entries(
+    store: &dyn SecretStore,
     delete_ids: &[String],
     opts: Option<&KeyringOptions>,
+    manifest: &mut KeyringManifest,
 ) -> Result<()> {
     if delete_ids.is_empty() {
         return Ok(());
@@ -292,8 +332,9 @@ fn cleanup_stale_keyring_entries(
         if !seen.insert(id.clone()) {
             continue;
         }
-        if let Err(e) = keyring_store::delete(opts, id) {
-            failures.push(format!("'{}': {}", id, e));
+        match keyring_store::delete(store, opts, id) {
+            Ok(()) => manifest.forget(opts, [id.as_str()]),
+            Err(e) => failures.push(format!("'{}': {}", id, e)),
         }
     }
 
@@ -304,11 +345,222 @@ fn cleanup_stale_keyring_entries(
     Err(Error::Keyring(format!(
         "some stale keyring entries could not be removed ({}). The config data \
          was written successfully, but orphaned entries may remain in the OS \
-         keyring.",
+         keyring; run keyring_gc to retry.",
         failures.join("; ")
     )))
 }
 
+/// Expands entries with wildcard dotpaths (e.g. `servers.*.password`) into one
+/// concrete entry per matched element, rendering each id from the entry's id
+/// template.
+///
+/// With `take_values`, each concrete entry carries the value found in `data`
+/// and elements whose value is absent or `null` are skipped; otherwise every
+/// match is returned with a `null` value.
+fn expand_wildcard_entries(
+    data: &Value,
+    entries: &[&KeyringEntry],
+    take_values: bool,
+) -> Result<Vec<KeyringEntry>> {
+    let mut out = Vec::new();
+    for entry in entries {
+        for found in dotpath::expand(data, &entry.dotpath)? {
+            let value = match found.value {
+                _ if !take_values => Value::Null,
+                Some(value) if !value.is_null() => value.clone(),
+                _ => continue,
+            };
+            out.push(KeyringEntry {
+                id: keyring_store::render_id(&entry.id, &found.steps)?,
+                dotpath: found.path,
+                value,
+                is_optional: entry.is_optional,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// Returns `entries` with wildcard entries expanded against `data` for reading.
+fn expand_keyring_reads(data: &Value, entries: &[KeyringEntry]) -> Result<Vec<KeyringEntry>> {
+    let mut out = Vec::with_capacity(entries.len());
+    for entry in entries {
+        if dotpath::has_wildcard(&entry.dotpath) {
+            out.extend(expand_wildcard_entries(data, &[entry], false)?);
+        } else {
+            out.push(entry.clone());
+        }
+    }
+    ensure_unique_keyring_ids(&out)?;
+    Ok(out)
+}
+
+fn ensure_unique_keyring_ids(entries: &[KeyringEntry]) -> Result<()> {
+    let mut seen = BTreeSet::new();
+    for entry in entries {
+        if !seen.insert(entry.id.as_str()) {
+            return Err(Error::InvalidPayload(format!(
+                "keyring id '{}' is used by more than one field",
+                entry.id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Concrete keyring writes for one create/save/patch.
+struct KeyringWrite<'a> {
+    entries: Vec<KeyringEntry>,
+    opts: &'a KeyringOptions,
+    /// Ids rendered from wildcard entries for elements that existed in the
+    /// previous file but are gone from the data being written.
+    stale_ids: Vec<String>,
+}
+
+/// Expands wildcard entries against the data about to be written and works
+/// out which previously expanded ids no longer have an element.
+///
+/// The previous expansion is computed from the file currently on disk.  A
+/// missing file has no previous elements; any other read error fails the
+/// write.
+fn plan_keyring_write<'a, R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+    keyring: Option<(&'a [KeyringEntry], &'a KeyringOptions)>,
+    data: &Value,
+) -> Result<Option<KeyringWrite<'a>>> {
+    let Some((entries, opts)) = keyring else {
+        return Ok(None);
+    };
+    let (wildcard, concrete): (Vec<&KeyringEntry>, Vec<&KeyringEntry>) = entries
+        .iter()
+        .partition(|entry| dotpath::has_wildcard(&entry.dotpath));
+
+    let mut write = KeyringWrite {
+        entries: concrete.into_iter().cloned().collect(),
+        opts,
+        stale_ids: Vec::new(),
+    };
+    if !wildcard.is_empty() {
+        write
+            .entries
+            .extend(expand_wildcard_entries(data, &wildcard, true)?);
+
+        let current: BTreeSet<String> = expand_wildcard_entries(data, &wildcard, false)?
+            .into_iter()
+            .map(|entry| entry.id)
+            .collect();
+        // An unreadable file must not skip the stale-id cleanup and the
+        // removal check, so only a missing file has no previous elements.
+        if let Some(previous) = load_existing_plain_data(app, payload)? {
+            if let Ok(expanded) = expand_wildcard_entries(&previous, &wildcard, false) {
+                write.stale_ids = expanded
+                    .into_iter()
+                    .map(|entry| entry.id)
+                    .filter(|id| !current.contains(id))
+                    .collect();
+            }
+            check_indexed_removals(&previous, data, &wildcard)?;
+        }
+    }
+    ensure_unique_keyring_ids(&write.entries)?;
+    Ok(Some(write))
+}
+
+/// Rejects a write that removed array elements addressed by an `{index}` id
+/// template while a remaining element relies on its stored secret (`null` or
+/// absent in `data`).
+///
+/// `{index}` ids are positional: after a removal the plugin cannot tell which
+/// stored secret belongs to which remaining element, so those secrets must be
+/// sent again, or the entry must use a `{key}` / `{field:…}` template.
+fn check_indexed_removals(
+    previous: &Value,
+    data: &Value,
+    wildcard: &[&KeyringEntry],
+) -> Result<()> {
+    for entry in wildcard {
+        if !keyring_store::has_index_placeholder(&entry.id) {
+            continue;
+        }
+        // A previous file of another shape has no elements to remove.
+        let Ok(before) = expand_wildcard_entries(previous, &[*entry], false) else {
+            continue;
+        };
+        let after = dotpath::expand(data, &entry.dotpath)?;
+        let mut current = BTreeSet::new();
+        for found in &after {
+            current.insert(keyring_store::render_id(&entry.id, &found.steps)?);
+        }
+        if before.iter().all(|old| current.contains(&old.id)) {
+            continue;
+        }
+        if let Some(found) = after
+            .iter()
+            .find(|found| found.value.is_none_or(Value::is_null))
+        {
+            return Err(Error::InvalidPayload(format!(
+                "array elements matching '{}' were removed, so the stored secret for \
+                 '{}' cannot be matched to its element by position; send every \
+                 remaining secret or use a {{key}} / {{field:…}} id template",
+                entry.dotpath, found.path
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects secrets that exceed the secret store's size limit before anything
+/// is written, so an oversized value never leaves a half-saved config.
+fn check_keyring_sizes<R: Runtime>(
+    app: &AppHandle<R>,
+    keyring: Option<&KeyringWrite<'_>>,
+) -> Result<()> {
+    if let Some(write) = keyring {
+        let store = config::secret_store(app);
+        for entry in &write.entries {
+            keyring_store::check_size(store.as_ref(), &entry.id, &entry.value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes keyring secrets and removes stale ones after the config file was
+/// persisted, keeping the keyring manifest beside the file in sync.
+///
+/// New ids are recorded in the manifest before any secret is written so a
+/// partial failure never leaves untracked entries behind.
+fn persist_keyring<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+    keyring: Option<&KeyringWrite<'_>>,
+) -> Result<()> {
+    if keyring.is_none() && payload.keyring_delete_ids.is_empty() {
+        return Ok(());
+    }
+
+    let store = config::secret_store(app);
+    let manifest_path = keyring_manifest::manifest_path(&resolve_file_path(app, payload)?);
+    let mut manifest = KeyringManifest::load(&manifest_path)?;
+    let mut delete_ids = payload.keyring_delete_ids.clone();
+
+    if let Some(write) = keyring {
+        manifest.record(write.opts, write.entries.iter().map(|e| e.id.as_str()));
+        manifest.save(&manifest_path)?;
+        write_keyring_entries(store.as_ref(), write.opts, &write.entries)?;
+        delete_ids.extend(write.stale_ids.iter().cloned());
+    }
+
+    let cleanup = cleanup_stale_keyring_entries(
+        store.as_ref(),
+        &delete_ids,
+        payload.keyring_options.as_ref(),
+        &mut manifest,
+    );
+    manifest.save(&manifest_path)?;
+    cleanup
+}
+
 /// Validates keyring payload pair semantics.
 ///
 /// `keyring_entries` and `keyring_options` must either both be provided or
@@ -336,7 +588,7 @@ fn keyring_pair<'a>(
     }
 }
 
-fn load_plain_data<R: Runtime>(
+pub(crate) fn load_plain_data<R: Runtime>(
     app: &AppHandle<R>,
     payload: &NormalizedConfiguratePayload,
 ) -> Result<Value> {
@@ -348,10 +600,26 @@ fn load_plain_data<R: Runtime>(
         max_read_bytes,
     )?;
     let path = resolve_file_path(app, payload)?;
-    backend.read(&path)
+    match config::read_cache(app) {
+        Some(cache) => cache.read(&path, &payload.provider, || backend.read(&path)),
+        None => backend.read(&path),
+    }
+}
+
+/// Like [`load_plain_data`], but a missing file yields `None`.  Every other
+/// read failure is returned.
+fn load_existing_plain_data<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+) -> Result<Option<Value>> {
+    match load_plain_data(app, payload) {
+        Ok(data) => Ok(Some(data)),
+        Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
-fn save_plain_data<R: Runtime>(
+pub(crate) fn save_plain_data<R: Runtime>(
     app: &AppHandle<R>,
     payload: &NormalizedConfiguratePayload,
     data: &Value,
@@ -368,7 +636,11 @@ fn save_plain_data<R: Runtime>(
         max_read_bytes,
     )?;
     let path = resolve_file_path(app, payload)?;
-    backend.write(&path, data)
+    let result = backend.write(&path, data);
+    if let Some(cache) = config::read_cache(app) {
+        cache.invalidate(&path);
+    }
+    result
 }
 
 fn delete_plain_data<R: Runtime>(
@@ -376,6 +648,9 @@ fn delete_plain_data<R: Runtime>(
     payload: &NormalizedConfiguratePayload,
 ) -> Result<()> {
     let path = resolve_file_path(app, payload)?;
+    if let Some(cache) = config::read_cache(app) {
+        cache.invalidate(&path);
+    }
     // Remove the config file. Treat "file not found" as success.
     match std::fs::remove_file(&path) {
         Ok(_) => {}
@@ -394,6 +669,18 @@ fn execute_create<R: Runtime>(
         .take()
         .unwrap_or(Value::Object(serde_json::Map::new()));
 
+    // Over an existing file, `create` is held to the same rules as `save`.
+    // A new file (including `reset`, which deletes first, and `seedFrom`
+    // seeding) simply gets the policy values written in.
+    let policy = policy::for_payload(app, &payload)?;
+    let stored = stored_for_write(app, &payload, policy.as_ref())?;
+    strip_env_overrides(app, stored.as_ref(), &mut data)?;
+    match (&policy, &stored) {
+        (Some(policy), Some(stored)) => policy.enforce(Some(stored), &mut data)?,
+        (Some(policy), None) => policy.apply(&mut data)?,
+        (None, _) => {}
+    }
+
     // Keep a copy for the unlocked response before nullifying secrets.
     let unlocked_data = if payload.with_unlock && payload.return_data {
         Some(data.clone())
@@ -408,25 +695,22 @@ fn execute_create<R: Runtime>(
         &payload.keyring_options,
     )?;
 
+    let keyring = plan_keyring_write(app, &payload, keyring, &data)?;
+
     // Nullify secrets in the on-disk data before saving.
-    if let Some((entries, _opts)) = &keyring {
-        for entry in *entries {
+    if let Some(write) = &keyring {
+        for entry in &write.entries {
             dotpath::nullify(&mut data, &entry.dotpath)?;
         }
     }
 
+    check_keyring_sizes(app, keyring.as_ref())?;
+
     // Persist plain data first so that if it fails, the keyring is not updated.
     save_plain_data(app, &payload, &data)?;
 
     // Only write secrets to the OS keyring after successful storage write.
-    if let Some((entries, opts)) = keyring {
-        write_keyring_entries(opts, entries)?;
-    }
-
-    cleanup_stale_keyring_entries(
-        &payload.keyring_delete_ids,
-        payload.keyring_options.as_ref(),
-    )?;
+    persist_keyring(app, &payload, keyring.as_ref())?;
     if payload.return_data {
         Ok(unlocked_data.unwrap_or(data))
     } else {
@@ -434,13 +718,172 @@ fn execute_create<R: Runtime>(
     }
 }
 
+/// Reads the `seedFrom` template of a config that does not exist yet and
+/// deep-merges `data` over it.  Returns `None` when the config exists or no
+/// template was given.
+fn seed_data<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+) -> Result<Option<Value>> {
+    let Some(template) = &payload.seed_from else {
+        return Ok(None);
+    };
+    if resolve_file_path(app, payload)?.try_exists()? {
+        return Ok(None);
+    }
+    let mut data = load_plain_data(app, template).map_err(|e| match e {
+        Error::Io(e) if e.kind() == std::io::ErrorKind::NotFound => Error::InvalidPayload(
+            format!("seedFrom template '{}' does not exist", template.file_name),
+        ),
+        e => e,
+    })?;
+    if let Some(overlay) = payload.data.clone() {
+        let strategies = patch::ArrayStrategies::parse(&payload.array_merge)?;
+        patch::deep_merge(&mut data, overlay, &strategies);
+    }
+    Ok(Some(data))
+}
+
+/// `create` with `seedFrom`: writes template + `data` when the config is
+/// missing, otherwise leaves it untouched and returns the stored data.
+/// The flag reports whether the file was written.
+fn execute_seeded_create<R: Runtime>(
+    app: &AppHandle<R>,
+    mut payload: NormalizedConfiguratePayload,
+) -> Result<(Value, bool)> {
+    if let Some(data) = seed_data(app, &payload)? {
+        payload.data = Some(data);
+        return Ok((execute_create(app, payload)?, true));
+    }
+    if !payload.return_data {
+        return Ok((Value::Null, false));
+    }
+    let mut data = load_plain_data(app, &payload)?;
+    if payload.with_unlock {
+        let keyring = keyring_pair(
+            "create",
+            KeyringEntryUse::Write,
+            &payload.keyring_entries,
+            &payload.keyring_options,
+        )?;
+        if let Some((entries, opts)) = keyring {
+            apply_keyring_reads(config::secret_store(app).as_ref(), &mut data, entries, opts)?;
+        }
+    }
+    Ok((data, false))
+}
+
+/// Seeds a missing config from its `seedFrom` template before `load`.  The
+/// seeded data is written as plain data: keyring fields are nulled and no
+/// secrets are stored, and managed policy values replace the template's.
+/// Returns the change event to emit.
+fn seed_before_load<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+    scope: &Scope,
+) -> Result<Option<ConfigChangeEvent>> {
+    if payload.seed_from.is_none() {
+        return Ok(None);
+    }
+    let target = payload.clone().scoped(scope, Access::Write);
+    let Some(mut data) = seed_data(app, &target)? else {
+        return Ok(None);
+    };
+    config::validate_writable(app, target.base_dir)?;
+    if let Some(entries) = &target.keyring_entries {
+        for entry in expand_keyring_reads(&data, entries)? {
+            dotpath::nullify(&mut data, &entry.dotpath)?;
+        }
+    }
+    if let Some(policy) = policy::for_payload(app, &target)? {
+        policy.apply(&mut data)?;
+    }
+    save_plain_data(app, &target, &data)?;
+    Ok(Some(build_change_event(&target, "create")))
+}
+
+/// Loaded plain data plus the environment overrides and managed policy
+/// keys applied to it.
+struct LoadedData {
+    data: Value,
+    env_overrides: Vec<EnvOverride>,
+    locked_keys: Vec<String>,
+}
+
+impl LoadedData {
+    fn metadata(&self) -> Option<LoadMetadata> {
+        if self.env_overrides.is_empty() && self.locked_keys.is_empty() {
+            return None;
+        }
+        Some(LoadMetadata {
+            env_overrides: self.env_overrides.clone(),
+            locked_keys: self.locked_keys.clone(),
+        })
+    }
+}
+
 fn execute_load<R: Runtime>(
     app: &AppHandle<R>,
     payload: NormalizedConfiguratePayload,
-) -> Result<Value> {
+) -> Result<LoadedData> {
     validate_load_keyring_policy(&payload)?;
     let data = load_plain_data(app, &payload)?;
-    Ok(data)
+    let policy = policy::for_payload(app, &payload)?;
+    effective_data(data, config::env_overrides(app).as_deref(), policy.as_ref())
+}
+
+/// Applies environment overrides and then the managed policy, which wins
+/// over them, to the stored `data`.
+fn effective_data(
+    mut data: Value,
+    overrides: Option<&EnvOverrides>,
+    policy: Option<&policy::Policy>,
+) -> Result<LoadedData> {
+    let mut env_overrides = match overrides {
+        Some(overrides) => overrides.apply(&mut data)?,
+        None => Vec::new(),
+    };
+    let locked_keys = match policy {
+        Some(policy) => {
+            policy.apply(&mut data)?;
+            policy.locked_keys()
+        }
+        None => Vec::new(),
+    };
+    env_overrides.retain(|o| !locked_keys.contains(&o.path));
+    Ok(LoadedData {
+        data,
+        env_overrides,
+        locked_keys,
+    })
+}
+
+/// Reads the stored data when a write needs it to undo environment overrides
+/// or enforce the managed policy.  A missing file yields `None`; any other
+/// read failure aborts the write, since enforcing against a file that could
+/// not be read would let locked values through.
+pub(crate) fn stored_for_write<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+    policy: Option<&policy::Policy>,
+) -> Result<Option<Value>> {
+    if config::env_overrides(app).is_none() && policy.is_none() {
+        return Ok(None);
+    }
+    load_existing_plain_data(app, payload)
+}
+
+/// Restores stored values for keys that still hold an environment override so
+/// overrides are never persisted.  `stored` is the file's current content.
+fn strip_env_overrides<R: Runtime>(
+    app: &AppHandle<R>,
+    stored: Option<&Value>,
+    data: &mut Value,
+) -> Result<()> {
+    match config::env_overrides(app) {
+        Some(overrides) => overrides.restore(stored, data),
+        None => Ok(()),
+    }
 }
 
 fn execute_save<R: Runtime>(
@@ -452,6 +895,13 @@ fn execute_save<R: Runtime>(
         .take()
         .unwrap_or(Value::Object(serde_json::Map::new()));
 
+    let policy = policy::for_payload(app, &payload)?;
+    let stored = stored_for_write(app, &payload, policy.as_ref())?;
+    strip_env_overrides(app, stored.as_ref(), &mut data)?;
+    if let Some(policy) = &policy {
+        policy.enforce(stored.as_ref(), &mut data)?;
+    }
+
     let unlocked_data = if payload.with_unlock && payload.return_data {
         Some(data.clone())
     } else {
@@ -465,25 +915,22 @@ fn execute_save<R: Runtime>(
         &payload.keyring_options,
     )?;
 
+    let keyring = plan_keyring_write(app, &payload, keyring, &data)?;
+
     // Nullify secrets in the on-disk data before saving.
-    if let Some((entries, _opts)) = &keyring {
-        for entry in *entries {
+    if let Some(write) = &keyring {
+        for entry in &write.entries {
             dotpath::nullify(&mut data, &entry.dotpath)?;
         }
     }
 
+    check_keyring_sizes(app, keyring.as_ref())?;
+
     // Persist plain data first so that if it fails, the keyring is not updated.
     save_plain_data(app, &payload, &data)?;
 
     // Only write secrets to the OS keyring after successful storage write.
-    if let Some((entries, opts)) = keyring {
-        write_keyring_entries(opts, entries)?;
-    }
-
-    cleanup_stale_keyring_entries(
-        &payload.keyring_delete_ids,
-        payload.keyring_options.as_ref(),
-    )?;
+    persist_keyring(app, &payload, keyring.as_ref())?;
     if payload.return_data {
         Ok(unlocked_data.unwrap_or(data))
     } else {
@@ -495,31 +942,49 @@ fn execute_delete<R: Runtime>(
     app: &AppHandle<R>,
     payload: NormalizedConfiguratePayload,
 ) -> Result<()> {
+    let keyring = keyring_pair(
+        "delete",
+        KeyringEntryUse::Read,
+        &payload.keyring_entries,
+        &payload.keyring_options,
+    )?;
+    // Wildcard entries are expanded against the file before it is removed.  A
+    // file that cannot be read is not deleted, so its secrets are not orphaned.
+    let keyring = match keyring {
+        Some((entries, opts)) => {
+            let data = if entries.iter().any(|e| dotpath::has_wildcard(&e.dotpath)) {
+                load_existing_plain_data(app, &payload)?.unwrap_or(Value::Null)
+            } else {
+                Value::Null
+            };
+            Some((expand_keyring_reads(&data, entries)?, opts))
+        }
+        None => None,
+    };
+
     // Delete storage first so that if it fails the keyring entries are
     // preserved and the user can retry without data loss.
     // If storage deletion succeeds but keyring cleanup fails later, the
     // worst case is orphaned (but harmless) entries in the OS keyring.
     delete_plain_data(app, &payload)?;
 
-    if let Some((entries, opts)) =
-        keyring_pair(
-            "delete",
-            KeyringEntryUse::Read,
-            &payload.keyring_entries,
-            &payload.keyring_options,
-        )?
-    {
+    if let Some((entries, opts)) = keyring {
+        let store = config::secret_store(app);
+        let manifest_path = keyring_manifest::manifest_path(&resolve_file_path(app, &payload)?);
+        let mut manifest = KeyringManifest::load(&manifest_path)?;
         let mut failures: Vec<String> = Vec::new();
-        for entry in entries {
-            if let Err(e) = keyring_store::delete(opts, &entry.id) {
-                failures.push(format!("'{}': {}", entry.id, e));
+        for entry in &entries {
+            match keyring_store::delete(store.as_ref(), opts, &entry.id) {
+                Ok(()) => manifest.forget(opts, [entry.id.as_str()]),
+                Err(e) => failures.push(format!("'{}': {}", entry.id, e)),
             }
         }
+        manifest.save(&manifest_path)?;
         if !failures.is_empty() {
             return Err(Error::Keyring(format!(
                 "config file was deleted but some keyring entries could not be removed ({}). \
-                 The orphaned entries are harmless but can be cleaned up manually via the OS \
-                 keyring manager.",
+                 The orphaned entries are still tracked in the keyring manifest and can be \
+                 removed with keyring_gc.",
                 failures.join("; ")
             )));
         }
@@ -593,11 +1058,15 @@ fn validate_batch_ids(payload: &BatchPayload) -> Result<()> {
     Ok(())
 }
 
-fn provider_kind(provider: &NormalizedProvider) -> &'static str {
+pub(crate) fn provider_kind(provider: &NormalizedProvider) -> &'static str {
     match provider {
         NormalizedProvider::Json => "json",
         NormalizedProvider::Yml => "yml",
         NormalizedProvider::Toml => "toml",
+        NormalizedProvider::Ini => "ini",
+        NormalizedProvider::Dotenv => "dotenv",
+        NormalizedProvider::Xml => "xml",
+        NormalizedProvider::Plist => "plist",
         NormalizedProvider::Binary { .. } => "binary",
     }
 }
@@ -615,7 +1084,7 @@ fn change_target_id(payload: &NormalizedConfiguratePayload) -> String {
     )
 }
 
-fn build_change_event(
+pub(crate) fn build_change_event(
     payload: &NormalizedConfiguratePayload,
     operation: &str,
 ) -> ConfigChangeEvent {
@@ -623,62 +1092,137 @@ fn build_change_event(
         file_name: payload.file_name.clone(),
         operation: operation.to_string(),
         target_id: change_target_id(payload),
+        path: None,
     }
 }
 
-fn emit_change<R: Runtime>(app: &AppHandle<R>, event: ConfigChangeEvent) {
+pub(crate) fn emit_change<R: Runtime>(app: &AppHandle<R>, event: ConfigChangeEvent) {
     let _ = app.emit(CHANGE_EVENT, event);
 }
 
-/// Returns an `Arc<Mutex<()>>` that serialises access to the config file path
-/// within this process so multi-step operations (patch, reset) cannot interleave.
-fn acquire_file_lock<R: Runtime>(
+/// Returns the lock that serialises access to the config file path within
+/// this process so multi-step operations (patch, reset) cannot interleave.
+pub(crate) fn acquire_file_lock<R: Runtime>(
     app: &AppHandle<R>,
     payload: &NormalizedConfiguratePayload,
-) -> Option<Arc<Mutex<()>>> {
+) -> Option<FileLock> {
     resolve_file_path(app, payload).ok().map(|p| {
         let registry = app.state::<crate::locker::FileLockRegistry>();
         registry.acquire(p)
     })
 }
 
+/// Runs `body` on the blocking thread pool while holding the file lock of
+/// `payload`.  Waiting for the lock fails with error kind `cancelled` once
+/// the invoking window is closed.
+async fn run_locked<R, T, F>(
+    app: &AppHandle<R>,
+    cancel: &CancelToken,
+    payload: NormalizedConfiguratePayload,
+    body: F,
+) -> Result<T>
+where
+    R: Runtime,
+    T: Send + 'static,
+    F: FnOnce(&AppHandle<R>, NormalizedConfiguratePayload) -> Result<T> + Send + 'static,
+{
+    let lock = acquire_file_lock(app, &payload);
+    let app = app.clone();
+    tasks::run_locked(lock, cancel, move || body(&app, payload)).await
+}
+
+/// Derives the Argon2 keys the operation on `payload` will need on the
+/// blocking thread pool.  Errors are left for the operation itself to report.
+pub(crate) async fn prefetch_keys<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+    write: bool,
+) {
+    if let Ok(path) = resolve_file_path(app, payload) {
+        let _ = storage::prefetch_keys(&payload.provider, &path, write).await;
+    }
+}
+
 #[command]
 pub(crate) async fn create<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<Value> {
-    let normalized = payload.normalize()?;
+    let normalized = payload.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
     let change_event = build_change_event(&normalized, "create");
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    let result = execute_create(&app, normalized)?;
-    drop(_guard);
-    emit_change(&app, change_event);
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    // The existence check and the seeding write happen under the file lock,
+    // so concurrent first-run calls seed the file exactly once.
+    let (result, written) = run_locked(&app, &cancel, normalized, |app, normalized| {
+        if normalized.seed_from.is_some() {
+            execute_seeded_create(app, normalized)
+        } else {
+            Ok((execute_create(app, normalized)?, true))
+        }
+    })
+    .await?;
+    if written {
+        emit_change(&app, change_event);
+    }
     Ok(result)
 }
 
 #[command]
 pub(crate) async fn load<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
+    channel: Option<JavaScriptChannelId>,
 ) -> Result<Value> {
-    let normalized = payload.normalize()?;
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    execute_load(&app, normalized)
+    let normalized = payload.normalize()?.scoped(&scope, Access::Read);
+    prefetch_keys(&app, &normalized, false).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let with_metadata = normalized.with_metadata;
+    let (seeded, loaded) = run_locked(&app, &cancel, normalized, move |app, normalized| {
+        let seeded = seed_before_load(app, &normalized, &scope)?;
+        Ok((seeded, execute_load(app, normalized)?))
+    })
+    .await?;
+    if let Some(change_event) = seeded {
+        emit_change(&app, change_event);
+    }
+    let response = if with_metadata {
+        let metadata = loaded.metadata().unwrap_or_default();
+        json!({
+            "data": loaded.data,
+            "metadata": metadata,
+        })
+    } else {
+        loaded.data
+    };
+    match channel {
+        Some(channel) => {
+            let channel = channel.channel_on(webview);
+            tasks::blocking(move || streaming::send_json(&channel, &response)).await?;
+            Ok(Value::Null)
+        }
+        None => Ok(response),
+    }
 }
 
 #[command]
 pub(crate) async fn save<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<Value> {
-    let normalized = payload.normalize()?;
+    let normalized = payload.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
     let change_event = build_change_event(&normalized, "save");
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    let result = execute_save(&app, normalized)?;
-    drop(_guard);
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let result = run_locked(&app, &cancel, normalized, execute_save).await?;
     emit_change(&app, change_event);
     Ok(result)
 }
@@ -686,14 +1230,15 @@ pub(crate) async fn save<R: Runtime>(
 #[command]
 pub(crate) async fn delete<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<()> {
-    let normalized = payload.normalize()?;
+    let normalized = payload.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
     let change_event = build_change_event(&normalized, "delete");
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    execute_delete(&app, normalized)?;
-    drop(_guard);
+    let cancel = CancelToken::for_webview(&webview);
+    run_locked(&app, &cancel, normalized, execute_delete).await?;
     emit_change(&app, change_event);
     Ok(())
 }
@@ -701,37 +1246,46 @@ pub(crate) async fn delete<R: Runtime>(
 #[command]
 pub(crate) async fn exists<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<bool> {
-    let normalized = payload.normalize()?;
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    execute_exists(&app, normalized)
+    let normalized = payload.normalize()?.scoped(&scope, Access::Read);
+    let cancel = CancelToken::for_webview(&webview);
+    run_locked(&app, &cancel, normalized, execute_exists).await
 }
 
 #[command]
 pub(crate) async fn load_all<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: BatchPayload,
+    scope: Scope,
 ) -> Result<BatchRunResult> {
     validate_batch_ids(&payload)?;
+    let cancel = CancelToken::for_webview(&webview);
+    let entries = normalize_batch(payload, &scope, Access::Read);
+    let outcomes = run_batch(&app, entries, move |app, normalized| {
+        load_batch_entry(app, cancel.clone(), normalized)
+    })
+    .await?;
 
     let mut results = BTreeMap::new();
 
-    for entry in payload.entries {
-        let entry_result = match entry.payload.normalize().and_then(|p| {
-            let lock = acquire_file_lock(&app, &p);
-            let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-            execute_load(&app, p)
-        }) {
-            Ok(data) => BatchEntryResult::Success(BatchEntrySuccess { ok: true, data }),
+    for (id, outcome) in outcomes {
+        let entry_result = match outcome {
+            Ok(loaded) => BatchEntryResult::Success(BatchEntrySuccess {
+                ok: true,
+                metadata: loaded.metadata(),
+                data: loaded.data,
+            }),
             Err(error) => BatchEntryResult::Failure(BatchEntryFailure {
                 ok: false,
                 error: to_batch_error_value(&error),
             }),
         };
 
-        results.insert(entry.id, entry_result);
+        results.insert(id, entry_result);
     }
 
     Ok(BatchRunResult { results })
@@ -740,24 +1294,126 @@ pub(crate) async fn load_all<R: Runtime>(
 #[command]
 pub(crate) async fn save_all<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: BatchPayload,
+    scope: Scope,
 ) -> Result<BatchRunResult> {
     validate_batch_ids(&payload)?;
+    let cancel = CancelToken::for_webview(&webview);
+    let entries = normalize_batch(payload, &scope, Access::Write);
+    let outcomes = run_batch(&app, entries, move |app, normalized| {
+        write_batch_entry(app, cancel.clone(), normalized, "save", execute_save)
+    })
+    .await?;
+    Ok(finish_write_batch(&app, outcomes))
+}
+
+/// Normalizes every batch entry, keeping a failure with the entry it
+/// belongs to.
+fn normalize_batch(
+    payload: BatchPayload,
+    scope: &Scope,
+    access: Access,
+) -> Vec<(String, Result<NormalizedConfiguratePayload>)> {
+    payload
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let normalized = entry.payload.normalize().map(|p| p.scoped(scope, access));
+            (entry.id, normalized)
+        })
+        .collect()
+}
+
+/// Runs `run` for every batch entry and returns the outcomes in payload
+/// order.  Entries on distinct files run concurrently, at most
+/// [`Builder::batch_parallelism`](crate::Builder::batch_parallelism) files at
+/// a time; entries on the same file run one after another in payload order.
+async fn run_batch<R, T, F, Fut>(
+    app: &AppHandle<R>,
+    entries: Vec<(String, Result<NormalizedConfiguratePayload>)>,
+    run: F,
+) -> Result<Vec<(String, Result<T>)>>
+where
+    R: Runtime,
+    T: Send + 'static,
+    F: Fn(AppHandle<R>, NormalizedConfiguratePayload) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+{
+    let items = entries
+        .into_iter()
+        .map(|(id, normalized)| {
+            let path = normalized
+                .as_ref()
+                .ok()
+                .and_then(|p| resolve_file_path(app, p).ok());
+            (path, (id, normalized))
+        })
+        .collect();
+    let parallelism = config::batch_parallelism(app);
+    let app = app.clone();
+    tasks::run_grouped(items, parallelism, move |(id, normalized)| {
+        let (app, run) = (app.clone(), run.clone());
+        async move {
+            let outcome = match normalized {
+                Ok(normalized) => run(app, normalized).await,
+                Err(error) => Err(error),
+            };
+            (id, outcome)
+        }
+    })
+    .await
+}
+
+/// Loads one `load_all` entry.
+async fn load_batch_entry<R: Runtime>(
+    app: AppHandle<R>,
+    cancel: CancelToken,
+    normalized: NormalizedConfiguratePayload,
+) -> Result<LoadedData> {
+    prefetch_keys(&app, &normalized, false).await;
+    run_locked(&app, &cancel, normalized, execute_load).await
+}
+
+/// Writes one `save_all` / `patch_all` entry with `execute` and returns the
+/// result together with the change event to emit.
+async fn write_batch_entry<R, F>(
+    app: AppHandle<R>,
+    cancel: CancelToken,
+    normalized: NormalizedConfiguratePayload,
+    operation: &'static str,
+    execute: F,
+) -> Result<(Value, ConfigChangeEvent)>
+where
+    R: Runtime,
+    F: FnOnce(&AppHandle<R>, NormalizedConfiguratePayload) -> Result<Value> + Send + 'static,
+{
+    config::validate_writable(&app, normalized.base_dir)?;
+    let change_event = build_change_event(&normalized, operation);
+    prefetch_keys(&app, &normalized, true).await;
+    let data = run_locked(&app, &cancel, normalized, execute).await?;
+    Ok((data, change_event))
+}
 
+/// Builds the `save_all` / `patch_all` result and emits the change events of
+/// the successful entries in payload order.
+fn finish_write_batch<R: Runtime>(
+    app: &AppHandle<R>,
+    outcomes: Vec<(String, Result<(Value, ConfigChangeEvent)>)>,
+) -> BatchRunResult {
     let mut results = BTreeMap::new();
     let mut change_events = Vec::new();
 
-    for entry in payload.entries {
-        let crate::models::BatchEntryPayload { id, payload } = entry;
-        let entry_result = match payload.normalize().and_then(|p| {
-            let change_event = build_change_event(&p, "save");
-            let lock = acquire_file_lock(&app, &p);
-            let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-            let result = execute_save(&app, p)?;
-            change_events.push(change_event);
-            Ok(result)
-        }) {
-            Ok(data) => BatchEntryResult::Success(BatchEntrySuccess { ok: true, data }),
+    for (id, outcome) in outcomes {
+        let entry_result = match outcome {
+            Ok((data, change_event)) => {
+                change_events.push(change_event);
+                BatchEntryResult::Success(BatchEntrySuccess {
+                    ok: true,
+                    data,
+                    metadata: None,
+                })
+            }
             Err(error) => BatchEntryResult::Failure(BatchEntryFailure {
                 ok: false,
                 error: to_batch_error_value(&error),
@@ -768,56 +1424,55 @@ pub(crate) async fn save_all<R: Runtime>(
     }
 
     for change_event in change_events {
-        emit_change(&app, change_event);
+        emit_change(app, change_event);
     }
 
-    Ok(BatchRunResult { results })
+    BatchRunResult { results }
 }
 
-/// Deep-merges `patch` into `base`. Object keys are merged recursively;
-/// all other values are replaced.
-fn deep_merge(base: &mut Value, patch: Value) {
-    match (base, patch) {
-        (Value::Object(base_map), Value::Object(patch_map)) => {
-            for (key, patch_val) in patch_map {
-                let entry = base_map
-                    .entry(key)
-                    .or_insert(Value::Null);
-                deep_merge(entry, patch_val);
-            }
-        }
-        (base, patch) => {
-            *base = patch;
-        }
-    }
-}
-
-fn execute_patch<R: Runtime>(
+/// Loads the current data for a read-modify-write operation.  A missing file
+/// is an error unless the payload sets `createIfMissing`.
+fn load_for_update<R: Runtime>(
     app: &AppHandle<R>,
-    mut payload: NormalizedConfiguratePayload,
+    payload: &NormalizedConfiguratePayload,
 ) -> Result<Value> {
-    let mut existing = match load_plain_data(app, &payload) {
-        Ok(data) => data,
+    match load_plain_data(app, payload) {
+        Ok(data) => Ok(data),
         Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
             if payload.create_if_missing {
-                Value::Object(serde_json::Map::new())
+                Ok(Value::Object(serde_json::Map::new()))
             } else {
-                return Err(Error::InvalidPayload(format!(
+                Err(Error::InvalidPayload(format!(
                     "config '{}' does not exist; use create() or save() to create it \
                      first, or call .createIfMissing() on the patch entry",
                     payload.file_name
-                )));
+                )))
             }
         }
-        Err(e) => return Err(e),
-    };
+        Err(e) => Err(e),
+    }
+}
+
+fn execute_patch<R: Runtime>(
+    app: &AppHandle<R>,
+    mut payload: NormalizedConfiguratePayload,
+) -> Result<Value> {
+    let strategies = patch::ArrayStrategies::parse(&payload.array_merge)?;
+    let mut existing = load_for_update(app, &payload)?;
 
     let patch_data = payload
         .data
         .take()
         .unwrap_or(Value::Object(serde_json::Map::new()));
 
-    deep_merge(&mut existing, patch_data);
+    let policy = policy::for_payload(app, &payload)?;
+    let stored = (config::env_overrides(app).is_some() || policy.is_some())
+        .then(|| existing.clone());
+    patch::apply(&mut existing, patch_data, payload.patch_format, &strategies)?;
+    strip_env_overrides(app, stored.as_ref(), &mut existing)?;
+    if let Some(policy) = &policy {
+        policy.enforce(stored.as_ref(), &mut existing)?;
+    }
 
     let unlocked_data = if payload.with_unlock && payload.return_data {
         Some(existing.clone())
@@ -832,101 +1487,383 @@ fn execute_patch<R: Runtime>(
         &payload.keyring_options,
     )?;
 
+    let keyring = plan_keyring_write(app, &payload, keyring, &existing)?;
+
     // Nullify secrets in the on-disk data before saving.
-    if let Some((entries, _opts)) = &keyring {
-        for entry in *entries {
+    if let Some(write) = &keyring {
+        for entry in &write.entries {
             dotpath::nullify(&mut existing, &entry.dotpath)?;
         }
     }
 
-    // Persist plain data first so that if it fails, the keyring is not updated.
-    save_plain_data(app, &payload, &existing)?;
+    check_keyring_sizes(app, keyring.as_ref())?;
+
+    // Persist plain data first so that if it fails, the keyring is not updated.
+    save_plain_data(app, &payload, &existing)?;
+
+    // Only write secrets to the OS keyring after successful storage write.
+    persist_keyring(app, &payload, keyring.as_ref())?;
+    if payload.return_data {
+        Ok(unlocked_data.unwrap_or(existing))
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+#[command]
+pub(crate) async fn patch<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: ConfiguratePayload,
+    scope: Scope,
+) -> Result<Value> {
+    let normalized = payload.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
+    let change_event = build_change_event(&normalized, "patch");
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    // Patch is a read-then-write; lock the file for the full duration.
+    let result = run_locked(&app, &cancel, normalized, execute_patch).await?;
+    emit_change(&app, change_event);
+    Ok(result)
+}
+
+/// Path-scoped commands never touch the keyring; secrets go through
+/// `save` / `patch` with `lock()` and `unlock`.
+fn validate_value_keyring_policy(
+    command: &str,
+    payload: &NormalizedConfiguratePayload,
+) -> Result<()> {
+    if payload.keyring_entries.is_some() || payload.keyring_options.is_some() {
+        return Err(Error::InvalidPayload(format!(
+            "{} cannot access the keyring; use save/patch with lock() or unlock() instead",
+            command
+        )));
+    }
+    Ok(())
+}
+
+fn execute_get_value<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: NormalizedConfiguratePayload,
+    path: &str,
+) -> Result<Option<Value>> {
+    validate_value_keyring_policy("get_value", &payload)?;
+    dotpath::validate_path(path)?;
+    // Same view as `load`: environment overrides and locked keys applied.
+    let loaded = execute_load(app, payload)?;
+    Ok(dotpath::get(&loaded.data, path)?.cloned())
+}
+
+fn execute_set_value<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: NormalizedConfiguratePayload,
+    path: &str,
+    value: Value,
+) -> Result<()> {
+    validate_value_keyring_policy("set_value", &payload)?;
+    dotpath::validate_path(path)?;
+    let mut data = load_for_update(app, &payload)?;
+    let stored = data.clone();
+    dotpath::set(&mut data, path, value)?;
+    if let Some(policy) = policy::for_payload(app, &payload)? {
+        policy.enforce(Some(&stored), &mut data)?;
+    }
+    save_plain_data(app, &payload, &data)
+}
+
+/// Returns whether a value existed at `path` (and was removed).  Nothing is
+/// written when the file or the path does not exist.
+fn execute_remove_value<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: NormalizedConfiguratePayload,
+    path: &str,
+) -> Result<bool> {
+    validate_value_keyring_policy("remove_value", &payload)?;
+    dotpath::validate_path(path)?;
+    let mut data = match load_plain_data(app, &payload) {
+        Ok(data) => data,
+        Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let stored = data.clone();
+    if dotpath::remove(&mut data, path)?.is_none() {
+        return Ok(false);
+    }
+    if let Some(policy) = policy::for_payload(app, &payload)? {
+        policy.enforce(Some(&stored), &mut data)?;
+    }
+    save_plain_data(app, &payload, &data)?;
+    Ok(true)
+}
+
+/// Reads the value at one dotpath without sending the whole config over IPC.
+/// Returns `null` when the path does not exist.
+#[command]
+pub(crate) async fn get_value<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: ValuePayload,
+    scope: Scope,
+) -> Result<Option<Value>> {
+    let normalized = payload.target.normalize()?.scoped(&scope, Access::Read);
+    prefetch_keys(&app, &normalized, false).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let dotpath = payload.dotpath;
+    run_locked(&app, &cancel, normalized, move |app, normalized| {
+        execute_get_value(app, normalized, &dotpath)
+    })
+    .await
+}
 
-    // Only write secrets to the OS keyring after successful storage write.
-    if let Some((entries, opts)) = keyring {
-        write_keyring_entries(opts, entries)?;
-    }
+/// Writes one value at a dotpath, creating intermediate objects/arrays.
+#[command]
+pub(crate) async fn set_value<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: ValuePayload,
+    scope: Scope,
+) -> Result<()> {
+    let ValuePayload {
+        target,
+        dotpath,
+        value,
+    } = payload;
+    let normalized = target.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
+    let mut change_event = build_change_event(&normalized, "set");
+    change_event.path = Some(dotpath.clone());
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    run_locked(&app, &cancel, normalized, move |app, normalized| {
+        execute_set_value(app, normalized, &dotpath, value)
+    })
+    .await?;
+    emit_change(&app, change_event);
+    Ok(())
+}
 
-    cleanup_stale_keyring_entries(
-        &payload.keyring_delete_ids,
-        payload.keyring_options.as_ref(),
-    )?;
-    if payload.return_data {
-        Ok(unlocked_data.unwrap_or(existing))
-    } else {
-        Ok(Value::Null)
+/// Removes the value at a dotpath.  Returns `false` when there was nothing
+/// to remove, in which case no file is written and no event is emitted.
+#[command]
+pub(crate) async fn remove_value<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: ValuePayload,
+    scope: Scope,
+) -> Result<bool> {
+    let normalized = payload.target.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
+    let mut change_event = build_change_event(&normalized, "remove");
+    change_event.path = Some(payload.dotpath.clone());
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let dotpath = payload.dotpath;
+    let removed = run_locked(&app, &cancel, normalized, move |app, normalized| {
+        execute_remove_value(app, normalized, &dotpath)
+    })
+    .await?;
+    if removed {
+        emit_change(&app, change_event);
     }
+    Ok(removed)
 }
 
+/// Loads every layer of a layered config and returns the merged view with
+/// per-key provenance.
 #[command]
-pub(crate) async fn patch<R: Runtime>(
+pub(crate) async fn load_layered<R: Runtime>(
     app: AppHandle<R>,
-    payload: ConfiguratePayload,
-) -> Result<Value> {
-    let normalized = payload.normalize()?;
-    let change_event = build_change_event(&normalized, "patch");
-    // Patch is a read-then-write; lock the file for the full duration.
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    let result = execute_patch(&app, normalized)?;
-    drop(_guard);
-    emit_change(&app, change_event);
-    Ok(result)
+    webview: Webview<R>,
+    payload: LayeredPayload,
+    scope: Scope,
+) -> Result<LayeredData> {
+    let (layered, _) = payload.into_parts();
+    let prepared = PreparedLayers::for_load(layered, Some(&scope))?;
+    let locks = prepared.locks(&app);
+    let cancel = CancelToken::for_webview(&webview);
+    tasks::run_locked(locks, &cancel, move || prepared.load(&app)).await
+}
+
+/// Writes `data` to the write layer of a layered config (as the difference
+/// from the layers below it) and returns the new merged view.
+#[command]
+pub(crate) async fn save_layered<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: LayeredPayload,
+    scope: Scope,
+) -> Result<LayeredData> {
+    let (layered, data) = payload.into_parts();
+    let data = data.ok_or_else(|| {
+        Error::InvalidPayload("save_layered requires data".to_string())
+    })?;
+    let prepared = PreparedLayers::for_save(&app, layered, Some(&scope))?;
+    let locks = prepared.locks(&app);
+    let cancel = CancelToken::for_webview(&webview);
+    tasks::run_locked(locks, &cancel, move || prepared.save(&app, data)).await
 }
 
 /// Reads keyring secrets and inlines them into already-loaded plain data,
 /// returning the fully unlocked config **without re-reading the file from disk**.
 #[command]
-pub(crate) async fn unlock(payload: UnlockPayload) -> Result<Value> {
-    let mut data = payload.data;
-    if let Some((entries, opts)) =
-        keyring_pair(
+pub(crate) async fn unlock<R: Runtime>(
+    app: AppHandle<R>,
+    payload: UnlockPayload,
+    scope: Scope,
+) -> Result<Value> {
+    authorize_unlock(payload.target, &scope)?;
+    tasks::blocking(move || {
+        let mut data = payload.data;
+        if let Some((entries, opts)) = keyring_pair(
             "unlock",
             KeyringEntryUse::Read,
             &payload.keyring_entries,
             &payload.keyring_options,
-        )?
-    {
-        apply_keyring_reads(&mut data, entries, opts)?;
-    }
-    Ok(data)
+        )? {
+            let store = config::secret_store(&app);
+            apply_keyring_reads(store.as_ref(), &mut data, entries, opts)?;
+        }
+        Ok(data)
+    })
+    .await
+}
+
+/// Checks that the window may read the secrets of the config `unlock` is
+/// called for.
+pub(crate) fn authorize_unlock(target: ConfiguratePayload, scope: &Scope) -> Result<()> {
+    let target = target.normalize()?;
+    validate_file_name(&target.file_name)?;
+    scope.check(&target, Access::Keyring)
 }
 
+/// Reports the keyring ids tracked in the manifest of a config and whether a
+/// secret currently exists for each.  Secret values are never returned.
 #[command]
-pub(crate) async fn patch_all<R: Runtime>(
+pub(crate) async fn keyring_list<R: Runtime>(
     app: AppHandle<R>,
-    payload: BatchPayload,
-) -> Result<BatchRunResult> {
-    validate_batch_ids(&payload)?;
-
-    let mut results = BTreeMap::new();
-    let mut change_events = Vec::new();
+    webview: Webview<R>,
+    payload: ConfiguratePayload,
+    scope: Scope,
+) -> Result<Vec<KeyringListEntry>> {
+    let normalized = payload.normalize()?.scoped(&scope, Access::Keyring);
+    let cancel = CancelToken::for_webview(&webview);
+    run_locked(&app, &cancel, normalized, |app, normalized| {
+        let path = keyring_manifest::manifest_path(&resolve_file_path(app, &normalized)?);
+        let manifest = KeyringManifest::load(&path)?;
+        let store = config::secret_store(app);
+
+        manifest
+            .entries(normalized.keyring_options.as_ref())
+            .into_iter()
+            .map(|entry| {
+                let opts = manifest_entry_options(&entry);
+                let exists =
+                    keyring_store::get_optional(store.as_ref(), &opts, &entry.id)?.is_some();
+                Ok(KeyringListEntry { entry, exists })
+            })
+            .collect()
+    })
+    .await
+}
 
-    for entry in payload.entries {
-        let crate::models::BatchEntryPayload { id, payload } = entry;
-        let entry_result = match payload.normalize().and_then(|p| {
-            let change_event = build_change_event(&p, "patch");
-            let lock = acquire_file_lock(&app, &p);
-            let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-            let result = execute_patch(&app, p)?;
-            change_events.push(change_event);
-            Ok(result)
-        }) {
-            Ok(data) => BatchEntryResult::Success(BatchEntrySuccess { ok: true, data }),
-            Err(error) => BatchEntryResult::Failure(BatchEntryFailure {
-                ok: false,
-                error: to_batch_error_value(&error),
-            }),
+/// Deletes keyring entries tracked in the manifest whose id is not in
+/// `keepIds` (the ids currently declared in the schema).  Id templates in
+/// `keepIds` keep every id they could have rendered.
+#[command]
+pub(crate) async fn keyring_gc<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: KeyringGcPayload,
+    scope: Scope,
+) -> Result<KeyringGcResult> {
+    validate_keyring_gc_keep_ids(&payload)?;
+    let normalized = payload.target.normalize()?.scoped(&scope, Access::Keyring);
+    let cancel = CancelToken::for_webview(&webview);
+    run_locked(&app, &cancel, normalized, move |app, normalized| {
+        let path = keyring_manifest::manifest_path(&resolve_file_path(app, &normalized)?);
+        let mut manifest = KeyringManifest::load(&path)?;
+        let store = config::secret_store(app);
+        let (templates, keep): (Vec<&str>, BTreeSet<&str>) = {
+            let (templates, ids): (Vec<&String>, Vec<&String>) = payload
+                .keep_ids
+                .iter()
+                .partition(|id| keyring_store::is_id_template(id));
+            (
+                templates.into_iter().map(String::as_str).collect(),
+                ids.into_iter().map(String::as_str).collect(),
+            )
         };
 
-        results.insert(id, entry_result);
+        let mut result = KeyringGcResult::default();
+        for entry in manifest.entries(normalized.keyring_options.as_ref()) {
+            if keep.contains(entry.id.as_str())
+                || templates
+                    .iter()
+                    .any(|template| keyring_store::template_matches(template, &entry.id))
+            {
+                continue;
+            }
+            let opts = manifest_entry_options(&entry);
+            if payload.dry_run {
+                result.removed.push(entry);
+                continue;
+            }
+            match keyring_store::delete(store.as_ref(), &opts, &entry.id) {
+                Ok(()) => {
+                    manifest.forget(&opts, [entry.id.as_str()]);
+                    result.removed.push(entry);
+                }
+                Err(error) => result.failed.push(KeyringGcFailure {
+                    entry,
+                    error: to_batch_error_value(&error),
+                }),
+            }
+        }
+
+        if !payload.dry_run {
+            manifest.save(&path)?;
+        }
+        Ok(result)
+    })
+    .await
+}
+
+/// Refuses an empty keep set, which would delete every tracked secret,
+/// unless the caller confirmed it with `all` or only asked for a dry run.
+fn validate_keyring_gc_keep_ids(payload: &KeyringGcPayload) -> Result<()> {
+    if payload.keep_ids.is_empty() && !payload.all && !payload.dry_run {
+        return Err(Error::InvalidPayload(
+            "keyring_gc with empty keepIds would delete every tracked secret; pass \
+             all: true to confirm or dryRun: true to preview"
+                .to_string(),
+        ));
     }
+    validate_keyring_delete_ids(&payload.keep_ids)
+}
 
-    for change_event in change_events {
-        emit_change(&app, change_event);
+fn manifest_entry_options(entry: &KeyringManifestEntry) -> KeyringOptions {
+    KeyringOptions {
+        service: entry.service.clone(),
+        account: entry.account.clone(),
     }
+}
 
-    Ok(BatchRunResult { results })
+#[command]
+pub(crate) async fn patch_all<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: BatchPayload,
+    scope: Scope,
+) -> Result<BatchRunResult> {
+    validate_batch_ids(&payload)?;
+    let cancel = CancelToken::for_webview(&webview);
+    let entries = normalize_batch(payload, &scope, Access::Write);
+    let outcomes = run_batch(&app, entries, move |app, normalized| {
+        write_batch_entry(app, cancel.clone(), normalized, "patch", execute_patch)
+    })
+    .await?;
+    Ok(finish_write_batch(&app, outcomes))
 }
 
 /// Begins watching a file for external changes and emits `configurate://change`
@@ -936,12 +1873,16 @@ pub(crate) async fn patch_all<R: Runtime>(
 pub(crate) async fn watch_file<R: Runtime>(
     app: AppHandle<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<()> {
-    let normalized = payload.normalize()?;
-    let path = resolve_file_path(&app, &normalized)?;
-    let change_event = build_change_event(&normalized, "external_change");
-    let watcher = app.state::<crate::watcher::WatcherState>();
-    watcher.watch(path, change_event)
+    let normalized = payload.normalize()?.scoped(&scope, Access::Read);
+    tasks::blocking(move || {
+        let path = resolve_file_path(&app, &normalized)?;
+        let change_event = build_change_event(&normalized, "external_change");
+        let watcher = app.state::<crate::watcher::WatcherState>();
+        watcher.watch(path, change_event)
+    })
+    .await
 }
 
 /// Stops watching a file that was previously registered via `watch_file`.
@@ -951,10 +1892,13 @@ pub(crate) async fn unwatch_file<R: Runtime>(
     payload: ConfiguratePayload,
 ) -> Result<()> {
     let normalized = payload.normalize()?;
-    let path = resolve_file_path(&app, &normalized)?;
-    let target_id = change_target_id(&normalized);
-    let watcher = app.state::<crate::watcher::WatcherState>();
-    watcher.unwatch(&path, &target_id)
+    tasks::blocking(move || {
+        let path = resolve_file_path(&app, &normalized)?;
+        let target_id = change_target_id(&normalized);
+        let watcher = app.state::<crate::watcher::WatcherState>();
+        watcher.unwatch(&path, &target_id)
+    })
+    .await
 }
 
 /// Returns `true` if `name` is a rotating-backup file (ends with `.bakN`
@@ -977,7 +1921,12 @@ fn should_list_config_file(name: &str, path: &std::path::Path, ext: Option<&str>
     if name.starts_with('.') && name.ends_with(".tmp") {
         return false;
     }
+    if keyring_manifest::is_manifest_filename(name) {
+        return false;
+    }
     match ext {
+        // `.env`, `.env.local` and `production.env`.
+        Some("env") => name == ".env" || name.starts_with(".env.") || name.ends_with(".env"),
         Some(e) => path.extension().is_some_and(|x| x == e),
         None => true,
     }
@@ -988,38 +1937,47 @@ fn should_list_config_file(name: &str, path: &std::path::Path, ext: Option<&str>
 pub(crate) async fn list_configs<R: Runtime>(
     app: AppHandle<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<Vec<String>> {
     let normalized = payload.normalize()?;
-
-    let root = resolve_root(
-        &app,
-        normalized.base_dir,
-        normalized.dir_name.as_deref(),
-        normalized.current_path.as_deref(),
-    )?;
-    let ext = match &normalized.provider {
-        NormalizedProvider::Json => Some("json"),
-        NormalizedProvider::Yml => Some("yml"),
-        NormalizedProvider::Toml => Some("toml"),
-        NormalizedProvider::Binary { .. } => None,
-    };
-    let mut names = Vec::new();
-    if root.is_dir() {
-        for entry in std::fs::read_dir(&root)? {
-            let entry = entry?;
-            let path = entry.path();
-            if !path.is_file() {
-                continue;
-            }
-            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                if should_list_config_file(name, &path, ext) {
-                    names.push(name.to_string());
+    tasks::blocking(move || {
+        let root = resolve_root(
+            &app,
+            normalized.base_dir,
+            normalized.dir_name.as_deref(),
+            normalized.current_path.as_deref(),
+        )?;
+        let ext = match &normalized.provider {
+            NormalizedProvider::Json => Some("json"),
+            NormalizedProvider::Yml => Some("yml"),
+            NormalizedProvider::Toml => Some("toml"),
+            NormalizedProvider::Ini => Some("ini"),
+            NormalizedProvider::Dotenv => Some("env"),
+            NormalizedProvider::Xml => Some("xml"),
+            NormalizedProvider::Plist => Some("plist"),
+            NormalizedProvider::Binary { .. } => None,
+        };
+        let mut names = Vec::new();
+        if root.is_dir() {
+            for entry in std::fs::read_dir(&root)? {
+                let entry = entry?;
+                let path = entry.path();
+                if !path.is_file() {
+                    continue;
+                }
+                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                    if should_list_config_file(name, &path, ext)
+                        && scope.permits_listing(&normalized, name)
+                    {
+                        names.push(name.to_string());
+                    }
                 }
             }
         }
-    }
-    names.sort();
-    Ok(names)
+        names.sort();
+        Ok(names)
+    })
+    .await
 }
 
 /// Resets a config by deleting the existing data and re-creating it with
@@ -1027,16 +1985,21 @@ pub(crate) async fn list_configs<R: Runtime>(
 #[command]
 pub(crate) async fn reset<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ConfiguratePayload,
+    scope: Scope,
 ) -> Result<Value> {
-    let normalized = payload.normalize()?;
+    let normalized = payload.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
     let change_event = build_change_event(&normalized, "reset");
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    // Delete then create = reset. Propagate errors so failures are not silently ignored.
-    delete_plain_data(&app, &normalized)?;
-    let result = execute_create(&app, normalized)?;
-    drop(_guard);
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let result = run_locked(&app, &cancel, normalized, |app, normalized| {
+        // Delete then create = reset. Propagate errors so failures are not silently ignored.
+        delete_plain_data(app, &normalized)?;
+        execute_create(app, normalized)
+    })
+    .await?;
     emit_change(&app, change_event);
     Ok(result)
 }
@@ -1050,35 +2013,86 @@ pub struct ExportPayload {
 }
 
 /// Exports a config from its current provider format to a different format
-/// string (JSON / YML / TOML).
+/// string (JSON / YML / TOML / INI / .env / XML / plist, or base64 CBOR /
+/// MessagePack).  With a `channel` the text is streamed in chunks and the
+/// command itself returns an empty string.  Only JSON and YAML are sent
+/// while they are serialized; the other formats are rendered in full
+/// before the first chunk is sent.
 #[command]
 pub(crate) async fn export_config<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ExportPayload,
+    scope: Scope,
+    channel: Option<JavaScriptChannelId>,
 ) -> Result<String> {
-    let normalized = payload.source.normalize()?;
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    let data = match normalized.data.clone() {
-        Some(data) => data,
-        None => execute_load(&app, normalized)?,
-    };
+    let normalized = payload.source.normalize()?.scoped(&scope, Access::Read);
+    prefetch_keys(&app, &normalized, false).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let target_format = payload.target_format;
+    let data = run_locked(&app, &cancel, normalized, move |app, normalized| {
+        match normalized.data.clone() {
+            Some(data) => Ok(data),
+            // Export the stored config: environment overrides are not part of it.
+            None => {
+                validate_load_keyring_policy(&normalized)?;
+                load_plain_data(app, &normalized)
+            }
+        }
+    })
+    .await?;
+    match channel {
+        Some(channel) => {
+            let channel = channel.channel_on(webview);
+            tasks::blocking(move || {
+                let mut writer = streaming::ChunkWriter::new(&channel);
+                write_export(&data, &target_format, &mut writer)?;
+                writer.finish()
+            })
+            .await?;
+            Ok(String::new())
+        }
+        None => {
+            tasks::blocking(move || {
+                let mut text = Vec::new();
+                write_export(&data, &target_format, &mut text)?;
+                String::from_utf8(text).map_err(|e| Error::Storage(e.to_string()))
+            })
+            .await
+        }
+    }
+}
 
-    match payload.target_format.as_str() {
-        "json" => serde_json::to_string_pretty(&data)
-            .map_err(|e| Error::Storage(e.to_string())),
-        "yml" | "yaml" => serde_yml::to_string(&data)
-            .map_err(|e| Error::Storage(e.to_string())),
-        "toml" => {
-            let toml_val = storage::json_to_toml(&data)?;
-            toml::to_string_pretty(&toml_val)
+/// Renders `data` in `format` into `out`.  JSON and YAML are serialized
+/// straight into `out`; the other formats are rendered in full first, so a
+/// rendering error never leaves partial output behind.
+fn write_export(data: &Value, format: &str, out: &mut impl std::io::Write) -> Result<()> {
+    let text = match format {
+        "json" => {
+            return serde_json::to_writer_pretty(out, data)
                 .map_err(|e| Error::Storage(e.to_string()))
         }
-        other => Err(Error::InvalidPayload(format!(
-            "unsupported export format '{}': expected json, yml, or toml",
-            other
-        ))),
-    }
+        "yml" | "yaml" => {
+            return serde_yml::to_writer(out, data).map_err(|e| Error::Storage(e.to_string()))
+        }
+        "toml" => toml_value::render(data, None)?,
+        "ini" => keyvalue::render(data, Dialect::Ini)?,
+        "env" | "dotenv" => keyvalue::render(data, Dialect::Dotenv)?,
+        "xml" => xml::render(data)?,
+        "plist" => String::from_utf8(property_list::render(data, None, false)?)
+            .map_err(|e| Error::Storage(e.to_string()))?,
+        other => match encoding::for_format(other) {
+            Some(encoding) => encoding::to_base64(data, encoding)?,
+            None => {
+                return Err(Error::InvalidPayload(format!(
+                    "unsupported export format '{}': expected json, yml, toml, ini, dotenv, xml, plist, cbor, or msgpack",
+                    other
+                )))
+            }
+        },
+    };
+    out.write_all(text.as_bytes())
+        .map_err(|e| Error::Storage(e.to_string()))
 }
 
 /// Import payload sent from TypeScript side.
@@ -1088,10 +2102,21 @@ pub struct ImportPayload {
     pub target: ConfiguratePayload,
     pub source_format: Option<String>,
     pub content: Option<String>,
+    /// Id returned by `import_chunk`; the uploaded content replaces `content`.
+    pub upload_id: Option<String>,
     #[serde(default)]
     pub parse_only: bool,
 }
 
+/// One piece of import content sent to `import_chunk`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportChunkPayload {
+    /// Upload to append to; `None` starts a new upload.
+    pub upload_id: Option<String>,
+    pub chunk: String,
+}
+
 fn parse_import_content(format: &str, content: &str, max_read_bytes: usize) -> Result<Value> {
     if content.len() > max_read_bytes {
         return Err(Error::InvalidPayload(format!(
@@ -1107,42 +2132,72 @@ fn parse_import_content(format: &str, content: &str, max_read_bytes: usize) -> R
                 serde_yml::from_str(content).map_err(|e| Error::Storage(e.to_string()))?;
             serde_json::to_value(yaml_val).map_err(|e| Error::Storage(e.to_string()))
         }
-        "toml" => {
-            let toml_val: toml::Value =
-                toml::from_str(content).map_err(|e| Error::Storage(e.to_string()))?;
-            serde_json::to_value(toml_val).map_err(|e| Error::Storage(e.to_string()))
-        }
-        other => Err(Error::InvalidPayload(format!(
-            "unsupported import format '{}': expected json, yml, or toml",
-            other
-        ))),
+        "toml" => toml_value::parse(content),
+        "ini" => keyvalue::parse(content, Dialect::Ini),
+        "env" | "dotenv" => keyvalue::parse(content, Dialect::Dotenv),
+        "xml" => xml::parse(content),
+        "plist" => property_list::parse(content.as_bytes()),
+        other => match encoding::for_format(other) {
+            Some(encoding) => encoding::from_base64(content, encoding),
+            None => Err(Error::InvalidPayload(format!(
+                "unsupported import format '{}': expected json, yml, toml, ini, dotenv, xml, plist, cbor, or msgpack",
+                other
+            ))),
+        },
     }
 }
 
+/// Appends a chunk of import content and returns the upload id to pass to
+/// further `import_chunk` calls and finally to `import_config`.  The upload
+/// is rejected as soon as it exceeds `max_read_bytes`.
+#[command]
+pub(crate) async fn import_chunk<R: Runtime>(
+    app: AppHandle<R>,
+    webview: Webview<R>,
+    payload: ImportChunkPayload,
+) -> Result<String> {
+    let uploads = app.state::<ImportUploads>();
+    uploads.append(
+        webview.window().label(),
+        payload.upload_id.as_deref(),
+        &payload.chunk,
+        config::max_read_bytes(&app),
+    )
+}
+
 /// Imports config data from a string in the given format, saving it to the
 /// target config location.
 #[command]
 pub(crate) async fn import_config<R: Runtime>(
     app: AppHandle<R>,
+    webview: Webview<R>,
     payload: ImportPayload,
+    scope: Scope,
 ) -> Result<Value> {
     let ImportPayload {
         target,
         source_format,
         content,
+        upload_id,
         parse_only,
     } = payload;
 
+    let content = match upload_id {
+        Some(id) => Some(
+            app.state::<ImportUploads>()
+                .take(webview.window().label(), &id)?,
+        ),
+        None => content,
+    };
     let data: Value = match target.data.clone() {
         Some(data) => data,
         None => {
             let format = source_format
-                .as_deref()
                 .ok_or_else(|| Error::InvalidPayload("missing sourceFormat".to_string()))?;
-            let raw = content
-                .as_deref()
-                .ok_or_else(|| Error::InvalidPayload("missing content".to_string()))?;
-            parse_import_content(format, raw, config::max_read_bytes(&app))?
+            let raw =
+                content.ok_or_else(|| Error::InvalidPayload("missing content".to_string()))?;
+            let max_read_bytes = config::max_read_bytes(&app);
+            tasks::blocking(move || parse_import_content(&format, &raw, max_read_bytes)).await?
         }
     };
 
@@ -1150,17 +2205,24 @@ pub(crate) async fn import_config<R: Runtime>(
         return Ok(data);
     }
 
-    let mut normalized = target.normalize()?;
+    let mut normalized = target.normalize()?.scoped(&scope, Access::Write);
+    config::validate_writable(&app, normalized.base_dir)?;
     normalized.data = Some(data);
     let change_event = build_change_event(&normalized, "import");
-    let lock = acquire_file_lock(&app, &normalized);
-    let _guard = lock.as_ref().map(|l| l.lock().unwrap_or_else(|e| e.into_inner()));
-    let result = execute_save(&app, normalized)?;
-    drop(_guard);
+    prefetch_keys(&app, &normalized, true).await;
+    let cancel = CancelToken::for_webview(&webview);
+    let result = run_locked(&app, &cancel, normalized, execute_save).await?;
     emit_change(&app, change_event);
     Ok(result)
 }
 
+/// Returns read cache counters for debugging, or `null` when the cache is
+/// not enabled (`Builder::cache`).
+#[command]
+pub(crate) async fn cache_stats<R: Runtime>(app: AppHandle<R>) -> Result<Option<CacheStats>> {
+    Ok(config::cache_stats(&app))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1208,37 +2270,6 @@ mod tests {
         assert!(validate_batch_ids(&payload).is_err());
     }
 
-    #[test]
-    fn deep_merge_adds_new_keys() {
-        let mut base = json!({"a": 1});
-        deep_merge(&mut base, json!({"b": 2}));
-        assert_eq!(base, json!({"a": 1, "b": 2}));
-    }
-
-    #[test]
-    fn deep_merge_overwrites_scalar() {
-        let mut base = json!({"a": 1});
-        deep_merge(&mut base, json!({"a": 99}));
-        assert_eq!(base, json!({"a": 99}));
-    }
-
-    #[test]
-    fn deep_merge_nested_objects() {
-        let mut base = json!({"db": {"host": "localhost", "port": 5432}});
-        deep_merge(&mut base, json!({"db": {"port": 3306, "name": "mydb"}}));
-        assert_eq!(
-            base,
-            json!({"db": {"host": "localhost", "port": 3306, "name": "mydb"}})
-        );
-    }
-
-    #[test]
-    fn deep_merge_replaces_non_object_with_object() {
-        let mut base = json!({"a": "string"});
-        deep_merge(&mut base, json!({"a": {"nested": true}}));
-        assert_eq!(base, json!({"a": {"nested": true}}));
-    }
-
     #[test]
     fn resolve_root_path_stays_under_base_dir() {
         let base = PathBuf::from("/home/user/.config/com.example.app");
@@ -1349,6 +2380,17 @@ mod tests {
         assert!(validate_dir_name("my-app").is_ok());
     }
 
+    #[test]
+    fn split_path_segments_splits_on_both_separators() {
+        fn segments(path: &str) -> Vec<&str> {
+            split_path_segments(path).collect()
+        }
+        assert_eq!(segments("a/b\\c"), ["a", "b", "c"]);
+        assert_eq!(segments("a//b"), ["a", "", "b"]);
+        assert_eq!(segments("a\\"), ["a", ""]);
+        assert_eq!(segments("plain"), ["plain"]);
+    }
+
     #[test]
     fn validate_dir_name_rejects_empty_segments() {
         assert!(validate_dir_name("").is_err());
@@ -1422,6 +2464,39 @@ mod tests {
         assert!(err.to_string().contains("unlock command"));
     }
 
+    #[test]
+    fn value_commands_reject_keyring_payloads() {
+        let payload: ValuePayload = serde_json::from_value(json!({
+            "target": {
+                "fileName": "app.json",
+                "baseDir": 8,
+                "provider": { "kind": "json" },
+                "keyringOptions": { "service": "svc", "account": "acc" }
+            },
+            "dotpath": "token",
+            "value": "plain"
+        }))
+        .unwrap();
+        let normalized = payload.target.normalize().unwrap();
+        let err = validate_value_keyring_policy("set_value", &normalized).unwrap_err();
+        assert!(err.to_string().contains("set_value cannot access the keyring"));
+    }
+
+    #[test]
+    fn change_event_path_is_omitted_for_whole_file_operations() {
+        let payload: ConfiguratePayload = serde_json::from_value(json!({
+            "fileName": "app.json",
+            "baseDir": 8,
+            "provider": { "kind": "json" }
+        }))
+        .unwrap();
+        let normalized = payload.normalize().unwrap();
+        let mut event = build_change_event(&normalized, "save");
+        assert!(serde_json::to_value(&event).unwrap().get("path").is_none());
+        event.path = Some("db.host".into());
+        assert_eq!(serde_json::to_value(&event).unwrap()["path"], "db.host");
+    }
+
     // ── is_backup_filename ───────────────────────────────────────────────────
 
     #[test]
@@ -1497,6 +2572,23 @@ mod tests {
         assert!(!should_list_config_file("my.bakery.bin", &path, Some("json")));
     }
 
+    #[test]
+    fn should_list_config_file_matches_dotenv_names() {
+        let path = std::path::Path::new("unused");
+        for name in [".env", ".env.local", "production.env"] {
+            assert!(should_list_config_file(name, path, Some("env")), "{}", name);
+        }
+        for name in ["env.json", ".envrc", ".env.bak1"] {
+            assert!(!should_list_config_file(name, path, Some("env")), "{}", name);
+        }
+    }
+
+    #[test]
+    fn list_configs_filter_excludes_keyring_manifest() {
+        let path = std::path::Path::new("app.bin.keyring-manifest");
+        assert!(!should_list_config_file("app.bin.keyring-manifest", path, None));
+    }
+
     #[test]
     fn list_configs_filter_does_not_exclude_bakery_name() {
         // Ensures "my.bakery.json" is NOT mistakenly treated as a backup file.
@@ -1508,24 +2600,185 @@ mod tests {
         let entries = vec![KeyringEntry {
             id: "api-key".to_string(),
             dotpath: "apiKey".to_string(),
-            value: "secret".to_string(),
+            value: json!("secret"),
             is_optional: false,
         }];
         assert!(validate_keyring_entries(&entries, KeyringEntryUse::Read).is_err());
         assert!(validate_keyring_entries(&entries, KeyringEntryUse::Write).is_ok());
     }
 
+    #[test]
+    fn keyring_read_accepts_null_or_legacy_empty_value() {
+        for value in [Value::Null, json!("")] {
+            let entries = vec![KeyringEntry {
+                id: "api-key".to_string(),
+                dotpath: "apiKey".to_string(),
+                value,
+                is_optional: false,
+            }];
+            assert!(validate_keyring_entries(&entries, KeyringEntryUse::Read).is_ok());
+        }
+        let typed = vec![KeyringEntry {
+            id: "pin".to_string(),
+            dotpath: "pin".to_string(),
+            value: json!(0),
+            is_optional: false,
+        }];
+        assert!(validate_keyring_entries(&typed, KeyringEntryUse::Read).is_err());
+    }
+
+    fn wildcard_entry(id: &str, dotpath: &str) -> KeyringEntry {
+        KeyringEntry {
+            id: id.to_string(),
+            dotpath: dotpath.to_string(),
+            value: Value::Null,
+            is_optional: false,
+        }
+    }
+
+    #[test]
+    fn wildcard_keyring_entries_require_template_and_no_value_on_write() {
+        let untemplated = vec![wildcard_entry("server", "servers.*.password")];
+        assert!(validate_keyring_entries(&untemplated, KeyringEntryUse::Read).is_err());
+
+        let mut with_value = wildcard_entry("server-{index}", "servers.*.password");
+        with_value.value = json!("secret");
+        assert!(validate_keyring_entries(&[with_value], KeyringEntryUse::Write).is_err());
+
+        let ok = vec![wildcard_entry("server-{index}", "servers.*.password")];
+        assert!(validate_keyring_entries(&ok, KeyringEntryUse::Write).is_ok());
+    }
+
+    #[test]
+    fn wildcard_writes_take_values_from_data_and_skip_nulls() {
+        let data = json!({"servers": [
+            {"name": "a", "password": "pa"},
+            {"name": "b", "password": null},
+            {"name": "c", "password": {"user": "u", "pass": "p"}}
+        ]});
+        let entry = wildcard_entry("srv-{field:name}", "servers.*.password");
+        let writes = expand_wildcard_entries(&data, &[&entry], true).unwrap();
+        let ids: Vec<_> = writes.iter().map(|e| (e.id.as_str(), e.dotpath.as_str())).collect();
+        assert_eq!(ids, vec![("srv-a", "servers.0.password"), ("srv-c", "servers.2.password")]);
+        assert_eq!(writes[1].value, json!({"user": "u", "pass": "p"}));
+
+        let reads = expand_wildcard_entries(&data, &[&entry], false).unwrap();
+        assert_eq!(reads.len(), 3);
+        assert!(reads.iter().all(|e| e.value.is_null()));
+    }
+
+    #[test]
+    fn unlock_expands_wildcards_against_loaded_data() {
+        let store = crate::secret_store::MemorySecretStore::new();
+        let opts = KeyringOptions {
+            service: "svc".to_string(),
+            account: "acc".to_string(),
+        };
+        keyring_store::set(&store, &opts, "server-0", &json!("p0")).unwrap();
+        keyring_store::set(&store, &opts, "server-1", &json!(1234)).unwrap();
+
+        let mut data = json!({"servers": [{"password": null}, {"password": null}]});
+        let entries = vec![
+            wildcard_entry("server-{index}", "servers.*.password"),
+            wildcard_entry("server-{index}", "servers.*.password"),
+        ];
+        // The same template listed twice expands to duplicate ids.
+        assert!(apply_keyring_reads(&store, &mut data, &entries, &opts).is_err());
+
+        apply_keyring_reads(&store, &mut data, &entries[..1], &opts).unwrap();
+        assert_eq!(data, json!({"servers": [{"password": "p0"}, {"password": 1234}]}));
+    }
+
     #[test]
     fn keyring_read_rejects_invalid_dotpath() {
         let entries = vec![KeyringEntry {
             id: "api-key".to_string(),
             dotpath: ".apiKey".to_string(),
-            value: String::new(),
+            value: Value::String(String::new()),
             is_optional: false,
         }];
         assert!(validate_keyring_entries(&entries, KeyringEntryUse::Read).is_err());
     }
 
+    #[test]
+    fn removing_indexed_elements_requires_the_remaining_secrets() {
+        let entry = wildcard_entry("server-{index}", "servers.*.password");
+        let previous = json!({ "servers": [{ "host": "a" }, { "host": "b" }] });
+
+        // Two elements with the same non-secret fields, first one removed:
+        // the survivor must not inherit the removed element's secret.
+        let twins = json!({ "servers": [{ "host": "a" }, { "host": "a" }] });
+        let survivor = json!({ "servers": [{ "host": "a", "password": null }] });
+        let err = check_indexed_removals(&twins, &survivor, &[&entry]).unwrap_err();
+        assert!(matches!(err, Error::InvalidPayload(_)));
+        assert!(err.to_string().contains("servers.0.password"));
+
+        // Removing one server and editing another is fine once secrets are sent.
+        let resent = json!({ "servers": [{ "host": "b2", "password": "pw-b" }] });
+        check_indexed_removals(&previous, &resent, &[&entry]).unwrap();
+
+        // In-place edits and appends keep secrets at their positions.
+        let edited = json!({ "servers": [{ "host": "a2" }, { "host": "b" }, { "host": "c" }] });
+        check_indexed_removals(&previous, &edited, &[&entry]).unwrap();
+
+        // Other templates identify elements by content the caller controls.
+        let by_host = wildcard_entry("server-{field:host}", "servers.*.password");
+        check_indexed_removals(&previous, &survivor, &[&by_host]).unwrap();
+    }
+
+    #[test]
+    fn effective_data_applies_env_overrides_under_the_policy() {
+        let overrides = EnvOverrides::from_vars(
+            "MYAPP",
+            [
+                ("MYAPP__THEME".to_string(), "light".to_string()),
+                ("MYAPP__CHANNEL".to_string(), "beta".to_string()),
+            ],
+        )
+        .unwrap();
+        let policy = policy::Policy::new(json!({ "channel": "stable" })).unwrap();
+        let stored = json!({ "theme": "dark", "channel": "nightly", "size": 3 });
+
+        let loaded = effective_data(stored, Some(&overrides), Some(&policy)).unwrap();
+        // `get_value` reads from this view rather than the stored file.
+        assert_eq!(
+            dotpath::get(&loaded.data, "theme").unwrap(),
+            Some(&json!("light"))
+        );
+        assert_eq!(
+            dotpath::get(&loaded.data, "channel").unwrap(),
+            Some(&json!("stable"))
+        );
+        assert_eq!(dotpath::get(&loaded.data, "size").unwrap(), Some(&json!(3)));
+        assert_eq!(loaded.locked_keys, ["channel"]);
+        assert_eq!(loaded.env_overrides.len(), 1);
+        assert_eq!(loaded.env_overrides[0].path, "theme");
+    }
+
+    #[test]
+    fn keyring_gc_requires_confirmation_for_empty_keep_ids() {
+        let payload = |extra: Value| -> serde_json::Result<KeyringGcPayload> {
+            let mut payload = json!({
+                "target": { "fileName": "app.json", "baseDir": 8, "provider": { "kind": "json" } }
+            });
+            if let (Some(payload), Value::Object(extra)) = (payload.as_object_mut(), extra) {
+                payload.extend(extra);
+            }
+            serde_json::from_value(payload)
+        };
+
+        assert!(payload(json!({})).is_err(), "keepIds is required");
+        let empty = payload(json!({ "keepIds": [] })).unwrap();
+        assert!(validate_keyring_gc_keep_ids(&empty).is_err());
+        for extra in [
+            json!({ "keepIds": [], "all": true }),
+            json!({ "keepIds": [], "dryRun": true }),
+            json!({ "keepIds": ["db-password"] }),
+        ] {
+            assert!(validate_keyring_gc_keep_ids(&payload(extra).unwrap()).is_ok());
+        }
+    }
+
     #[test]
     fn import_content_over_limit_is_rejected() {
         let max_read_bytes = config::DEFAULT_MAX_READ_BYTES;
@@ -1533,4 +2786,15 @@ mod tests {
         let err = parse_import_content("json", &content, max_read_bytes).unwrap_err();
         assert!(err.to_string().contains("maximum size"));
     }
+
+    #[test]
+    fn import_content_accepts_xml_and_plist() {
+        let max_read_bytes = config::DEFAULT_MAX_READ_BYTES;
+        let xml = "<prefs><theme>dark</theme></prefs>";
+        let plist = "<plist version=\"1.0\"><dict><key>theme</key><string>dark</string></dict></plist>";
+        for (format, content) in [("xml", xml), ("plist", plist)] {
+            let value = parse_import_content(format, content, max_read_bytes).unwrap();
+            assert_eq!(value, serde_json::json!({"theme": "dark"}), "{}", format);
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
 use tauri::{
     path::BaseDirectory,
@@ -5,9 +7,18 @@ use tauri::{
     Manager, Runtime,
 };
 
+use crate::cache::{CacheStats, ReadCache};
+use crate::env_overrides::EnvOverrides;
 use crate::error::{Error, Result};
 use crate::locker;
+use crate::policy::{ManagedPolicy, PolicyState};
+use crate::secret_store::{
+    FallbackSecretStore, LazyVaultStore, OsKeyringStore, SecretStore,
+    SharedSecretStore, VaultKey, FALLBACK_VAULT_FILE,
+};
 use crate::storage;
+use crate::streaming;
+use crate::tasks;
 use crate::watcher;
 
 #[cfg(desktop)]
@@ -18,6 +29,9 @@ use crate::mobile;
 /// Default maximum bytes read from a config file or import content string (16 MiB).
 pub const DEFAULT_MAX_READ_BYTES: usize = 16_777_216;
 
+/// Default number of files a batch command works on at the same time.
+pub const DEFAULT_BATCH_PARALLELISM: usize = 4;
+
 /// Policy for which [`BaseDirectory`] values IPC payloads may use.
 #[derive(Debug, Clone)]
 pub enum BaseDirPolicy {
@@ -31,11 +45,34 @@ fn base_dir_id(dir: BaseDirectory) -> u16 {
     dir as u16
 }
 
+/// Whether IPC payloads may write to a [`BaseDirectory`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseDirAccess {
+    /// Configs may be loaded but not saved, patched, deleted, reset or imported into.
+    ReadOnly,
+    /// Configs may be read and written.
+    ReadWrite,
+}
+
 /// Resolved plugin settings stored in Tauri state.
 #[derive(Debug, Clone)]
 pub struct PluginSettings {
     pub max_read_bytes: usize,
     pub allowed_base_dirs: BaseDirPolicy,
+    /// Access mode per base directory (`BaseDirectory` discriminant values);
+    /// directories not listed are [`BaseDirAccess::ReadWrite`].
+    pub base_dir_access: BTreeMap<u16, BaseDirAccess>,
+    pub batch_parallelism: usize,
+}
+
+impl PluginSettings {
+    /// Returns the access mode configured for `base_dir`.
+    pub fn access_for(&self, base_dir: BaseDirectory) -> BaseDirAccess {
+        self.base_dir_access
+            .get(&base_dir_id(base_dir))
+            .copied()
+            .unwrap_or(BaseDirAccess::ReadWrite)
+    }
 }
 
 /// Plugin configuration from `tauri.conf.json` (`plugins.configurate`).
@@ -59,11 +96,23 @@ pub fn default_allowed_base_directories() -> Vec<BaseDirectory> {
     ]
 }
 
+/// Default access modes: bundle resources are read-only.
+fn default_base_dir_access() -> BTreeMap<u16, BaseDirAccess> {
+    BTreeMap::from([(base_dir_id(BaseDirectory::Resource), BaseDirAccess::ReadOnly)])
+}
+
 /// Builder for [`tauri_plugin_configurate`].
 #[derive(Debug, Clone)]
 pub struct Builder {
     max_read_bytes: usize,
     allowed_base_dirs: BaseDirPolicy,
+    base_dir_access: BTreeMap<u16, BaseDirAccess>,
+    secret_store: Option<SharedSecretStore>,
+    keyring_fallback: Option<VaultKey>,
+    env_prefix: Option<String>,
+    managed_policy: Option<ManagedPolicy>,
+    cache: Option<(usize, usize)>,
+    batch_parallelism: usize,
 }
 
 impl Default for Builder {
@@ -76,6 +125,13 @@ impl Default for Builder {
                     .map(base_dir_id)
                     .collect(),
             ),
+            base_dir_access: default_base_dir_access(),
+            secret_store: None,
+            keyring_fallback: None,
+            env_prefix: None,
+            managed_policy: None,
+            cache: None,
+            batch_parallelism: DEFAULT_BATCH_PARALLELISM,
         }
     }
 }
@@ -110,6 +166,85 @@ impl Builder {
         self
     }
 
+    /// Sets the access mode for one [`BaseDirectory`].
+    ///
+    /// [`BaseDirectory::Resource`] is [`BaseDirAccess::ReadOnly`] by default so
+    /// a webview cannot overwrite shipped configs; every other directory is
+    /// [`BaseDirAccess::ReadWrite`].  Writes to a read-only directory fail with
+    /// error kind `read_only`.
+    pub fn base_directory_access(mut self, dir: BaseDirectory, access: BaseDirAccess) -> Self {
+        self.base_dir_access.insert(base_dir_id(dir), access);
+        self
+    }
+
+    /// Replaces the OS keyring with a custom [`SecretStore`] for `keyring(...)` fields.
+    ///
+    /// Use [`MemorySecretStore`](crate::MemorySecretStore) in tests or headless CI,
+    /// or [`EncryptedFileSecretStore`](crate::EncryptedFileSecretStore) where no
+    /// OS keyring is available.
+    pub fn secret_store(mut self, store: impl SecretStore + 'static) -> Self {
+        self.secret_store = Some(SharedSecretStore(std::sync::Arc::new(store)));
+        self
+    }
+
+    /// Falls back to an encrypted vault file when the OS keyring is unavailable.
+    ///
+    /// The vault lives at `{AppLocalData}/configurate-secrets.vault` and takes
+    /// the operations where the keyring (or the store set via
+    /// [`Builder::secret_store`]) fails with `PlatformFailure` /
+    /// `NoStorageAccess`, e.g. Linux desktops without a running Secret Service.
+    /// Once the keyring works again, secrets are read from the vault when the
+    /// keyring misses them and moved into the keyring.
+    pub fn keyring_fallback(mut self, key: VaultKey) -> Self {
+        self.keyring_fallback = Some(key);
+        self
+    }
+
+    /// Overrides loaded config values from environment variables.
+    ///
+    /// With prefix `MYAPP`, `MYAPP__DATABASE__HOST=db.internal` replaces
+    /// `database.host` in every loaded config that has that key, coerced to
+    /// the key's existing JSON type.  Variables are read once at setup and are
+    /// never written back on save.
+    pub fn env_overrides(mut self, prefix: impl Into<String>) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Enforces admin-managed values that the webview cannot change.
+    ///
+    /// Every leaf of the policy is a locked key: `load` returns the policy
+    /// value and reports the key in `lockedKeys`, and `save` / `patch` /
+    /// `import` either re-apply the policy value or reject the write (see
+    /// [`PolicyEnforcement`](crate::PolicyEnforcement)).
+    pub fn managed_policy(mut self, policy: ManagedPolicy) -> Self {
+        self.managed_policy = Some(policy);
+        self
+    }
+
+    /// Caches parsed configs in memory so repeated loads skip reading,
+    /// parsing and key derivation.
+    ///
+    /// At most `max_entries` files and `max_bytes` of on-disk size are kept,
+    /// evicting the least recently used; larger files are never cached.  An
+    /// entry is dropped when the plugin writes or deletes the file, when a
+    /// watched file changes, and when the file's mtime or length differ from
+    /// the cached read.  Counters are available from `cache_stats`.
+    pub fn cache(mut self, max_entries: usize, max_bytes: usize) -> Self {
+        self.cache = Some((max_entries, max_bytes));
+        self
+    }
+
+    /// Sets how many files `load_all` / `save_all` / `patch_all` process at
+    /// the same time (default [`DEFAULT_BATCH_PARALLELISM`], at least 1).
+    ///
+    /// Entries that resolve to the same file always run one after another in
+    /// payload order.
+    pub fn batch_parallelism(mut self, files: usize) -> Self {
+        self.batch_parallelism = files.max(1);
+        self
+    }
+
     pub fn build<R: Runtime>(self) -> TauriPlugin<R, Option<PluginConfig>> {
         let builder = self;
         PluginBuilder::<R, Option<PluginConfig>>::new("configurate")
@@ -118,18 +253,27 @@ impl Builder {
                 crate::commands::load,
                 crate::commands::save,
                 crate::commands::patch,
+                crate::commands::get_value,
+                crate::commands::set_value,
+                crate::commands::remove_value,
+                crate::commands::load_layered,
+                crate::commands::save_layered,
                 crate::commands::delete,
                 crate::commands::exists,
                 crate::commands::load_all,
                 crate::commands::save_all,
                 crate::commands::patch_all,
                 crate::commands::unlock,
+                crate::commands::keyring_list,
+                crate::commands::keyring_gc,
                 crate::commands::watch_file,
                 crate::commands::unwatch_file,
                 crate::commands::list_configs,
                 crate::commands::reset,
                 crate::commands::export_config,
+                crate::commands::import_chunk,
                 crate::commands::import_config,
+                crate::commands::cache_stats,
             ])
             .setup(move |app, api| {
                 let settings = resolve_settings(&builder, api.config().as_ref())?;
@@ -142,25 +286,78 @@ impl Builder {
 
                 app.manage(configurate);
                 app.manage(settings);
+                app.manage(build_secret_store(app, &builder)?);
+                if let Some(prefix) = &builder.env_prefix {
+                    app.manage(EnvOverrides::from_env(prefix)?);
+                }
+                if let Some(policy) = &builder.managed_policy {
+                    app.manage(PolicyState::new(policy)?);
+                }
+                if let Some((max_entries, max_bytes)) = builder.cache {
+                    app.manage(ReadCache::new(max_entries, max_bytes));
+                }
                 app.manage(locker::FileLockRegistry::new());
+                app.manage(tasks::TaskRegistry::default());
+                app.manage(streaming::ImportUploads::default());
                 app.manage(std::sync::Arc::new(storage::BackupRegistry::new()));
                 let watcher_state = watcher::WatcherState::new(app.clone())?;
                 app.manage(watcher_state);
                 Ok(())
             })
-            .on_event(|app, event| {
-                if let tauri::RunEvent::Exit = event {
+            .on_event(|app, event| match event {
+                tauri::RunEvent::WindowEvent {
+                    label,
+                    event: tauri::WindowEvent::Destroyed,
+                    ..
+                } => {
+                    if let Some(tasks) = app.try_state::<tasks::TaskRegistry>() {
+                        tasks.cancel(label);
+                    }
+                    if let Some(uploads) = app.try_state::<streaming::ImportUploads>() {
+                        uploads.discard_window(label);
+                    }
+                }
+                tauri::RunEvent::Exit => {
                     if let Some(registry) =
                         app.try_state::<std::sync::Arc<storage::BackupRegistry>>()
                     {
                         registry.cleanup_all();
                     }
                 }
+                _ => {}
             })
             .build()
     }
 }
 
+fn build_secret_store<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    builder: &Builder,
+) -> Result<SharedSecretStore> {
+    let store = builder
+        .secret_store
+        .clone()
+        .unwrap_or_else(|| SharedSecretStore(std::sync::Arc::new(OsKeyringStore)));
+    let Some(key) = &builder.keyring_fallback else {
+        return Ok(store);
+    };
+
+    let dir = app
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| Error::Storage(e.to_string()))?;
+    // The key is resolved on first vault use so setup never fails on it.
+    let vault = LazyVaultStore::new(
+        dir.join(FALLBACK_VAULT_FILE),
+        key.clone(),
+        app.config().identifier.clone(),
+    );
+    Ok(SharedSecretStore(std::sync::Arc::new(FallbackSecretStore::new(
+        store.0,
+        std::sync::Arc::new(vault),
+    ))))
+}
+
 pub(crate) fn resolve_settings(
     builder: &Builder,
     file: Option<&PluginConfig>,
@@ -170,6 +367,8 @@ pub(crate) fn resolve_settings(
             .and_then(|config| config.max_read_bytes)
             .unwrap_or(builder.max_read_bytes),
         allowed_base_dirs: builder.allowed_base_dirs.clone(),
+        base_dir_access: builder.base_dir_access.clone(),
+        batch_parallelism: builder.batch_parallelism,
     })
 }
 
@@ -188,6 +387,41 @@ pub(crate) fn max_read_bytes<R: Runtime>(app: &tauri::AppHandle<R>) -> usize {
         .unwrap_or(DEFAULT_MAX_READ_BYTES)
 }
 
+pub(crate) fn batch_parallelism<R: Runtime>(app: &tauri::AppHandle<R>) -> usize {
+    app.try_state::<PluginSettings>()
+        .map(|settings| settings.batch_parallelism)
+        .unwrap_or(DEFAULT_BATCH_PARALLELISM)
+}
+
+/// Returns the environment overrides, if any variables matched the prefix.
+pub(crate) fn env_overrides<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Option<tauri::State<'_, EnvOverrides>> {
+    app.try_state::<EnvOverrides>()
+        .filter(|overrides| !overrides.is_empty())
+}
+
+/// Returns the read cache, when enabled with [`Builder::cache`].
+pub(crate) fn read_cache<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Option<tauri::State<'_, ReadCache>> {
+    app.try_state::<ReadCache>()
+}
+
+/// Returns the read cache counters, or `None` when caching is disabled.
+pub(crate) fn cache_stats<R: Runtime>(app: &tauri::AppHandle<R>) -> Option<CacheStats> {
+    read_cache(app).map(|cache| cache.stats())
+}
+
+/// Returns the configured secret store, defaulting to the OS keyring.
+pub(crate) fn secret_store<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> std::sync::Arc<dyn SecretStore> {
+    app.try_state::<SharedSecretStore>()
+        .map(|store| std::sync::Arc::clone(&store.0))
+        .unwrap_or_else(|| std::sync::Arc::new(OsKeyringStore))
+}
+
 pub(crate) fn validate_base_directory<R: Runtime>(
     app: &tauri::AppHandle<R>,
     base_dir: BaseDirectory,
@@ -225,6 +459,31 @@ pub(crate) fn validate_base_directory_policy(
     }
 }
 
+/// Fails with [`Error::ReadOnly`] when `base_dir` is configured read-only.
+pub(crate) fn validate_writable<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    base_dir: BaseDirectory,
+) -> Result<()> {
+    let access = match app.try_state::<PluginSettings>() {
+        Some(settings) => settings.access_for(base_dir),
+        None => default_base_dir_access()
+            .get(&base_dir_id(base_dir))
+            .copied()
+            .unwrap_or(BaseDirAccess::ReadWrite),
+    };
+    validate_access(access, base_dir)
+}
+
+fn validate_access(access: BaseDirAccess, base_dir: BaseDirectory) -> Result<()> {
+    match access {
+        BaseDirAccess::ReadWrite => Ok(()),
+        BaseDirAccess::ReadOnly => Err(Error::ReadOnly(format!(
+            "baseDir '{}' is read-only; call Builder::base_directory_access to allow writes",
+            base_dir.variable()
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -255,4 +514,39 @@ mod tests {
         assert!(validate_base_directory_policy(&BaseDirPolicy::Unrestricted, BaseDirectory::Home)
             .is_ok());
     }
+
+    #[test]
+    fn resource_is_read_only_by_default() {
+        let settings = resolve_settings(&Builder::default(), None).unwrap();
+        assert_eq!(
+            settings.access_for(BaseDirectory::Resource),
+            BaseDirAccess::ReadOnly
+        );
+        assert_eq!(
+            settings.access_for(BaseDirectory::AppConfig),
+            BaseDirAccess::ReadWrite
+        );
+        let err = validate_access(
+            settings.access_for(BaseDirectory::Resource),
+            BaseDirectory::Resource,
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::ReadOnly(_)));
+
+        let settings = resolve_settings(
+            &Builder::default()
+                .base_directory_access(BaseDirectory::Resource, BaseDirAccess::ReadWrite)
+                .base_directory_access(BaseDirectory::AppCache, BaseDirAccess::ReadOnly),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.access_for(BaseDirectory::Resource),
+            BaseDirAccess::ReadWrite
+        );
+        assert_eq!(
+            settings.access_for(BaseDirectory::AppCache),
+            BaseDirAccess::ReadOnly
+        );
+    }
 }
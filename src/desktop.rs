@@ -9,4 +9,34 @@ pub fn init<R: Runtime, C: DeserializeOwned>(
 }
 
 /// Access to the configurate APIs on desktop platforms.
+///
+/// The methods block while waiting for file locks.  Call them from
+/// synchronous code, a blocking thread or a multi-thread runtime; inside a
+/// current-thread async runtime they fail instead of waiting.
 pub struct Configurate<R: Runtime>(AppHandle<R>);
+
+impl<R: Runtime> Configurate<R> {
+    /// Loads every layer of `config` and returns the merged view with per-key
+    /// provenance.  Missing optional layers are skipped.
+    pub fn load_layered(&self, config: crate::LayeredConfig) -> crate::Result<crate::LayeredData> {
+        crate::layered::load(&self.0, config)
+    }
+
+    /// Stores `data` (the full desired config) in the write layer of `config`,
+    /// keeping only the values that differ from the layers below it and
+    /// leaving out values still supplied by the layers above it, and returns
+    /// the new merged view.
+    pub fn save_layered(
+        &self,
+        config: crate::LayeredConfig,
+        data: serde_json::Value,
+    ) -> crate::Result<crate::LayeredData> {
+        crate::layered::save(&self.0, config, data)
+    }
+
+    /// Read cache counters, or `None` unless enabled with
+    /// [`Builder::cache`](crate::Builder::cache).
+    pub fn cache_stats(&self) -> Option<crate::CacheStats> {
+        crate::config::cache_stats(&self.0)
+    }
+}
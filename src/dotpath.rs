@@ -1,41 +1,292 @@
-/// Utilities for traversing and mutating `serde_json::Value` via dot-separated paths.
-/// Example path: `"database.password"` refers to `value["database"]["password"]`.
+/// Utilities for traversing and mutating `serde_json::Value` via paths.
+///
+/// Three path syntaxes are accepted:
+///
+/// - dot-separated: `"database.password"`, `"servers.0.host"`;
+/// - bracket notation, mixable with dots: `plugins["com.example"].enabled`,
+///   `servers[0].host` — quoted keys are JSON strings, so they may contain
+///   dots, brackets or digits;
+/// - RFC 6901 JSON Pointer, when the path starts with `/`:
+///   `"/plugins/com.example~1x"` (`~1` = `/`, `~0` = `~`).  Empty reference
+///   tokens (`"/"`, `"/a//b"`) are the empty key.  The whole document (`""`)
+///   cannot be addressed.
+///
+/// Unquoted numeric segments (`servers.0`, `/servers/0`) are resolved by what
+/// the value actually is: an array index on arrays, an object key on objects.
+/// Pointer tokens with a leading zero (`/servers/01`) are always object keys,
+/// as RFC 6901 does not allow them as indices.
+/// `[0]` is always an array index and `["0"]` always an object key.  When a
+/// missing container has to be created, a numeric segment creates an array.
+///
+/// A `*` segment (or `[*]`) is a wildcard matching every element of an array
+/// (or every value of an object).  Wildcard paths are only accepted by
+/// [`expand`], which resolves them against actual data into concrete paths.
 use crate::error::{Error, Result};
 use serde_json::Value;
 
-/// Maximum number of dot-separated segments in a path.
+/// Maximum number of segments in a path.
 pub const MAX_PATH_SEGMENTS: usize = 64;
 
-/// Maximum array index allowed in dot paths (inclusive).
+/// Maximum array index allowed in paths (inclusive).
 pub const MAX_ARRAY_INDEX: usize = 10_000;
 
-/// Validates that a dot-separated `path` is non-empty and contains no empty segments
-/// (i.e. no leading dots, trailing dots, or consecutive dots).
+/// Path segment matching every element of an array or object.
+pub const WILDCARD: &str = "*";
+
+/// One parsed path segment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Object key (`["key"]`, or a non-numeric dot / pointer segment).
+    Key(String),
+    /// Array index (`[0]`).
+    Index(usize),
+    /// Numeric dot / pointer segment; an index on arrays, a key on objects.
+    Auto(String),
+    /// `*` / `[*]`.
+    Wildcard,
+}
+
+impl Segment {
+    fn from_unquoted(raw: String) -> Self {
+        if raw == WILDCARD {
+            Segment::Wildcard
+        } else if is_array_index_segment(&raw) {
+            Segment::Auto(raw)
+        } else {
+            Segment::Key(raw)
+        }
+    }
+
+    /// Whether a missing container for this segment should be an array.
+    fn prefers_array(&self) -> bool {
+        matches!(self, Segment::Index(_) | Segment::Auto(_))
+    }
+}
+
+fn invalid(path: &str, reason: impl std::fmt::Display) -> Error {
+    Error::Dotpath(format!("invalid path '{}': {}", path, reason))
+}
+
+/// Parses `path` into segments, validating segment count and index bounds.
 ///
-/// Returns an error for paths such as `""`, `".path"`, `"path."`, `"path..name"`.
-pub fn validate_path(path: &str) -> Result<()> {
+/// Returns an error for empty paths, empty dot segments (`".path"`,
+/// `"path."`, `"path..name"`), malformed brackets and bad pointer escapes.
+pub fn parse(path: &str) -> Result<Vec<Segment>> {
     if path.is_empty() {
         return Err(Error::Dotpath("path must not be empty".to_string()));
     }
-    let segments: Vec<&str> = path.split('.').collect();
-    if segments.iter().any(|segment| segment.is_empty()) {
-        return Err(Error::Dotpath(format!(
-            "invalid path '{}': empty segment is not allowed",
-            path
-        )));
-    }
+    let segments = if path.starts_with('/') {
+        parse_pointer(path)?
+    } else {
+        parse_dotted(path)?
+    };
     if segments.len() > MAX_PATH_SEGMENTS {
-        return Err(Error::Dotpath(format!(
-            "invalid path '{}': exceeds maximum of {} segments",
-            path, MAX_PATH_SEGMENTS
-        )));
+        return Err(invalid(
+            path,
+            format_args!("exceeds maximum of {} segments", MAX_PATH_SEGMENTS),
+        ));
     }
-    for segment in segments {
-        if is_array_index_segment(segment) {
-            validate_array_index(segment, path)?;
+    Ok(segments)
+}
+
+fn parse_pointer(path: &str) -> Result<Vec<Segment>> {
+    let tokens = pointer_tokens(path).map_err(|reason| invalid(path, reason))?;
+    Ok(tokens
+        .into_iter()
+        .map(|token| {
+            if token.len() > 1 && token.starts_with('0') && is_array_index_segment(&token) {
+                Segment::Key(token)
+            } else {
+                Segment::from_unquoted(token)
+            }
+        })
+        .collect())
+}
+
+/// Splits an RFC 6901 JSON Pointer into its unescaped reference tokens.
+/// `""` (the whole document) has none; any other pointer starts with `/`.
+pub(crate) fn pointer_tokens(pointer: &str) -> std::result::Result<Vec<String>, &'static str> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err("path must be a JSON Pointer starting with '/'");
+    };
+    rest.split('/')
+        .map(|raw| {
+            let mut token = String::with_capacity(raw.len());
+            let mut chars = raw.chars();
+            while let Some(ch) = chars.next() {
+                if ch != '~' {
+                    token.push(ch);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => token.push('~'),
+                    Some('1') => token.push('/'),
+                    _ => return Err("'~' must be followed by '0' or '1'"),
+                }
+            }
+            Ok(token)
+        })
+        .collect()
+}
+
+fn parse_dotted(path: &str) -> Result<Vec<Segment>> {
+    let bytes = path.as_bytes();
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    // Whether a segment must start at `pos` (start of path or after a dot).
+    let mut expect_segment = true;
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'[' => {
+                if expect_segment && pos > 0 {
+                    return Err(invalid(path, "'[' must not follow '.'"));
+                }
+                let (segment, end) = parse_bracket(path, pos)?;
+                segments.push(segment);
+                pos = end;
+                expect_segment = false;
+            }
+            b'.' if !expect_segment => {
+                pos += 1;
+                expect_segment = true;
+                if pos == bytes.len() {
+                    return Err(invalid(path, "empty segment is not allowed"));
+                }
+            }
+            _ if expect_segment => {
+                let end = path[pos..]
+                    .find(['.', '['])
+                    .map_or(path.len(), |i| pos + i);
+                if end == pos {
+                    return Err(invalid(path, "empty segment is not allowed"));
+                }
+                let raw = &path[pos..end];
+                if raw.contains(']') {
+                    return Err(invalid(path, "unmatched ']'"));
+                }
+                segments.push(Segment::from_unquoted(raw.to_string()));
+                pos = end;
+                expect_segment = false;
+            }
+            _ => return Err(invalid(path, "expected '.' or '[' after ']'")),
         }
     }
-    Ok(())
+    Ok(segments)
+}
+
+/// Parses `[...]` starting at `start`; returns the segment and the position
+/// after the closing bracket.
+fn parse_bracket(path: &str, start: usize) -> Result<(Segment, usize)> {
+    let rest = &path[start + 1..];
+    if let Some(quoted) = rest.strip_prefix('"') {
+        // Find the closing quote, skipping escaped characters.
+        let mut escaped = false;
+        let mut close = None;
+        for (i, ch) in quoted.char_indices() {
+            match ch {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    close = Some(i);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let close = close.ok_or_else(|| invalid(path, "unterminated quoted key"))?;
+        let literal = &rest[..close + 2];
+        let key: String = serde_json::from_str(literal)
+            .map_err(|e| invalid(path, format_args!("invalid quoted key {}: {}", literal, e)))?;
+        let end = start + 1 + literal.len();
+        if path.as_bytes().get(end) != Some(&b']') {
+            return Err(invalid(path, "expected ']' after quoted key"));
+        }
+        return Ok((Segment::Key(key), end + 1));
+    }
+
+    let close = rest
+        .find(']')
+        .ok_or_else(|| invalid(path, "unclosed '['"))?;
+    let inner = &rest[..close];
+    let segment = if inner == WILDCARD {
+        Segment::Wildcard
+    } else if is_array_index_segment(inner) {
+        Segment::Index(validate_array_index(inner, path)?)
+    } else {
+        return Err(invalid(
+            path,
+            format_args!("'[{}]' must be an index, '*' or a quoted key", inner),
+        ));
+    };
+    Ok((segment, start + 1 + close + 1))
+}
+
+/// Formats segments back into a path accepted by [`parse`], using dot
+/// syntax where unambiguous and bracket notation otherwise.  Indices are
+/// written as bare numbers and therefore parse back as [`Segment::Auto`].
+pub fn format(segments: &[Segment]) -> String {
+    let mut out = String::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Key(key) if is_plain_key(key, i == 0) => {
+                if i > 0 {
+                    out.push('.');
+                }
+                out.push_str(key);
+            }
+            Segment::Key(key) => {
+                out.push('[');
+                out.push_str(&serde_json::to_string(key).unwrap_or_default());
+                out.push(']');
+            }
+            // Concrete indices come from real arrays, where a bare numeric
+            // segment resolves to the same element.
+            Segment::Index(idx) => {
+                if i > 0 {
+                    out.push('.');
+                }
+                out.push_str(&idx.to_string());
+            }
+            Segment::Auto(raw) => {
+                if i > 0 {
+                    out.push('.');
+                }
+                out.push_str(raw);
+            }
+            Segment::Wildcard => {
+                if i > 0 {
+                    out.push('.');
+                }
+                out.push_str(WILDCARD);
+            }
+        }
+    }
+    out
+}
+
+/// Whether `key` can be written as a bare dot segment without changing meaning.
+fn is_plain_key(key: &str, first: bool) -> bool {
+    if key.is_empty() || key == WILDCARD || is_array_index_segment(key) {
+        return false;
+    }
+    if first && key.starts_with('/') {
+        return false;
+    }
+    !key.contains(['.', '[', ']', '"'])
+}
+
+/// Validates that `path` parses (see [`parse`]).
+pub fn validate_path(path: &str) -> Result<()> {
+    parse(path).map(|_| ())
+}
+
+/// Returns `true` if `path` contains a wildcard segment.
+pub fn has_wildcard(path: &str) -> bool {
+    parse(path).is_ok_and(|segments| segments.contains(&Segment::Wildcard))
 }
 
 fn is_array_index_segment(segment: &str) -> bool {
@@ -58,48 +309,192 @@ fn validate_array_index(segment: &str, path: &str) -> Result<usize> {
     Ok(idx)
 }
 
-fn parse_array_index(segment: &str, path: &str) -> Result<usize> {
-    if !is_array_index_segment(segment) {
-        return Err(Error::Dotpath(format!(
+/// Resolves `segment` as an array index, or errors when it is a key.
+fn array_index(segment: &Segment, path: &str) -> Result<usize> {
+    match segment {
+        Segment::Index(idx) => Ok(*idx),
+        Segment::Auto(raw) => validate_array_index(raw, path),
+        Segment::Key(key) => Err(Error::Dotpath(format!(
             "expected array index at segment '{}' of path '{}'",
-            segment, path
-        )));
+            key, path
+        ))),
+        Segment::Wildcard => Err(Error::Dotpath(format!(
+            "wildcard path '{}' must be expanded against data before use",
+            path
+        ))),
+    }
+}
+
+/// Resolves `segment` as an object key, or errors when it is an explicit index.
+fn object_key<'a>(segment: &'a Segment, path: &str) -> Result<&'a str> {
+    match segment {
+        Segment::Key(key) | Segment::Auto(key) => Ok(key),
+        Segment::Index(idx) => Err(Error::Dotpath(format!(
+            "expected array at index segment [{}] of path '{}'",
+            idx, path
+        ))),
+        Segment::Wildcard => Err(Error::Dotpath(format!(
+            "wildcard path '{}' must be expanded against data before use",
+            path
+        ))),
+    }
+}
+
+fn segment_label(segment: &Segment) -> String {
+    match segment {
+        Segment::Key(key) | Segment::Auto(key) => key.clone(),
+        Segment::Index(idx) => idx.to_string(),
+        Segment::Wildcard => WILDCARD.to_string(),
+    }
+}
+
+/// Element matched by one wildcard segment of a path.
+#[derive(Debug, Clone, Copy)]
+pub struct WildcardStep<'a> {
+    /// Array position, when the wildcard matched an array element.
+    pub index: Option<usize>,
+    /// Object key, when the wildcard matched an object value.
+    pub key: Option<&'a str>,
+    /// The matched element itself.
+    pub element: &'a Value,
+}
+
+/// One concrete path produced by [`expand`].
+#[derive(Debug, Clone)]
+pub struct WildcardMatch<'a> {
+    /// Concrete path with every wildcard replaced (see [`format`]).
+    pub path: String,
+    /// One step per wildcard segment, outermost first.
+    pub steps: Vec<WildcardStep<'a>>,
+    /// Value at `path`, or `None` when only its parent exists.
+    pub value: Option<&'a Value>,
+}
+
+/// Expands a (possibly wildcard) `path` against `root`.
+///
+/// Every segment except the last must exist in `root`; the last one may be
+/// missing, in which case the match has `value: None`.  Wildcards over
+/// missing or scalar values produce no matches.  A path without wildcards
+/// yields at most one match.
+pub fn expand<'a>(root: &'a Value, path: &str) -> Result<Vec<WildcardMatch<'a>>> {
+    let segments = parse(path)?;
+    let mut out = Vec::new();
+    expand_from(root, &segments, &mut Vec::new(), &mut Vec::new(), path, &mut out)?;
+    Ok(out)
+}
+
+fn expand_from<'a>(
+    node: &'a Value,
+    segments: &[Segment],
+    concrete: &mut Vec<Segment>,
+    steps: &mut Vec<WildcardStep<'a>>,
+    path: &str,
+    out: &mut Vec<WildcardMatch<'a>>,
+) -> Result<()> {
+    let Some((segment, rest)) = segments.split_first() else {
+        out.push(WildcardMatch {
+            path: format(concrete),
+            steps: steps.clone(),
+            value: Some(node),
+        });
+        return Ok(());
+    };
+
+    if *segment == Segment::Wildcard {
+        let children: Vec<(Segment, WildcardStep<'a>)> = match node {
+            Value::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(idx, element)| {
+                    (
+                        Segment::Index(idx),
+                        WildcardStep {
+                            index: Some(idx),
+                            key: None,
+                            element,
+                        },
+                    )
+                })
+                .collect(),
+            Value::Object(map) => map
+                .iter()
+                .map(|(key, element)| {
+                    (
+                        Segment::Key(key.clone()),
+                        WildcardStep {
+                            index: None,
+                            key: Some(key.as_str()),
+                            element,
+                        },
+                    )
+                })
+                .collect(),
+            _ => return Ok(()),
+        };
+        for (child_segment, step) in children {
+            concrete.push(child_segment);
+            steps.push(step);
+            expand_from(step.element, rest, concrete, steps, path, out)?;
+            steps.pop();
+            concrete.pop();
+        }
+        return Ok(());
+    }
+
+    let (child, resolved) = match (node, segment) {
+        (Value::Array(arr), Segment::Index(_) | Segment::Auto(_)) => {
+            let idx = array_index(segment, path)?;
+            (arr.get(idx), Segment::Index(idx))
+        }
+        (Value::Object(map), Segment::Key(key) | Segment::Auto(key)) => {
+            (map.get(key), Segment::Key(key.clone()))
+        }
+        _ => return Ok(()),
+    };
+    concrete.push(resolved);
+    match child {
+        Some(child) => expand_from(child, rest, concrete, steps, path, out)?,
+        None if rest.is_empty() => out.push(WildcardMatch {
+            path: format(concrete),
+            steps: steps.clone(),
+            value: None,
+        }),
+        None => {}
+    }
+    concrete.pop();
+    Ok(())
+}
+
+fn new_container(next: &Segment) -> Value {
+    if next.prefers_array() {
+        Value::Array(Vec::new())
+    } else {
+        Value::Object(serde_json::Map::new())
     }
-    validate_array_index(segment, path)
 }
 
-/// Sets the value at the given dot-separated `path` inside `root` to `new_val`.
+/// Sets the value at the given `path` inside `root` to `new_val`.
 /// Intermediate objects/arrays are created automatically if they are missing.
 pub fn set(root: &mut Value, path: &str, new_val: Value) -> Result<()> {
-    validate_path(path)?;
-
-    let parts: Vec<&str> = path.split('.').collect();
+    let parts = concrete_segments(path)?;
     let mut current = root;
 
     for (i, part) in parts.iter().enumerate() {
-        let is_last = i == parts.len() - 1;
-        let next_is_index = if is_last {
-            false
-        } else {
-            is_array_index_segment(parts[i + 1])
-        };
+        let next = parts.get(i + 1);
 
         match current {
             Value::Object(map) => {
-                if is_last {
-                    map.insert((*part).to_string(), new_val);
+                let key = object_key(part, path)?;
+                let Some(next) = next else {
+                    map.insert(key.to_string(), new_val);
                     return Ok(());
-                }
-                current = map.entry((*part).to_string()).or_insert_with(|| {
-                    if next_is_index {
-                        Value::Array(Vec::new())
-                    } else {
-                        Value::Object(serde_json::Map::new())
-                    }
-                });
+                };
+                current = map
+                    .entry(key.to_string())
+                    .or_insert_with(|| new_container(next));
             }
             Value::Array(arr) => {
-                let idx = parse_array_index(part, path)?;
+                let idx = array_index(part, path)?;
                 let required_len = idx
                     .checked_add(1)
                     .ok_or_else(|| Error::Dotpath(format!("array index overflow in path '{}'", path)))?;
@@ -112,16 +507,12 @@ pub fn set(root: &mut Value, path: &str, new_val: Value) -> Result<()> {
                 if idx >= arr.len() {
                     arr.resize_with(required_len, || Value::Null);
                 }
-                if is_last {
+                let Some(next) = next else {
                     arr[idx] = new_val;
                     return Ok(());
-                }
+                };
                 if arr[idx].is_null() {
-                    arr[idx] = if next_is_index {
-                        Value::Array(Vec::new())
-                    } else {
-                        Value::Object(serde_json::Map::new())
-                    };
+                    arr[idx] = new_container(next);
                 }
                 match &mut arr[idx] {
                     Value::Object(_) | Value::Array(_) => {
@@ -130,7 +521,8 @@ pub fn set(root: &mut Value, path: &str, new_val: Value) -> Result<()> {
                     _ => {
                         return Err(Error::Dotpath(format!(
                             "expected object or array at segment '{}' of path '{}'",
-                            part, path
+                            segment_label(part),
+                            path
                         )))
                     }
                 }
@@ -138,18 +530,89 @@ pub fn set(root: &mut Value, path: &str, new_val: Value) -> Result<()> {
             _ => {
                 return Err(Error::Dotpath(format!(
                     "expected object or array at segment '{}' of path '{}'",
-                    part, path
+                    segment_label(part),
+                    path
                 )))
             }
         }
     }
 
     // All non-empty paths with valid segments are handled inside the loop;
-    // the final iteration always returns from the `i == parts.len() - 1` branch.
+    // the final iteration always returns from the `next == None` branch.
     unreachable!("path '{}' was not resolved inside loop", path)
 }
 
-/// Replaces the value at the given dot-separated `path` inside `root` with `null`.
+fn concrete_segments(path: &str) -> Result<Vec<Segment>> {
+    let parts = parse(path)?;
+    if parts.contains(&Segment::Wildcard) {
+        return Err(Error::Dotpath(format!(
+            "wildcard path '{}' must be expanded against data before use",
+            path
+        )));
+    }
+    Ok(parts)
+}
+
+/// Resolves one segment against `node` without creating anything.
+fn child<'a>(node: &'a Value, segment: &Segment, path: &str) -> Result<Option<&'a Value>> {
+    Ok(match (node, segment) {
+        (Value::Array(arr), Segment::Index(_) | Segment::Auto(_)) => {
+            arr.get(array_index(segment, path)?)
+        }
+        (Value::Object(map), Segment::Key(key) | Segment::Auto(key)) => map.get(key),
+        _ => None,
+    })
+}
+
+/// Returns the value at `path` inside `root`, or `None` when any segment is
+/// missing or does not match the kind of value it is applied to.
+pub fn get<'a>(root: &'a Value, path: &str) -> Result<Option<&'a Value>> {
+    let parts = concrete_segments(path)?;
+    let mut current = root;
+    for part in &parts {
+        match child(current, part, path)? {
+            Some(next) => current = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(current))
+}
+
+/// Removes the value at `path` inside `root` and returns it.
+///
+/// Object keys are removed; array elements are removed and the following
+/// elements shift down.  Returns `None` (leaving `root` untouched) when the
+/// path does not exist.
+pub fn remove(root: &mut Value, path: &str) -> Result<Option<Value>> {
+    let parts = concrete_segments(path)?;
+    let Some((last, parents)) = parts.split_last() else {
+        return Ok(None);
+    };
+    let mut current = root;
+    for part in parents {
+        let exists = child(current, part, path)?.is_some();
+        if !exists {
+            return Ok(None);
+        }
+        current = match (current, part) {
+            (Value::Array(arr), _) => &mut arr[array_index(part, path)?],
+            (Value::Object(map), Segment::Key(key) | Segment::Auto(key)) => map
+                .get_mut(key)
+                .expect("key existence checked above"),
+            _ => unreachable!("child() only resolves arrays and objects"),
+        };
+    }
+    Ok(match (current, last) {
+        (Value::Array(arr), Segment::Index(_) | Segment::Auto(_)) => {
+            let idx = array_index(last, path)?;
+            (idx < arr.len()).then(|| arr.remove(idx))
+        }
+        (Value::Object(map), Segment::Key(key) | Segment::Auto(key)) => map.remove(key),
+        _ => None,
+    })
+}
+
+/// Replaces the value at the given `path` inside `root` with `null`.
 pub fn nullify(root: &mut Value, path: &str) -> Result<()> {
     set(root, path, Value::Null)
 }
@@ -263,4 +726,185 @@ mod tests {
         assert!(set(&mut root, &path, json!("x")).is_err());
     }
 
+    #[test]
+    fn set_rejects_wildcard_path() {
+        let mut root = json!({"servers": [{}]});
+        assert!(set(&mut root, "servers.*.password", json!("x")).is_err());
+    }
+
+    #[test]
+    fn expand_array_wildcard_matches_each_element() {
+        let root = json!({"servers": [{"password": "a"}, {"host": "h"}]});
+        let matches = expand(&root, "servers.*.password").unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].path, "servers.0.password");
+        assert_eq!(matches[0].value, Some(&json!("a")));
+        assert_eq!(matches[0].steps[0].index, Some(0));
+        assert_eq!(matches[1].path, "servers.1.password");
+        assert_eq!(matches[1].value, None);
+        assert_eq!(matches[1].steps[0].element, &json!({"host": "h"}));
+    }
+
+    #[test]
+    fn expand_nested_and_object_wildcards() {
+        let root = json!({
+            "envs": {
+                "prod": {"servers": [{"token": 1}, {"token": 2}]},
+                "dev": {"servers": []}
+            }
+        });
+        let matches = expand(&root, "envs.*.servers.*.token").unwrap();
+        let paths: Vec<_> = matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec!["envs.prod.servers.0.token", "envs.prod.servers.1.token"]);
+        assert_eq!(matches[1].steps[0].key, Some("prod"));
+        assert_eq!(matches[1].steps[1].index, Some(1));
+    }
+
+    #[test]
+    fn expand_without_wildcard_and_missing_parents() {
+        let root = json!({"db": {"password": "x"}});
+        assert_eq!(expand(&root, "db.password").unwrap().len(), 1);
+        assert_eq!(expand(&root, "db.user").unwrap()[0].value, None);
+        assert!(expand(&root, "missing.*.password").unwrap().is_empty());
+        assert!(expand(&json!({"list": "scalar"}), "list.*").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_accepts_bracket_and_pointer_syntax() {
+        assert_eq!(
+            parse(r#"a["b.c"][0]"#).unwrap(),
+            vec![
+                Segment::Key("a".into()),
+                Segment::Key("b.c".into()),
+                Segment::Index(0)
+            ]
+        );
+        assert_eq!(
+            parse("/plugins/com.example~1x/~0tilde/0").unwrap(),
+            vec![
+                Segment::Key("plugins".into()),
+                Segment::Key("com.example/x".into()),
+                Segment::Key("~tilde".into()),
+                Segment::Auto("0".into())
+            ]
+        );
+        assert_eq!(
+            parse(r#"servers[*].password"#).unwrap()[1],
+            Segment::Wildcard
+        );
+        assert_eq!(parse(r#"["*"]"#).unwrap(), vec![Segment::Key("*".into())]);
+    }
+
+    #[test]
+    fn parse_rejects_malformed_paths() {
+        for bad in [
+            "a[",
+            "a[b]",
+            r#"a["b]"#,
+            r#"a["b"]c"#,
+            "a]",
+            "",
+            "/a~2",
+            "a.",
+            "a.[0]",
+        ] {
+            assert!(parse(bad).is_err(), "expected '{}' to be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn pointer_tokens_follow_rfc_6901() {
+        assert_eq!(parse("/").unwrap(), vec![Segment::Key(String::new())]);
+        assert_eq!(
+            parse("/a//b").unwrap(),
+            vec![
+                Segment::Key("a".into()),
+                Segment::Key(String::new()),
+                Segment::Key("b".into())
+            ]
+        );
+        assert_eq!(parse("/01").unwrap(), vec![Segment::Key("01".into())]);
+        assert_eq!(parse("/0").unwrap(), vec![Segment::Auto("0".into())]);
+
+        let root = json!({"": {"": 1}, "arr": [10, 11], "map": {"01": "key"}});
+        assert_eq!(get(&root, "/").unwrap(), Some(&json!({"": 1})));
+        assert_eq!(get(&root, "//").unwrap(), Some(&json!(1)));
+        assert_eq!(get(&root, "/arr/1").unwrap(), Some(&json!(11)));
+        assert_eq!(get(&root, "/arr/01").unwrap(), None);
+        assert_eq!(get(&root, "/map/01").unwrap(), Some(&json!("key")));
+
+        // Empty keys survive formatting back into a path.
+        let segments = parse("/a//b").unwrap();
+        assert_eq!(parse(&format(&segments)).unwrap(), segments);
+    }
+
+    #[test]
+    fn set_with_dotted_key_via_brackets_and_pointer() {
+        let mut root = json!({});
+        set(&mut root, r#"hosts["api.example.com"].token"#, json!("t")).unwrap();
+        set(&mut root, "/plugins/com.example~1x", json!(true)).unwrap();
+        assert_eq!(root["hosts"]["api.example.com"]["token"], "t");
+        assert_eq!(root["plugins"]["com.example/x"], true);
+    }
+
+    #[test]
+    fn numeric_segment_is_key_on_objects_and_index_on_arrays() {
+        let mut root = json!({"years": {"2024": "a"}, "list": ["x"]});
+        set(&mut root, "years.2024", json!("b")).unwrap();
+        set(&mut root, "/list/0", json!("y")).unwrap();
+        assert_eq!(root, json!({"years": {"2024": "b"}, "list": ["y"]}));
+
+        // Explicit forms are not reinterpreted.
+        assert!(set(&mut root, "years[2024]", json!("c")).is_err());
+        assert!(set(&mut root, r#"list["0"]"#, json!("z")).is_err());
+    }
+
+    #[test]
+    fn expand_formats_keys_that_need_brackets() {
+        let root = json!({"hosts": {"api.example.com": {"token": 1}, "7": {"token": 2}}});
+        let matches = expand(&root, "hosts.*.token").unwrap();
+        let paths: Vec<_> = matches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, vec![r#"hosts["7"].token"#, r#"hosts["api.example.com"].token"#]);
+        for m in &matches {
+            let mut copy = root.clone();
+            set(&mut copy, &m.path, json!(null)).unwrap();
+        }
+    }
+
+    #[test]
+    fn format_roundtrips_through_parse() {
+        let segments = vec![
+            Segment::Key("/lead".into()),
+            Segment::Key("a.b".into()),
+            Segment::Auto("4".into()),
+            Segment::Key("q\"uote".into()),
+            Segment::Key("plain".into()),
+        ];
+        assert_eq!(parse(&format(&segments)).unwrap(), segments);
+        assert_eq!(format(&[Segment::Key("a".into()), Segment::Index(3)]), "a.3");
+    }
+
+    #[test]
+    fn get_resolves_all_syntaxes_and_reports_missing() {
+        let root = json!({"db": {"hosts": ["a", "b"]}, "plugins": {"x.y": true}});
+        assert_eq!(get(&root, "db.hosts.1").unwrap(), Some(&json!("b")));
+        assert_eq!(get(&root, "/db/hosts/0").unwrap(), Some(&json!("a")));
+        assert_eq!(get(&root, r#"plugins["x.y"]"#).unwrap(), Some(&json!(true)));
+        assert_eq!(get(&root, "db.hosts.5").unwrap(), None);
+        assert_eq!(get(&root, "db.missing.deep").unwrap(), None);
+        assert_eq!(get(&root, "db.hosts.0.x").unwrap(), None);
+        assert!(get(&root, "db.*").is_err());
+    }
+
+    #[test]
+    fn remove_deletes_keys_and_shifts_array_elements() {
+        let mut root = json!({"a": {"b": 1, "c": 2}, "list": [1, 2, 3]});
+        assert_eq!(remove(&mut root, "a.b").unwrap(), Some(json!(1)));
+        assert_eq!(remove(&mut root, "list[0]").unwrap(), Some(json!(1)));
+        assert_eq!(root, json!({"a": {"c": 2}, "list": [2, 3]}));
+        assert_eq!(remove(&mut root, "a.missing").unwrap(), None);
+        assert_eq!(remove(&mut root, "nope.deep").unwrap(), None);
+        assert_eq!(remove(&mut root, "list.9").unwrap(), None);
+        assert_eq!(root, json!({"a": {"c": 2}, "list": [2, 3]}));
+    }
 }
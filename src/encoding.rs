@@ -0,0 +1,388 @@
+/// CBOR and MessagePack encodings for the Binary provider.
+///
+/// Config data is a `serde_json::Value` everywhere else in the plugin, so the
+/// encodings map to and from JSON:
+///
+/// - byte strings become `{"$bytes": "<base64>"}` and such single-key objects
+///   are written back as byte strings;
+/// - integers outside the `i64` / `u64` range, non-finite floats, non-text
+///   map keys (other than integers, which become their decimal string) and
+///   MessagePack extension types are rejected;
+/// - CBOR tags are dropped and their content kept.
+///
+/// Stored files start with [`ENCODING_MAGIC`] and an encoding byte so reads
+/// detect the encoding; JSON is stored without a header, as before.
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{Map, Number, Value};
+
+use crate::error::{Error, Result};
+use crate::models::BinaryEncoding;
+
+/// Object key marking a byte string in the JSON view of CBOR / MessagePack.
+pub(crate) const BYTES_KEY: &str = "$bytes";
+
+/// Marks a CBOR or MessagePack payload.  JSON text never starts with a NUL
+/// byte, so headerless payloads are read as JSON.
+const ENCODING_MAGIC: &[u8; 4] = b"\0CFE";
+const CBOR_TAG: u8 = 1;
+const MSGPACK_TAG: u8 = 2;
+
+/// Encodes `value` for storage, with the encoding header for CBOR and
+/// MessagePack.
+pub(crate) fn encode_stored(value: &Value, encoding: BinaryEncoding) -> Result<Vec<u8>> {
+    let tag = match encoding {
+        BinaryEncoding::Json => return Ok(serde_json::to_vec(value)?),
+        BinaryEncoding::Cbor => CBOR_TAG,
+        BinaryEncoding::Msgpack => MSGPACK_TAG,
+    };
+    let mut output = Vec::from(&ENCODING_MAGIC[..]);
+    output.push(tag);
+    output.extend(encode(value, encoding)?);
+    Ok(output)
+}
+
+/// Decodes a payload written by [`encode_stored`], detecting its encoding.
+pub(crate) fn decode_stored(bytes: &[u8]) -> Result<Value> {
+    let Some(rest) = bytes.strip_prefix(ENCODING_MAGIC) else {
+        return decode(bytes, BinaryEncoding::Json);
+    };
+    match rest.split_first() {
+        Some((&CBOR_TAG, body)) => decode(body, BinaryEncoding::Cbor),
+        Some((&MSGPACK_TAG, body)) => decode(body, BinaryEncoding::Msgpack),
+        Some((tag, _)) => Err(Error::Storage(format!(
+            "unsupported binary encoding {}",
+            tag
+        ))),
+        None => Err(Error::Storage(
+            "binary file is missing its encoding".to_string(),
+        )),
+    }
+}
+
+/// Encodes `value` as plain CBOR / MessagePack / JSON bytes, without header.
+pub(crate) fn encode(value: &Value, encoding: BinaryEncoding) -> Result<Vec<u8>> {
+    match encoding {
+        BinaryEncoding::Json => Ok(serde_json::to_vec(value)?),
+        BinaryEncoding::Cbor => {
+            let mut output = Vec::new();
+            ciborium::into_writer(&to_cbor(value)?, &mut output)
+                .map_err(|e| Error::Storage(format!("CBOR encoding failed: {}", e)))?;
+            Ok(output)
+        }
+        BinaryEncoding::Msgpack => {
+            let mut output = Vec::new();
+            rmpv::encode::write_value(&mut output, &to_msgpack(value)?)
+                .map_err(|e| Error::Storage(format!("MessagePack encoding failed: {}", e)))?;
+            Ok(output)
+        }
+    }
+}
+
+/// Decodes plain CBOR / MessagePack / JSON bytes.
+pub(crate) fn decode(bytes: &[u8], encoding: BinaryEncoding) -> Result<Value> {
+    match encoding {
+        BinaryEncoding::Json => {
+            serde_json::from_slice(bytes).map_err(|e| Error::Storage(e.to_string()))
+        }
+        BinaryEncoding::Cbor => {
+            let mut rest = bytes;
+            let value: ciborium::Value = ciborium::from_reader(&mut rest)
+                .map_err(|e| Error::Storage(format!("invalid CBOR: {}", e)))?;
+            if !rest.is_empty() {
+                return Err(Error::Storage("invalid CBOR: trailing data".to_string()));
+            }
+            from_cbor(value)
+        }
+        BinaryEncoding::Msgpack => {
+            let mut rest = bytes;
+            let value = rmpv::decode::read_value(&mut rest)
+                .map_err(|e| Error::Storage(format!("invalid MessagePack: {}", e)))?;
+            if !rest.is_empty() {
+                return Err(Error::Storage(
+                    "invalid MessagePack: trailing data".to_string(),
+                ));
+            }
+            from_msgpack(value)
+        }
+    }
+}
+
+/// The binary encoding an export / import format names, if any.
+pub(crate) fn for_format(format: &str) -> Option<BinaryEncoding> {
+    match format {
+        "cbor" => Some(BinaryEncoding::Cbor),
+        "msgpack" => Some(BinaryEncoding::Msgpack),
+        _ => None,
+    }
+}
+
+/// Encodes `value` as base64 text, for exports over IPC.
+pub(crate) fn to_base64(value: &Value, encoding: BinaryEncoding) -> Result<String> {
+    Ok(BASE64.encode(encode(value, encoding)?))
+}
+
+/// Decodes base64 text produced by [`to_base64`] (or any plain CBOR /
+/// MessagePack document).
+pub(crate) fn from_base64(text: &str, encoding: BinaryEncoding) -> Result<Value> {
+    let bytes = BASE64
+        .decode(text.trim())
+        .map_err(|e| Error::InvalidPayload(format!("invalid base64 import content: {}", e)))?;
+    decode(&bytes, encoding)
+}
+
+/// The bytes of a `{"$bytes": "<base64>"}` object, if `map` is one.
+pub(crate) fn byte_string(map: &Map<String, Value>) -> Result<Option<Vec<u8>>> {
+    match (map.len(), map.get(BYTES_KEY)) {
+        (1, Some(Value::String(encoded))) => BASE64.decode(encoded).map(Some).map_err(|e| {
+            Error::InvalidPayload(format!("invalid base64 in '{}': {}", BYTES_KEY, e))
+        }),
+        _ => Ok(None),
+    }
+}
+
+pub(crate) fn bytes_object(bytes: &[u8]) -> Value {
+    let mut map = Map::new();
+    map.insert(BYTES_KEY.to_string(), Value::String(BASE64.encode(bytes)));
+    Value::Object(map)
+}
+
+fn float_value(f: f64) -> Result<Value> {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .ok_or_else(|| Error::Storage(format!("{} cannot be represented in config data", f)))
+}
+
+fn to_cbor(value: &Value) -> Result<ciborium::Value> {
+    use ciborium::Value as Cbor;
+    Ok(match value {
+        Value::Null => Cbor::Null,
+        Value::Bool(b) => Cbor::Bool(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Cbor::Integer(i.into()),
+            (_, Some(u)) => Cbor::Integer(u.into()),
+            _ => Cbor::Float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => Cbor::Text(s.clone()),
+        Value::Array(items) => Cbor::Array(items.iter().map(to_cbor).collect::<Result<_>>()?),
+        Value::Object(map) => match byte_string(map)? {
+            Some(bytes) => Cbor::Bytes(bytes),
+            None => Cbor::Map(
+                map.iter()
+                    .map(|(k, v)| Ok((Cbor::Text(k.clone()), to_cbor(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+        },
+    })
+}
+
+fn from_cbor(value: ciborium::Value) -> Result<Value> {
+    use ciborium::Value as Cbor;
+    Ok(match value {
+        Cbor::Null => Value::Null,
+        Cbor::Bool(b) => Value::Bool(b),
+        Cbor::Integer(i) => {
+            if let Ok(i) = i64::try_from(i) {
+                Value::from(i)
+            } else if let Ok(u) = u64::try_from(i) {
+                Value::from(u)
+            } else {
+                return Err(Error::Storage(format!(
+                    "CBOR integer {} is out of range",
+                    i128::from(i)
+                )));
+            }
+        }
+        Cbor::Float(f) => float_value(f)?,
+        Cbor::Bytes(bytes) => bytes_object(&bytes),
+        Cbor::Text(s) => Value::String(s),
+        Cbor::Array(items) => {
+            Value::Array(items.into_iter().map(from_cbor).collect::<Result<_>>()?)
+        }
+        Cbor::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let key = match key {
+                    Cbor::Text(key) => key,
+                    Cbor::Integer(i) => i128::from(i).to_string(),
+                    other => {
+                        return Err(Error::Storage(format!(
+                            "unsupported CBOR map key {:?}",
+                            other
+                        )))
+                    }
+                };
+                map.insert(key, from_cbor(value)?);
+            }
+            Value::Object(map)
+        }
+        Cbor::Tag(_, inner) => from_cbor(*inner)?,
+        other => {
+            return Err(Error::Storage(format!(
+                "unsupported CBOR value {:?}",
+                other
+            )))
+        }
+    })
+}
+
+fn to_msgpack(value: &Value) -> Result<rmpv::Value> {
+    use rmpv::Value as Msgpack;
+    Ok(match value {
+        Value::Null => Msgpack::Nil,
+        Value::Bool(b) => Msgpack::Boolean(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Msgpack::from(i),
+            (_, Some(u)) => Msgpack::from(u),
+            _ => Msgpack::F64(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => Msgpack::from(s.as_str()),
+        Value::Array(items) => Msgpack::Array(items.iter().map(to_msgpack).collect::<Result<_>>()?),
+        Value::Object(map) => match byte_string(map)? {
+            Some(bytes) => Msgpack::Binary(bytes),
+            None => Msgpack::Map(
+                map.iter()
+                    .map(|(k, v)| Ok((Msgpack::from(k.as_str()), to_msgpack(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+        },
+    })
+}
+
+fn from_msgpack(value: rmpv::Value) -> Result<Value> {
+    use rmpv::Value as Msgpack;
+    Ok(match value {
+        Msgpack::Nil => Value::Null,
+        Msgpack::Boolean(b) => Value::Bool(b),
+        Msgpack::Integer(i) => match (i.as_i64(), i.as_u64()) {
+            (Some(i), _) => Value::from(i),
+            (_, Some(u)) => Value::from(u),
+            _ => {
+                return Err(Error::Storage(format!(
+                    "MessagePack integer {} is out of range",
+                    i
+                )))
+            }
+        },
+        Msgpack::F32(f) => float_value(f64::from(f))?,
+        Msgpack::F64(f) => float_value(f)?,
+        Msgpack::String(s) => {
+            Value::String(s.into_str().ok_or_else(|| {
+                Error::Storage("MessagePack string is not valid UTF-8".to_string())
+            })?)
+        }
+        Msgpack::Binary(bytes) => bytes_object(&bytes),
+        Msgpack::Array(items) => {
+            Value::Array(items.into_iter().map(from_msgpack).collect::<Result<_>>()?)
+        }
+        Msgpack::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let key = match key {
+                    Msgpack::String(key) => key.into_str().ok_or_else(|| {
+                        Error::Storage("MessagePack map key is not valid UTF-8".to_string())
+                    })?,
+                    Msgpack::Integer(i) => i.to_string(),
+                    other => {
+                        return Err(Error::Storage(format!(
+                            "unsupported MessagePack map key {}",
+                            other
+                        )))
+                    }
+                };
+                map.insert(key, from_msgpack(value)?);
+            }
+            Value::Object(map)
+        }
+        Msgpack::Ext(kind, _) => {
+            return Err(Error::Storage(format!(
+                "unsupported MessagePack extension type {}",
+                kind
+            )))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "name": "app",
+            "count": -3,
+            "big": u64::MAX,
+            "ratio": 0.25,
+            "enabled": true,
+            "missing": null,
+            "tags": ["a", "b"],
+            "blob": {"$bytes": "AAEC/w=="},
+            "nested": {"deep": {"list": [1, {"x": 2}]}},
+        })
+    }
+
+    #[test]
+    fn binary_encodings_round_trip_through_json() {
+        for encoding in [
+            BinaryEncoding::Cbor,
+            BinaryEncoding::Msgpack,
+            BinaryEncoding::Json,
+        ] {
+            let bytes = encode_stored(&sample(), encoding).unwrap();
+            assert_eq!(decode_stored(&bytes).unwrap(), sample(), "{:?}", encoding);
+            let plain = encode(&sample(), encoding).unwrap();
+            assert_eq!(
+                decode(&plain, encoding).unwrap(),
+                sample(),
+                "{:?}",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn byte_strings_are_stored_natively() {
+        let value = json!({"$bytes": "AAEC/w=="});
+        let cbor: ciborium::Value =
+            ciborium::from_reader(&encode(&value, BinaryEncoding::Cbor).unwrap()[..]).unwrap();
+        assert_eq!(cbor, ciborium::Value::Bytes(vec![0, 1, 2, 255]));
+        let msgpack =
+            rmpv::decode::read_value(&mut &encode(&value, BinaryEncoding::Msgpack).unwrap()[..])
+                .unwrap();
+        assert_eq!(msgpack, rmpv::Value::Binary(vec![0, 1, 2, 255]));
+
+        let err = encode(&json!({"$bytes": "not base64!"}), BinaryEncoding::Cbor).unwrap_err();
+        assert!(matches!(err, Error::InvalidPayload(_)));
+    }
+
+    #[test]
+    fn base64_exports_convert_between_encodings() {
+        let cbor = to_base64(&sample(), for_format("cbor").unwrap()).unwrap();
+        let value = from_base64(&cbor, BinaryEncoding::Cbor).unwrap();
+        let msgpack = to_base64(&value, for_format("msgpack").unwrap()).unwrap();
+        assert_eq!(
+            from_base64(&msgpack, BinaryEncoding::Msgpack).unwrap(),
+            sample()
+        );
+        assert!(for_format("toml").is_none());
+        assert!(from_base64("%%%", BinaryEncoding::Cbor).is_err());
+    }
+
+    #[test]
+    fn headerless_payloads_are_json_and_unknown_encodings_fail() {
+        assert_eq!(decode_stored(br#"{"a":1}"#).unwrap(), json!({"a": 1}));
+        assert!(decode_stored(b"\0CFE\x09").is_err());
+        assert!(decode_stored(b"\0CFE").is_err());
+    }
+
+    #[test]
+    fn trailing_bytes_after_a_value_are_rejected() {
+        for encoding in [BinaryEncoding::Cbor, BinaryEncoding::Msgpack] {
+            let mut bytes = encode(&json!({"a": 1}), encoding).unwrap();
+            assert_eq!(decode(&bytes, encoding).unwrap(), json!({"a": 1}));
+            bytes.push(0);
+            assert!(decode(&bytes, encoding).is_err(), "{:?}", encoding);
+        }
+    }
+}
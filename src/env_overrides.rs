@@ -0,0 +1,318 @@
+/// Environment-variable overrides (`Builder::env_overrides`).
+///
+/// With prefix `MYAPP`, the variable `MYAPP__DATABASE__HOST` overrides the
+/// dotpath `database.host` of every loaded config that already has that key.
+/// Segments are matched case-insensitively against object keys (an exact
+/// match wins) and as indices on arrays.  The raw string is coerced to the
+/// JSON type of the value it replaces.
+///
+/// Variables are read once at plugin setup.  Overrides are applied to loaded
+/// data only; saves and patches restore the stored value for any key that
+/// still holds the override, so overrides are never persisted.
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::dotpath::{self, Segment};
+use crate::error::{Error, Result};
+
+/// Separator between the prefix and path segments in variable names.
+const SEPARATOR: &str = "__";
+
+/// A key of a loaded config that was replaced by an environment variable.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvOverride {
+    /// Dotpath of the overridden key, using the config's own key spelling.
+    pub path: String,
+    /// Name of the environment variable that supplied the value.
+    pub variable: String,
+}
+
+#[derive(Debug)]
+struct EnvVar {
+    name: String,
+    segments: Vec<String>,
+    raw: String,
+}
+
+/// Override variables captured at plugin setup, sorted by name.
+#[derive(Debug, Default)]
+pub(crate) struct EnvOverrides {
+    vars: Vec<EnvVar>,
+}
+
+impl EnvOverrides {
+    /// Captures every process environment variable starting with `{prefix}__`.
+    pub(crate) fn from_env(prefix: &str) -> Result<Self> {
+        Self::from_vars(prefix, std::env::vars())
+    }
+
+    pub(crate) fn from_vars(
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self> {
+        if prefix.is_empty() || prefix.ends_with('_') {
+            return Err(Error::InvalidPayload(format!(
+                "env override prefix '{}' must be non-empty and must not end with '_'",
+                prefix
+            )));
+        }
+        let lead = format!("{}{}", prefix, SEPARATOR);
+        let mut vars: Vec<EnvVar> = vars
+            .into_iter()
+            .filter_map(|(name, raw)| {
+                let rest = name.strip_prefix(&lead)?;
+                let segments: Vec<String> = rest.split(SEPARATOR).map(str::to_string).collect();
+                if segments.iter().any(|segment| segment.is_empty()) {
+                    return None;
+                }
+                Some(EnvVar {
+                    name,
+                    segments,
+                    raw,
+                })
+            })
+            .collect();
+        vars.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self { vars })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Applies every variable whose path exists in `data` and returns the
+    /// overridden keys.  Fails when a value cannot be coerced.
+    pub(crate) fn apply(&self, data: &mut Value) -> Result<Vec<EnvOverride>> {
+        let mut applied = Vec::new();
+        for var in &self.vars {
+            let Some(path) = resolve(data, &var.segments) else {
+                continue;
+            };
+            let target = lookup_mut(data, &path).ok_or_else(|| unresolved(&var.name))?;
+            *target = coerce(&var.raw, target)
+                .map_err(|reason| Error::EnvOverride(format!("{}: {}", var.name, reason)))?;
+            applied.push(EnvOverride {
+                path: dotpath::format(&path),
+                variable: var.name.clone(),
+            });
+        }
+        Ok(applied)
+    }
+
+    /// Replaces values in `data` that still equal an active override with the
+    /// value stored in `stored` (the file's current content).  Keys the file
+    /// does not have were never overridden and are left alone.
+    pub(crate) fn restore(&self, stored: Option<&Value>, data: &mut Value) -> Result<()> {
+        let Some(stored) = stored else {
+            return Ok(());
+        };
+        for var in &self.vars {
+            let Some(path) = resolve(data, &var.segments) else {
+                continue;
+            };
+            let Some(original) = lookup(stored, &path) else {
+                continue;
+            };
+            let current = lookup_mut(data, &path).ok_or_else(|| unresolved(&var.name))?;
+            if coerce(&var.raw, current).is_ok_and(|value| value == *current) {
+                *current = original.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error for a variable whose resolved path could not be looked up again.
+fn unresolved(variable: &str) -> Error {
+    Error::EnvOverride(format!("{}: resolved path does not exist", variable))
+}
+
+/// Resolves variable segments to concrete path segments present in `data`.
+fn resolve(data: &Value, segments: &[String]) -> Option<Vec<Segment>> {
+    let mut node = data;
+    let mut path = Vec::with_capacity(segments.len());
+    for segment in segments {
+        match node {
+            Value::Object(map) => {
+                let key = if map.contains_key(segment) {
+                    segment.clone()
+                } else {
+                    map.keys()
+                        .find(|key| key.eq_ignore_ascii_case(segment))?
+                        .clone()
+                };
+                node = &map[&key];
+                path.push(Segment::Key(key));
+            }
+            Value::Array(items) => {
+                let index: usize = segment.parse().ok()?;
+                node = items.get(index)?;
+                path.push(Segment::Index(index));
+            }
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+fn lookup<'a>(root: &'a Value, path: &[Segment]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |node, segment| match segment {
+        Segment::Key(key) => node.as_object()?.get(key),
+        Segment::Index(index) => node.as_array()?.get(*index),
+        _ => None,
+    })
+}
+
+fn lookup_mut<'a>(root: &'a mut Value, path: &[Segment]) -> Option<&'a mut Value> {
+    path.iter().try_fold(root, |node, segment| match segment {
+        Segment::Key(key) => node.as_object_mut()?.get_mut(key),
+        Segment::Index(index) => node.as_array_mut()?.get_mut(*index),
+        _ => None,
+    })
+}
+
+/// Coerces `raw` to the JSON type of `existing`.
+fn coerce(raw: &str, existing: &Value) -> std::result::Result<Value, String> {
+    match existing {
+        Value::String(_) => Ok(Value::String(raw.to_string())),
+        Value::Bool(_) => match raw.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" | "off" => Ok(Value::Bool(false)),
+            _ => Err(format!("expected a boolean, got '{}'", raw)),
+        },
+        Value::Number(n) if n.is_f64() => raw
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("expected a number, got '{}'", raw)),
+        Value::Number(_) => {
+            let raw = raw.trim();
+            raw.parse::<i64>()
+                .map(Value::from)
+                .or_else(|_| raw.parse::<u64>().map(Value::from))
+                .map_err(|_| format!("expected an integer, got '{}'", raw))
+        }
+        Value::Array(_) | Value::Object(_) => {
+            let expected = if existing.is_array() {
+                "array"
+            } else {
+                "object"
+            };
+            match serde_json::from_str::<Value>(raw) {
+                Ok(value)
+                    if value.is_array() == existing.is_array()
+                        && value.is_object() == existing.is_object() =>
+                {
+                    Ok(value)
+                }
+                _ => Err(format!("expected a JSON {}", expected)),
+            }
+        }
+        Value::Null => {
+            Ok(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn overrides(vars: &[(&str, &str)]) -> EnvOverrides {
+        EnvOverrides::from_vars(
+            "MYAPP",
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn apply_coerces_to_existing_types_and_reports_paths() {
+        let env = overrides(&[
+            ("MYAPP__DATABASE__HOST", "db.internal"),
+            ("MYAPP__DATABASE__PORT", "6543"),
+            ("MYAPP__DATABASE__MAXCONNECTIONS", "8"),
+            ("MYAPP__RATIO", "0.25"),
+            ("MYAPP__TELEMETRY", "off"),
+            ("MYAPP__SERVERS__1__NAME", "b2"),
+            ("MYAPP__TAGS", r#"["x","y"]"#),
+            ("MYAPP__MISSING", "ignored"),
+            ("OTHER__DATABASE__HOST", "ignored"),
+        ]);
+        let mut data = json!({
+            "database": {"host": "localhost", "port": 5432, "maxConnections": 4},
+            "ratio": 0.5,
+            "telemetry": true,
+            "servers": [{"name": "a"}, {"name": "b"}],
+            "tags": []
+        });
+        let applied = env.apply(&mut data).unwrap();
+        assert_eq!(
+            data,
+            json!({
+                "database": {"host": "db.internal", "port": 6543, "maxConnections": 8},
+                "ratio": 0.25,
+                "telemetry": false,
+                "servers": [{"name": "a"}, {"name": "b2"}],
+                "tags": ["x", "y"]
+            })
+        );
+        let paths: Vec<&str> = applied.iter().map(|o| o.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "database.host",
+                "database.maxConnections",
+                "database.port",
+                "ratio",
+                "servers.1.name",
+                "tags",
+                "telemetry"
+            ]
+        );
+        assert_eq!(applied[0].variable, "MYAPP__DATABASE__HOST");
+    }
+
+    #[test]
+    fn apply_rejects_values_that_do_not_match_the_existing_type() {
+        let env = overrides(&[("MYAPP__PORT", "not-a-number")]);
+        let err = env.apply(&mut json!({"port": 1})).unwrap_err();
+        assert!(matches!(err, Error::EnvOverride(_)));
+        assert!(err.to_string().contains("MYAPP__PORT"));
+
+        let env = overrides(&[("MYAPP__TAGS", r#"{"a":1}"#)]);
+        assert!(env.apply(&mut json!({"tags": []})).is_err());
+    }
+
+    #[test]
+    fn restore_puts_back_stored_values_for_unchanged_overrides() {
+        let env = overrides(&[
+            ("MYAPP__DATABASE__HOST", "db.internal"),
+            ("MYAPP__DATABASE__PORT", "6543"),
+            ("MYAPP__DEBUG", "true"),
+        ]);
+        let stored = json!({"database": {"host": "localhost", "port": 5432}});
+        // `port` was edited by the user; `debug` is new and was never overridden.
+        let mut data = json!({
+            "database": {"host": "db.internal", "port": 7000},
+            "debug": true
+        });
+        env.restore(Some(&stored), &mut data).unwrap();
+        assert_eq!(
+            data,
+            json!({"database": {"host": "localhost", "port": 7000}, "debug": true})
+        );
+    }
+
+    #[test]
+    fn prefix_must_not_be_empty_or_end_with_underscore() {
+        assert!(EnvOverrides::from_vars("", Vec::new()).is_err());
+        assert!(EnvOverrides::from_vars("MYAPP_", Vec::new()).is_err());
+        assert!(overrides(&[("MYAPP____X", "1")]).is_empty());
+    }
+}
@@ -15,10 +15,42 @@ pub enum Error {
     #[error("keyring error: {0}")]
     Keyring(String),
 
+    /// The OS keyring exists but cannot be used (e.g. no running Secret
+    /// Service, locked credential store).
+    #[error("keyring unavailable: {0}")]
+    KeyringUnavailable(String),
+
     /// Errors when resolving a dotpath inside a JSON value.
     #[error("dotpath error: {0}")]
     Dotpath(String),
 
+    /// A patch document could not be applied (bad JSON Patch operation,
+    /// failed `test`, missing path, ...).
+    #[error("patch error: {0}")]
+    Patch(String),
+
+    /// An environment-variable override could not be coerced to the type of
+    /// the config value it replaces.
+    #[error("environment override error: {0}")]
+    EnvOverride(String),
+
+    /// A write tried to change a key locked by the managed policy, or the
+    /// policy file could not be read.
+    #[error("policy error: {0}")]
+    Policy(String),
+
+    /// The invoking window's capability scope does not allow the operation.
+    #[error("forbidden: {0}")]
+    Forbidden(String),
+
+    /// A write targeted a base directory configured as read-only.
+    #[error("read-only: {0}")]
+    ReadOnly(String),
+
+    /// The invoking window was closed while the command waited for its file.
+    #[error("cancelled: {0}")]
+    Cancelled(String),
+
     /// Invalid payload sent from the frontend (wrong field combination, bad value, etc.).
     #[error("invalid payload: {0}")]
     InvalidPayload(String),
@@ -34,7 +66,12 @@ pub enum Error {
 
 impl From<keyring::Error> for Error {
     fn from(e: keyring::Error) -> Self {
-        Error::Keyring(e.to_string())
+        match e {
+            keyring::Error::PlatformFailure(_) | keyring::Error::NoStorageAccess(_) => {
+                Error::KeyringUnavailable(e.to_string())
+            }
+            _ => Error::Keyring(e.to_string()),
+        }
     }
 }
 
@@ -70,7 +107,14 @@ impl Serialize for Error {
             Error::Io(_) => "io",
             Error::Storage(_) => "storage",
             Error::Keyring(_) => "keyring",
+            Error::KeyringUnavailable(_) => "keyring_unavailable",
             Error::Dotpath(_) => "dotpath",
+            Error::Patch(_) => "patch",
+            Error::EnvOverride(_) => "env_override",
+            Error::Policy(_) => "policy",
+            Error::Forbidden(_) => "forbidden",
+            Error::ReadOnly(_) => "read_only",
+            Error::Cancelled(_) => "cancelled",
             Error::InvalidPayload(_) => "invalid_payload",
             Error::Json(_) => "json",
             #[cfg(mobile)]
@@ -84,3 +128,23 @@ impl Serialize for Error {
         map.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyring_platform_failures_map_to_unavailable() {
+        let err: Error =
+            keyring::Error::PlatformFailure(Box::new(std::io::Error::other("no dbus"))).into();
+        assert!(matches!(err, Error::KeyringUnavailable(_)));
+
+        let err: Error =
+            keyring::Error::NoStorageAccess(Box::new(std::io::Error::other("locked"))).into();
+        let value = serde_json::to_value(&err).unwrap();
+        assert_eq!(value["kind"], "keyring_unavailable");
+
+        let err: Error = keyring::Error::NoEntry.into();
+        assert!(matches!(err, Error::Keyring(_)));
+    }
+}
@@ -0,0 +1,208 @@
+/// Process-wide cache of Argon2id-derived cipher keys.
+///
+/// Argon2id is slow by design, and `BinaryArgon2Backend` needs a key for
+/// every read and write.  Derived keys are cached by (password, salt,
+/// Argon2 parameters) — identified only through a SHA-256 digest — bounded
+/// to [`CAPACITY`] entries with the least recently used evicted first, and
+/// zeroed when evicted.
+///
+/// Commands call [`prefetch`] before the synchronous file operation so the
+/// derivation runs on the blocking thread pool instead of an async worker.
+/// For writes it also prepares a fresh salt whose key is already derived;
+/// the backend consumes it with [`fresh_salt`].
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+use argon2::{Argon2, Params};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+/// Maximum number of cached keys.
+const CAPACITY: usize = 32;
+/// Maximum number of prepared write salts.
+const PENDING_CAPACITY: usize = 8;
+/// Salt length of the Argon2 file format.
+pub(crate) const SALT_LEN: usize = 16;
+
+type Digest32 = [u8; 32];
+
+struct Entry {
+    id: Digest32,
+    key: Zeroizing<[u8; 32]>,
+}
+
+struct PendingSalt {
+    owner: Digest32,
+    salt: [u8; SALT_LEN],
+}
+
+#[derive(Default)]
+struct KeyCache {
+    entries: VecDeque<Entry>,
+    pending: VecDeque<PendingSalt>,
+}
+
+impl KeyCache {
+    fn get(&mut self, id: &Digest32) -> Option<Zeroizing<[u8; 32]>> {
+        let index = self.entries.iter().position(|entry| entry.id == *id)?;
+        let entry = self.entries.remove(index)?;
+        let key = entry.key.clone();
+        self.entries.push_back(entry);
+        Some(key)
+    }
+
+    fn insert(&mut self, id: Digest32, key: Zeroizing<[u8; 32]>) {
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.push_back(Entry { id, key });
+        while self.entries.len() > CAPACITY {
+            // Dropping the entry zeroes its key.
+            self.entries.pop_front();
+        }
+    }
+}
+
+fn cache() -> MutexGuard<'static, KeyCache> {
+    static CACHE: OnceLock<Mutex<KeyCache>> = OnceLock::new();
+    CACHE
+        .get_or_init(|| Mutex::new(KeyCache::default()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+fn entry_id(password: &str, salt: &[u8]) -> Digest32 {
+    let params = Params::default();
+    let mut hasher = Sha256::new();
+    hasher.update(b"configurate/argon2-key\0");
+    hasher.update((password.len() as u64).to_le_bytes());
+    hasher.update(password.as_bytes());
+    hasher.update(salt);
+    hasher.update(params.m_cost().to_le_bytes());
+    hasher.update(params.t_cost().to_le_bytes());
+    hasher.update(params.p_cost().to_le_bytes());
+    hasher.finalize().into()
+}
+
+fn owner_id(password: &str) -> Digest32 {
+    let mut hasher = Sha256::new();
+    hasher.update(b"configurate/argon2-owner\0");
+    hasher.update(password.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Returns the cached key for `(password, salt)`, marking it recently used.
+pub(crate) fn cached(password: &str, salt: &[u8]) -> Option<Zeroizing<[u8; 32]>> {
+    cache().get(&entry_id(password, salt))
+}
+
+/// Returns the key for `(password, salt)`, deriving and caching it on a miss.
+pub(crate) fn derive(password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+    if let Some(key) = cached(password, salt) {
+        return Ok(key);
+    }
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut *key)
+        .map_err(|e| Error::Storage(format!("argon2 key derivation failed: {}", e)))?;
+    cache().insert(entry_id(password, salt), key.clone());
+    Ok(key)
+}
+
+/// Takes a salt prepared by [`prefetch`] for `password`, or generates a new
+/// one.  Each prepared salt is handed out once.
+pub(crate) fn fresh_salt(password: &str) -> [u8; SALT_LEN] {
+    let owner = owner_id(password);
+    let mut cache = cache();
+    if let Some(index) = cache.pending.iter().position(|p| p.owner == owner) {
+        if let Some(pending) = cache.pending.remove(index) {
+            return pending.salt;
+        }
+    }
+    drop(cache);
+    let mut salt = [0u8; SALT_LEN];
+    rand::rng().fill_bytes(&mut salt);
+    salt
+}
+
+/// Derives keys on the blocking thread pool ahead of a file operation.
+///
+/// `existing_salt` is the salt of the file about to be read or rewritten;
+/// `fresh` prepares a new salt for a write that does not reuse it.
+pub(crate) async fn prefetch(
+    password: Zeroizing<String>,
+    existing_salt: Option<[u8; SALT_LEN]>,
+    fresh: bool,
+) -> Result<()> {
+    let existing = existing_salt.filter(|salt| cached(&password, salt).is_none());
+    if existing.is_none() && !fresh {
+        return Ok(());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Some(salt) = existing {
+            derive(&password, &salt)?;
+        }
+        if fresh {
+            let mut salt = [0u8; SALT_LEN];
+            rand::rng().fill_bytes(&mut salt);
+            derive(&password, &salt)?;
+            let mut cache = cache();
+            cache.pending.push_back(PendingSalt {
+                owner: owner_id(&password),
+                salt,
+            });
+            while cache.pending.len() > PENDING_CAPACITY {
+                cache.pending.pop_front();
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| Error::Storage(format!("argon2 key derivation task failed: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_keys_are_cached_per_password_and_salt() {
+        let salt = [7u8; SALT_LEN];
+        assert!(cached("key-cache-test-a", &salt).is_none());
+        let key = derive("key-cache-test-a", &salt).unwrap();
+        assert_eq!(*cached("key-cache-test-a", &salt).unwrap(), *key);
+        assert!(cached("key-cache-test-b", &salt).is_none());
+        assert!(cached("key-cache-test-a", &[8u8; SALT_LEN]).is_none());
+        assert_ne!(entry_id("ab", b"c"), entry_id("a", b"bc"));
+    }
+
+    #[test]
+    fn cache_is_bounded() {
+        let mut cache = KeyCache::default();
+        let id = |i: usize| entry_id("bound", &[i as u8; SALT_LEN]);
+        for i in 0..=CAPACITY {
+            cache.insert(id(i), Zeroizing::new([i as u8; 32]));
+            // Keep the first key recently used.
+            assert!(cache.get(&id(0)).is_some());
+        }
+        assert_eq!(cache.entries.len(), CAPACITY);
+        assert!(cache.get(&id(1)).is_none());
+        assert_eq!(*cache.get(&id(CAPACITY)).unwrap(), [CAPACITY as u8; 32]);
+    }
+
+    #[test]
+    fn prepared_salts_are_handed_out_once() {
+        tauri::async_runtime::block_on(prefetch(
+            Zeroizing::new("key-cache-pending".to_string()),
+            None,
+            true,
+        ))
+        .unwrap();
+        let salt = fresh_salt("key-cache-pending");
+        assert!(cached("key-cache-pending", &salt).is_some());
+        let next = fresh_salt("key-cache-pending");
+        assert_ne!(salt, next);
+        assert!(cached("key-cache-pending", &next).is_none());
+    }
+}
@@ -0,0 +1,215 @@
+/// Per-config inventory of the keyring ids the plugin has written.
+///
+/// The manifest lives beside the config file as `{file_name}.keyring-manifest`
+/// and records ids grouped by keyring service and account.  It only holds
+/// ids — never secret values — and lets `keyring_gc` find entries whose field
+/// has since been removed from the schema.
+///
+/// Ids are recorded *before* the secrets are written, so the manifest is
+/// always a superset of what exists in the secret store.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::models::KeyringOptions;
+use crate::storage;
+
+/// Suffix appended to the config file name to build the manifest file name.
+pub(crate) const MANIFEST_SUFFIX: &str = ".keyring-manifest";
+
+/// Upper bound for the manifest file size.  Manifests only contain ids, so
+/// anything larger is treated as corrupt.
+const MAX_MANIFEST_BYTES: usize = 1024 * 1024;
+
+/// Returns the manifest path for the config file at `config_path`.
+pub(crate) fn manifest_path(config_path: &Path) -> PathBuf {
+    let mut name = config_path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    name.push(MANIFEST_SUFFIX);
+    config_path.with_file_name(name)
+}
+
+/// Returns `true` if `name` is a keyring manifest file name.
+pub(crate) fn is_manifest_filename(name: &str) -> bool {
+    name.len() > MANIFEST_SUFFIX.len() && name.ends_with(MANIFEST_SUFFIX)
+}
+
+/// A single keyring id tracked by the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct KeyringManifestEntry {
+    pub service: String,
+    pub account: String,
+    pub id: String,
+}
+
+/// On-disk manifest: `service → account → ids`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct KeyringManifest {
+    version: u32,
+    entries: BTreeMap<String, BTreeMap<String, BTreeSet<String>>>,
+}
+
+impl KeyringManifest {
+    const VERSION: u32 = 1;
+
+    /// Loads the manifest at `path`.  A missing file is an empty manifest.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let bytes = match storage::read_file_bounded(path, MAX_MANIFEST_BYTES) {
+            Ok(bytes) => bytes,
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(e),
+        };
+        let manifest: Self = serde_json::from_slice(&bytes).map_err(|e| {
+            Error::Storage(format!(
+                "invalid keyring manifest '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+        if manifest.version > Self::VERSION {
+            return Err(Error::Storage(format!(
+                "keyring manifest '{}' has unsupported version {}",
+                path.display(),
+                manifest.version
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// Writes the manifest to `path`, removing the file when no ids remain.
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        if self.entries.is_empty() {
+            return match std::fs::remove_file(path) {
+                Ok(_) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e.into()),
+            };
+        }
+        let manifest = Self {
+            version: Self::VERSION,
+            entries: self.entries.clone(),
+        };
+        let bytes = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        storage::write_file_safely(path, &bytes)
+    }
+
+    pub(crate) fn record<'a>(
+        &mut self,
+        opts: &KeyringOptions,
+        ids: impl IntoIterator<Item = &'a str>,
+    ) {
+        let set = self
+            .entries
+            .entry(opts.service.clone())
+            .or_default()
+            .entry(opts.account.clone())
+            .or_default();
+        set.extend(ids.into_iter().map(str::to_string));
+        if set.is_empty() {
+            self.forget(opts, []);
+        }
+    }
+
+    pub(crate) fn forget<'a>(
+        &mut self,
+        opts: &KeyringOptions,
+        ids: impl IntoIterator<Item = &'a str>,
+    ) {
+        let Some(accounts) = self.entries.get_mut(&opts.service) else {
+            return;
+        };
+        if let Some(set) = accounts.get_mut(&opts.account) {
+            for id in ids {
+                set.remove(id);
+            }
+            if set.is_empty() {
+                accounts.remove(&opts.account);
+            }
+        }
+        if accounts.is_empty() {
+            self.entries.remove(&opts.service);
+        }
+    }
+
+    /// Lists tracked ids, restricted to `filter`'s service/account when given.
+    pub(crate) fn entries(&self, filter: Option<&KeyringOptions>) -> Vec<KeyringManifestEntry> {
+        let mut out = Vec::new();
+        for (service, accounts) in &self.entries {
+            for (account, ids) in accounts {
+                if filter.is_some_and(|f| f.service != *service || f.account != *account) {
+                    continue;
+                }
+                out.extend(ids.iter().map(|id| KeyringManifestEntry {
+                    service: service.clone(),
+                    account: account.clone(),
+                    id: id.clone(),
+                }));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opts(account: &str) -> KeyringOptions {
+        KeyringOptions {
+            service: "svc".to_string(),
+            account: account.to_string(),
+        }
+    }
+
+    #[test]
+    fn manifest_path_appends_suffix() {
+        let path = manifest_path(Path::new("/cfg/app.json"));
+        assert_eq!(path, Path::new("/cfg/app.json.keyring-manifest"));
+        assert!(is_manifest_filename("app.json.keyring-manifest"));
+        assert!(!is_manifest_filename(".keyring-manifest"));
+        assert!(!is_manifest_filename("app.json"));
+    }
+
+    #[test]
+    fn record_forget_and_filter() {
+        let mut manifest = KeyringManifest::default();
+        manifest.record(&opts("a"), ["token", "password"]);
+        manifest.record(&opts("b"), ["token"]);
+
+        let a = manifest.entries(Some(&opts("a")));
+        assert_eq!(
+            a.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+            vec!["password", "token"]
+        );
+        assert_eq!(manifest.entries(None).len(), 3);
+
+        manifest.forget(&opts("a"), ["token", "password"]);
+        assert!(manifest.entries(Some(&opts("a"))).is_empty());
+        assert_eq!(manifest.entries(None).len(), 1);
+    }
+
+    #[test]
+    fn save_roundtrip_and_removes_empty_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = manifest_path(&dir.path().join("app.json"));
+
+        let mut manifest = KeyringManifest::default();
+        manifest.record(&opts("a"), ["token"]);
+        manifest.save(&path).unwrap();
+
+        let mut loaded = KeyringManifest::load(&path).unwrap();
+        assert_eq!(loaded.entries(None), manifest.entries(None));
+
+        loaded.forget(&opts("a"), ["token"]);
+        loaded.save(&path).unwrap();
+        assert!(!path.exists());
+        assert!(KeyringManifest::load(&path).unwrap().entries(None).is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-/// OS keyring integration via the `keyring` crate.
+/// Keyring-field secret access on top of a [`SecretStore`].
 ///
 /// Each entry is stored with:
 ///   - service  = `opts.service`          (e.g. "my-app")
@@ -7,8 +7,22 @@
 /// `/` is used as the separator (not `:`) because Windows Credential Manager
 /// builds the target name as `{user}.{service}`, and `:` in that string can
 /// be misinterpreted by some OS keyring backends.
+///
+/// Values are stored as text:
+///   - strings are stored verbatim, so existing entries keep working;
+///   - any other JSON value (and strings that happen to start with the tag)
+///     is stored as [`TYPED_SECRET_TAG`] followed by its JSON encoding and
+///     comes back with its original JSON type.
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use crate::dotpath;
 use crate::error::{Error, Result};
 use crate::models::KeyringOptions;
+use crate::secret_store::SecretStore;
+
+/// Prefix marking a secret whose remainder is a JSON document.
+pub(crate) const TYPED_SECRET_TAG: &str = "configurate:json:";
 
 /// Validates that a keyring `id` does not contain characters that would
 /// interfere with the `{account}/{id}` user string format.
@@ -27,6 +41,185 @@ pub(crate) fn validate_entry_id(id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Returns `true` if `id` is an id template: it parses and contains at least
+/// one known placeholder (`{index}`, `{key}`, `{field:name}`, ...).  Ids with
+/// other braces, such as `a{b` or `{name}`, stay literal ids.
+///
+/// Templates are required for entries with wildcard dotpaths and are
+/// rendered per matched element by [`render_id`].
+pub(crate) fn is_id_template(id: &str) -> bool {
+    parse_template(id).is_ok_and(|parts| {
+        parts
+            .iter()
+            .any(|part| !matches!(part, TemplatePart::Literal(_)))
+    })
+}
+
+/// Returns `true` if `template` is an id template with an `{index}` /
+/// `{indexN}` placeholder, whose rendered ids depend on array positions.
+pub(crate) fn has_index_placeholder(template: &str) -> bool {
+    parse_template(template).is_ok_and(|parts| {
+        parts
+            .iter()
+            .any(|part| matches!(part, TemplatePart::Index(_)))
+    })
+}
+
+/// Literal text or placeholder inside an id template.
+enum TemplatePart<'a> {
+    Literal(&'a str),
+    /// `{index}` / `{indexN}` — array position of the last / N-th wildcard.
+    Index(Option<usize>),
+    /// `{key}` / `{keyN}` — object key of the last / N-th wildcard.
+    Key(Option<usize>),
+    /// `{field:name}` — scalar field `name` of the element matched by the last wildcard.
+    Field(&'a str),
+}
+
+/// Splits a template into literal text and placeholders.
+fn parse_template(template: &str) -> Result<Vec<TemplatePart<'_>>> {
+    let invalid = |reason: &str| {
+        Error::InvalidPayload(format!("invalid keyring id template '{}': {}", template, reason))
+    };
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            parts.push(TemplatePart::Literal(&rest[..open]));
+        }
+        let close = rest[open..]
+            .find('}')
+            .map(|c| open + c)
+            .ok_or_else(|| invalid("unclosed '{'"))?;
+        let name = &rest[open + 1..close];
+        let ordinal = |suffix: &str| -> Result<Option<usize>> {
+            if suffix.is_empty() {
+                Ok(None)
+            } else {
+                suffix
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| invalid(&format!("unknown placeholder '{{{}}}'", name)))
+            }
+        };
+        let placeholder = if let Some(field) = name.strip_prefix("field:") {
+            if field.is_empty() {
+                return Err(invalid("'{field:}' requires a field name"));
+            }
+            TemplatePart::Field(field)
+        } else if let Some(suffix) = name.strip_prefix("index") {
+            TemplatePart::Index(ordinal(suffix)?)
+        } else if let Some(suffix) = name.strip_prefix("key") {
+            TemplatePart::Key(ordinal(suffix)?)
+        } else {
+            return Err(invalid(&format!("unknown placeholder '{{{}}}'", name)));
+        };
+        parts.push(placeholder);
+        rest = &rest[close + 1..];
+    }
+    if rest.contains('}') {
+        return Err(invalid("unmatched '}'"));
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Literal(rest));
+    }
+    Ok(parts)
+}
+
+/// Validates an id template without rendering it.
+pub(crate) fn validate_id_template(template: &str) -> Result<()> {
+    validate_entry_id(template)?;
+    if parse_template(template)?
+        .iter()
+        .all(|part| matches!(part, TemplatePart::Literal(_)))
+    {
+        return Err(Error::InvalidPayload(format!(
+            "keyring id template '{}' must contain a placeholder",
+            template
+        )));
+    }
+    Ok(())
+}
+
+/// Renders an id template for one element matched by a wildcard dotpath.
+pub(crate) fn render_id(template: &str, steps: &[dotpath::WildcardStep<'_>]) -> Result<String> {
+    let step = |ordinal: Option<usize>, what: &str| {
+        let pos = ordinal.unwrap_or(steps.len().saturating_sub(1));
+        steps.get(pos).copied().ok_or_else(|| {
+            Error::InvalidPayload(format!(
+                "keyring id template '{}' references {} of wildcard {} but the dotpath has {}",
+                template,
+                what,
+                pos,
+                steps.len()
+            ))
+        })
+    };
+    let mut id = String::new();
+    for part in parse_template(template)? {
+        match part {
+            TemplatePart::Literal(literal) => id.push_str(literal),
+            TemplatePart::Index(ordinal) => {
+                let index = step(ordinal, "the index")?.index.ok_or_else(|| {
+                    Error::InvalidPayload(format!(
+                        "keyring id template '{}' uses {{index}} for a wildcard over an object; use {{key}}",
+                        template
+                    ))
+                })?;
+                id.push_str(&index.to_string());
+            }
+            TemplatePart::Key(ordinal) => {
+                let key = step(ordinal, "the key")?.key.ok_or_else(|| {
+                    Error::InvalidPayload(format!(
+                        "keyring id template '{}' uses {{key}} for a wildcard over an array; use {{index}}",
+                        template
+                    ))
+                })?;
+                id.push_str(key);
+            }
+            TemplatePart::Field(field) => {
+                let element = step(None, "a field")?.element;
+                match element.get(field) {
+                    Some(Value::String(s)) if !s.is_empty() => id.push_str(s),
+                    Some(v @ (Value::Number(_) | Value::Bool(_))) => id.push_str(&v.to_string()),
+                    _ => {
+                        return Err(Error::InvalidPayload(format!(
+                            "keyring id template '{}' requires a non-empty string, number or \
+                             boolean field '{}' on every matched element",
+                            template, field
+                        )))
+                    }
+                }
+            }
+        }
+    }
+    validate_entry_id(&id)?;
+    Ok(id)
+}
+
+/// Returns `true` if `id` could have been rendered from `template`.
+/// Every placeholder matches one or more characters.
+pub(crate) fn template_matches(template: &str, id: &str) -> bool {
+    let Ok(parts) = parse_template(template) else {
+        return false;
+    };
+    fn matches(parts: &[TemplatePart<'_>], id: &str) -> bool {
+        match parts.split_first() {
+            None => id.is_empty(),
+            Some((TemplatePart::Literal(literal), rest)) => {
+                id.strip_prefix(literal).is_some_and(|tail| matches(rest, tail))
+            }
+            Some((_, rest)) => id
+                .char_indices()
+                .skip(1)
+                .map(|(i, _)| i)
+                .chain(std::iter::once(id.len()))
+                .any(|i| !id.is_empty() && matches(rest, &id[i..])),
+        }
+    }
+    matches(&parts, id)
+}
+
 fn validate_opts(opts: &KeyringOptions) -> Result<()> {
     if opts.service.trim().is_empty() {
         return Err(Error::InvalidPayload(
@@ -56,53 +249,247 @@ fn build_user(opts: &KeyringOptions, id: &str) -> String {
     format!("{}/{}", opts.account, id)
 }
 
-/// Stores `value` in the OS keyring.
+/// Encodes `value` into the text stored in the secret store.
+fn encode_value(value: &Value) -> Result<Zeroizing<String>> {
+    match value {
+        Value::String(s) if !s.starts_with(TYPED_SECRET_TAG) => Ok(Zeroizing::new(s.clone())),
+        other => {
+            let json = Zeroizing::new(
+                serde_json::to_string(other).map_err(|e| Error::Keyring(e.to_string()))?,
+            );
+            Ok(Zeroizing::new(format!("{}{}", TYPED_SECRET_TAG, json.as_str())))
+        }
+    }
+}
+
+/// Decodes text read from the secret store back into its JSON value.
+fn decode_value(id: &str, secret: Zeroizing<String>) -> Result<Value> {
+    match secret.strip_prefix(TYPED_SECRET_TAG) {
+        Some(json) => serde_json::from_str(json).map_err(|e| {
+            Error::Keyring(format!(
+                "keyring entry '{}' has a malformed typed value: {}",
+                id, e
+            ))
+        }),
+        None => Ok(Value::String(secret.to_string())),
+    }
+}
+
+/// Rejects `value` when its encoded form exceeds the store's size limit.
+pub(crate) fn check_size(store: &dyn SecretStore, id: &str, value: &Value) -> Result<()> {
+    let Some(max) = store.max_secret_size() else {
+        return Ok(());
+    };
+    let size = store.secret_size(&encode_value(value)?);
+    if size > max {
+        return Err(Error::InvalidPayload(format!(
+            "keyring entry '{}' is {} bytes when stored, exceeding the secret store limit of {} bytes",
+            id, size, max
+        )));
+    }
+    Ok(())
+}
+
+/// Stores `value` in the secret store.
 /// service = `opts.service`, user = `{account}/{id}`.
 /// If an existing entry exists it will be overwritten.
-pub fn set(opts: &KeyringOptions, id: &str, value: &str) -> Result<()> {
+pub fn set(store: &dyn SecretStore, opts: &KeyringOptions, id: &str, value: &Value) -> Result<()> {
     validate_opts(opts)?;
     validate_entry_id(id)?;
+    check_size(store, id, value)?;
     let user = build_user(opts, id);
-    let entry = keyring::Entry::new(&opts.service, &user)?;
-    entry.set_password(value)?;
-    Ok(())
+    store.set(&opts.service, &user, &encode_value(value)?)
 }
 
-/// Retrieves the value from the OS keyring.
+/// Retrieves the value from the secret store.
 /// service = `opts.service`, user = `{account}/{id}`.
-pub fn get(opts: &KeyringOptions, id: &str) -> Result<String> {
-    validate_opts(opts)?;
-    validate_entry_id(id)?;
-    let user = build_user(opts, id);
-    let entry = keyring::Entry::new(&opts.service, &user)?;
-    let password = entry.get_password()?;
-    Ok(password)
+pub fn get(store: &dyn SecretStore, opts: &KeyringOptions, id: &str) -> Result<Value> {
+    get_optional(store, opts, id)?.ok_or_else(|| {
+        Error::Keyring(format!(
+            "No matching entry found in secure storage for keyring id '{}'",
+            id
+        ))
+    })
 }
 
 /// Like `get`, but returns `Ok(None)` when the entry does not exist instead of an error.
 /// Used for optional keyring fields.
-pub fn get_optional(opts: &KeyringOptions, id: &str) -> Result<Option<String>> {
+pub fn get_optional(
+    store: &dyn SecretStore,
+    opts: &KeyringOptions,
+    id: &str,
+) -> Result<Option<Value>> {
     validate_opts(opts)?;
     validate_entry_id(id)?;
     let user = build_user(opts, id);
-    let entry = keyring::Entry::new(&opts.service, &user)?;
-    match entry.get_password() {
-        Ok(pwd) => Ok(Some(pwd)),
-        Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(e.into()),
-    }
+    store
+        .get(&opts.service, &user)?
+        .map(|secret| decode_value(id, Zeroizing::new(secret)))
+        .transpose()
 }
 
-/// Deletes the entry from the OS keyring.
+/// Deletes the entry from the secret store.
 /// Returns `Ok(())` even when the entry does not exist.
-pub fn delete(opts: &KeyringOptions, id: &str) -> Result<()> {
+pub fn delete(store: &dyn SecretStore, opts: &KeyringOptions, id: &str) -> Result<()> {
     validate_opts(opts)?;
     validate_entry_id(id)?;
     let user = build_user(opts, id);
-    let entry = keyring::Entry::new(&opts.service, &user)?;
-    match entry.delete_credential() {
-        Ok(_) => Ok(()),
-        Err(keyring::Error::NoEntry) => Ok(()),
-        Err(e) => Err(e.into()),
+    store.delete(&opts.service, &user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret_store::MemorySecretStore;
+    use serde_json::json;
+
+    fn opts() -> KeyringOptions {
+        KeyringOptions {
+            service: "svc".to_string(),
+            account: "acc".to_string(),
+        }
+    }
+
+    #[test]
+    fn set_get_delete_roundtrip_uses_account_prefixed_user() {
+        let store = MemorySecretStore::new();
+        set(&store, &opts(), "api-key", &json!("secret")).unwrap();
+
+        assert_eq!(get(&store, &opts(), "api-key").unwrap(), json!("secret"));
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/api-key"]);
+
+        delete(&store, &opts(), "api-key").unwrap();
+        assert_eq!(get_optional(&store, &opts(), "api-key").unwrap(), None);
+    }
+
+    #[test]
+    fn get_missing_entry_is_keyring_error() {
+        let store = MemorySecretStore::new();
+        let err = get(&store, &opts(), "missing").unwrap_err();
+        assert!(matches!(err, Error::Keyring(_)));
+    }
+
+    #[test]
+    fn invalid_options_are_rejected_before_store_access() {
+        let store = MemorySecretStore::new();
+        let bad = KeyringOptions {
+            service: " ".to_string(),
+            account: "acc".to_string(),
+        };
+        assert!(set(&store, &bad, "id", &json!("v")).is_err());
+        assert!(set(&store, &opts(), "a/b", &json!("v")).is_err());
+        assert!(store.list("svc").unwrap().is_empty());
+    }
+
+    #[test]
+    fn strings_are_stored_verbatim() {
+        let store = MemorySecretStore::new();
+        set(&store, &opts(), "tok", &json!("plain")).unwrap();
+        assert_eq!(store.get("svc", "acc/tok").unwrap().as_deref(), Some("plain"));
+    }
+
+    #[test]
+    fn typed_values_roundtrip_with_original_json_type() {
+        let store = MemorySecretStore::new();
+        let cases = [
+            json!(1234),
+            json!(1.5),
+            json!(true),
+            json!(null),
+            json!(["a", 1]),
+            json!({ "clientId": "id", "clientSecret": "s3cr3t" }),
+            json!(format!("{}looks tagged", TYPED_SECRET_TAG)),
+        ];
+        for value in cases {
+            set(&store, &opts(), "v", &value).unwrap();
+            assert_eq!(get(&store, &opts(), "v").unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn malformed_typed_value_is_keyring_error() {
+        let store = MemorySecretStore::new();
+        let raw = format!("{}{{not json", TYPED_SECRET_TAG);
+        store.set("svc", "acc/bad", &raw).unwrap();
+        assert!(matches!(get(&store, &opts(), "bad"), Err(Error::Keyring(_))));
+    }
+
+    struct LimitedStore(MemorySecretStore);
+
+    impl SecretStore for LimitedStore {
+        fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+            self.0.get(service, user)
+        }
+        fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+            self.0.set(service, user, secret)
+        }
+        fn delete(&self, service: &str, user: &str) -> Result<()> {
+            self.0.delete(service, user)
+        }
+        fn list(&self, service: &str) -> Result<Vec<String>> {
+            self.0.list(service)
+        }
+        fn max_secret_size(&self) -> Option<usize> {
+            Some(16)
+        }
+    }
+
+    #[test]
+    fn oversized_secret_is_rejected_before_write() {
+        let store = LimitedStore(MemorySecretStore::new());
+        set(&store, &opts(), "ok", &json!("0123456789abcdef")).unwrap();
+
+        let err = set(&store, &opts(), "big", &json!({ "k": "0123456789" })).unwrap_err();
+        assert!(matches!(err, Error::InvalidPayload(ref m) if m.contains("'big'")));
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/ok"]);
+    }
+
+    #[test]
+    fn render_id_substitutes_index_key_and_field() {
+        let root = json!({"envs": {"prod": {"servers": [{"name": "db1"}, {"name": "db2"}]}}});
+        let matches = dotpath::expand(&root, "envs.*.servers.*.password").unwrap();
+        let steps = &matches[1].steps;
+
+        assert_eq!(render_id("server-{index}", steps).unwrap(), "server-1");
+        assert_eq!(render_id("{key0}-{index1}", steps).unwrap(), "prod-1");
+        assert_eq!(render_id("srv-{field:name}", steps).unwrap(), "srv-db2");
+        assert!(render_id("srv-{key}", steps).is_err());
+        assert!(render_id("srv-{field:missing}", steps).is_err());
+        assert!(render_id("srv-{nope}", steps).is_err());
+    }
+
+    #[test]
+    fn id_templates_require_placeholders_and_match_rendered_ids() {
+        assert!(validate_id_template("server-{index}").is_ok());
+        assert!(validate_id_template("server").is_err());
+        assert!(validate_id_template("server-{index").is_err());
+
+        assert!(template_matches("server-{index}", "server-0"));
+        assert!(template_matches("{key}-{index}-pw", "prod-12-pw"));
+        assert!(!template_matches("server-{index}", "server-"));
+        assert!(!template_matches("server-{index}", "other-0"));
+    }
+
+    #[test]
+    fn index_placeholders_are_found_only_in_well_formed_templates() {
+        assert!(has_index_placeholder("server-{index}"));
+        assert!(has_index_placeholder("{key0}-{index1}"));
+        for other in ["{key}-pw", "{field:index}", "pw-{index", "{indexes}"] {
+            assert!(!has_index_placeholder(other), "{other}");
+        }
+    }
+
+    #[test]
+    fn ids_with_other_braces_stay_literal() {
+        assert!(is_id_template("server-{index}"));
+        assert!(is_id_template("{field:name}-pw"));
+        for literal in ["a{b", "a}b", "{name}", "pw-{index"] {
+            assert!(!is_id_template(literal), "{literal}");
+        }
+
+        let store = MemorySecretStore::new();
+        set(&store, &opts(), "a{b", &json!("secret")).unwrap();
+        assert_eq!(get(&store, &opts(), "a{b").unwrap(), json!("secret"));
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/a{b"]);
     }
 }
@@ -0,0 +1,849 @@
+/// INI and `.env` documents.
+///
+/// Both formats are line based.  They map to JSON as follows:
+///
+/// - INI: keys before the first `[section]` header are top-level keys, and
+///   every section becomes an object of its keys.  Sections do not nest:
+///   `[a.b]` is the key `"a.b"`, and objects inside sections are rejected.
+/// - `.env`: a flat object.  An `export ` prefix is accepted and kept.
+/// - Values: unquoted `true` / `false` (any case) are booleans; unquoted
+///   decimal integers and floats are numbers when writing the number back
+///   gives the same text (`8080`, `1.5`, `1.0`, `1e+100`); everything else,
+///   including `1.10`, `+5`, `-2e3`, `1e100`, leading zeros and every quoted
+///   value, is a string.  `'…'` is literal and `"…"` understands the
+///   escapes `\n`, `\r`, `\t`, `\"` and `\\`.  In `.env`, a `"…"` value
+///   may span lines; the line breaks are part of the value.
+/// - Comments are lines starting with `#` (or `;` in INI) and, in unquoted
+///   values, a `#` / `;` preceded by whitespace.
+/// - On write, `null` values are omitted (neither format has one), arrays
+///   are rejected and strings are quoted whenever the bare text would read
+///   back as something else.
+///
+/// Writes over an existing file keep its comments, blank lines, order and
+/// the exact text of unchanged entries; changed values keep their inline
+/// comment.  A number equal to the stored one (`1` over `1.0`) counts as
+/// unchanged.  Removed keys and sections are dropped and new ones appended.
+/// The file's line ending (LF or CRLF) is kept.
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Number, Value};
+
+use crate::error::{Error, Result};
+
+/// The two supported line-based formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    Ini,
+    Dotenv,
+}
+
+impl Dialect {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Ini => "INI",
+            Self::Dotenv => ".env",
+        }
+    }
+
+    fn is_comment_char(self, c: char) -> bool {
+        c == '#' || (c == ';' && self == Self::Ini)
+    }
+}
+
+/// Parses INI / `.env` text into a JSON object.
+pub(crate) fn parse(text: &str, dialect: Dialect) -> Result<Value> {
+    Document::parse(text, dialect)?.to_value()
+}
+
+/// Renders `value` as a new INI / `.env` document.
+pub(crate) fn render(value: &Value, dialect: Dialect) -> Result<String> {
+    rewrite("", value, dialect)
+}
+
+/// Renders `value` over the `existing` document, keeping its comments and
+/// the text of unchanged entries.
+pub(crate) fn rewrite(existing: &str, value: &Value, dialect: Dialect) -> Result<String> {
+    let mut document = Document::parse(existing, dialect)?;
+    document.update(value)?;
+    Ok(document.render())
+}
+
+type Slot = (Option<String>, String);
+
+enum Line {
+    /// Blank line or comment, kept verbatim.
+    Other(String),
+    /// `[name]` header (INI only).
+    Section {
+        name: String,
+        raw: String,
+    },
+    Entry(Entry),
+}
+
+struct Entry {
+    section: Option<String>,
+    key: String,
+    /// Text up to the value, e.g. `export KEY=` or `key = `.
+    lead: String,
+    /// Text after the value: trailing whitespace and inline comment.
+    trailer: String,
+    value: Value,
+    raw: String,
+}
+
+struct Document {
+    dialect: Dialect,
+    lines: Vec<Line>,
+    /// `"\r\n"` when the parsed text uses CRLF line endings, else `"\n"`.
+    newline: &'static str,
+}
+
+const UNTERMINATED_DOUBLE_QUOTE: &str = "unterminated double quote";
+
+impl Document {
+    fn parse(text: &str, dialect: Dialect) -> Result<Self> {
+        let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let newline = match text.find('\n') {
+            Some(end) if text[..end].ends_with('\r') => "\r\n",
+            _ => "\n",
+        };
+        let mut lines = Vec::new();
+        let mut section = None;
+        // A `.env` entry whose double-quoted value is still open, with the
+        // number of its first line.
+        let mut open: Option<(usize, String)> = None;
+        for (index, raw) in text.lines().enumerate() {
+            let (number, raw) = match open.take() {
+                Some((number, mut start)) => {
+                    start.push('\n');
+                    start.push_str(raw);
+                    (number, start)
+                }
+                None => (index + 1, raw.to_string()),
+            };
+            let line = match parse_line(&raw, &section, dialect) {
+                Err(message)
+                    if dialect == Dialect::Dotenv && message == UNTERMINATED_DOUBLE_QUOTE =>
+                {
+                    open = Some((number, raw));
+                    continue;
+                }
+                line => line.map_err(|message| line_error(dialect, number, message))?,
+            };
+            if let Line::Section { name, .. } = &line {
+                section = Some(name.clone());
+            }
+            lines.push(line);
+        }
+        if let Some((number, _)) = open {
+            return Err(line_error(
+                dialect,
+                number,
+                UNTERMINATED_DOUBLE_QUOTE.to_string(),
+            ));
+        }
+        Ok(Self {
+            dialect,
+            lines,
+            newline,
+        })
+    }
+
+    fn to_value(&self) -> Result<Value> {
+        let mut root = Map::new();
+        let mut sections: Vec<String> = Vec::new();
+        for line in &self.lines {
+            match line {
+                Line::Section { name, .. } if !sections.contains(name) => {
+                    sections.push(name.clone());
+                }
+                Line::Entry(entry) if entry.section.is_none() => {
+                    root.insert(entry.key.clone(), entry.value.clone());
+                }
+                _ => {}
+            }
+        }
+        for name in sections {
+            if root.contains_key(&name) {
+                return Err(Error::Storage(format!(
+                    "INI key '{}' conflicts with section [{}]",
+                    name, name
+                )));
+            }
+            let mut map = Map::new();
+            for line in &self.lines {
+                if let Line::Entry(entry) = line {
+                    if entry.section.as_ref() == Some(&name) {
+                        map.insert(entry.key.clone(), entry.value.clone());
+                    }
+                }
+            }
+            root.insert(name, Value::Object(map));
+        }
+        Ok(Value::Object(root))
+    }
+
+    /// Makes the document hold exactly `value`.
+    fn update(&mut self, value: &Value) -> Result<()> {
+        let dialect = self.dialect;
+        let target = Target::from_value(value, dialect)?;
+        let wanted: HashMap<Slot, &Value> = target
+            .entries()
+            .map(|(section, key, value)| ((section.cloned(), key.clone()), value))
+            .collect();
+
+        let mut lines = Vec::with_capacity(self.lines.len());
+        let mut written: HashSet<Slot> = HashSet::new();
+        let mut present: HashSet<Option<String>> = HashSet::from([None]);
+        let mut dropping = false;
+        for line in std::mem::take(&mut self.lines) {
+            match line {
+                Line::Section { ref name, .. } => {
+                    dropping = !target.has_section(name) || present.contains(&Some(name.clone()));
+                    if !dropping {
+                        present.insert(Some(name.clone()));
+                        lines.push(line);
+                    }
+                }
+                Line::Other(_) if !dropping => lines.push(line),
+                Line::Other(_) => {}
+                Line::Entry(mut entry) => {
+                    let slot = (entry.section.clone(), entry.key.clone());
+                    let Some(value) = wanted.get(&slot).copied() else {
+                        continue;
+                    };
+                    if dropping || !written.insert(slot) {
+                        continue;
+                    }
+                    if !same_scalar(value, &entry.value) {
+                        let mut trailer = std::mem::take(&mut entry.trailer);
+                        if !trailer.is_empty() && !trailer.starts_with(char::is_whitespace) {
+                            trailer.insert(0, ' ');
+                        }
+                        entry.raw =
+                            format!("{}{}{}", entry.lead, render_scalar(value, dialect), trailer);
+                    }
+                    lines.push(Line::Entry(entry));
+                }
+            }
+        }
+        self.lines = lines;
+
+        // Keys missing from existing sections go after the section's last entry.
+        let mut inserts: Vec<(usize, Vec<Line>)> = Vec::new();
+        for (section, keys) in target.sections_with_globals() {
+            if !present.contains(&section.cloned()) {
+                continue;
+            }
+            let new_lines: Vec<Line> = keys
+                .iter()
+                .filter(|(key, _)| !written.contains(&(section.cloned(), (*key).clone())))
+                .map(|(key, value)| new_entry(section, key, value, dialect))
+                .collect();
+            if !new_lines.is_empty() {
+                inserts.push((self.insert_position(section), new_lines));
+            }
+        }
+        inserts.sort_by_key(|(position, _)| std::cmp::Reverse(*position));
+        for (position, new_lines) in inserts {
+            self.lines.splice(position..position, new_lines);
+        }
+
+        // New sections go at the end.
+        for &(name, ref keys) in &target.sections {
+            if present.contains(&Some(name.clone())) {
+                continue;
+            }
+            let separated = match self.lines.last() {
+                None => true,
+                Some(Line::Other(raw)) => raw.trim().is_empty(),
+                Some(_) => false,
+            };
+            if !separated {
+                self.lines.push(Line::Other(String::new()));
+            }
+            self.lines.push(Line::Section {
+                name: name.clone(),
+                raw: format!("[{}]", name),
+            });
+            for (key, value) in keys {
+                self.lines.push(new_entry(Some(name), key, value, dialect));
+            }
+        }
+        Ok(())
+    }
+
+    /// Where new keys of `section` are inserted: after its last entry, else
+    /// after its header (or, for top-level keys, before the first section).
+    fn insert_position(&self, section: Option<&String>) -> usize {
+        let last_entry = self.lines.iter().rposition(
+            |line| matches!(line, Line::Entry(entry) if entry.section.as_ref() == section),
+        );
+        if let Some(index) = last_entry {
+            return index + 1;
+        }
+        match section {
+            Some(name) => self
+                .lines
+                .iter()
+                .position(|line| matches!(line, Line::Section { name: n, .. } if n == name))
+                .map_or(self.lines.len(), |index| index + 1),
+            None => self
+                .lines
+                .iter()
+                .position(|line| matches!(line, Line::Section { .. }))
+                .unwrap_or(self.lines.len()),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            let raw = match line {
+                Line::Other(raw) | Line::Section { raw, .. } => raw,
+                Line::Entry(entry) => &entry.raw,
+            };
+            // Multi-line `.env` values hold their line breaks as `\n`.
+            for (index, part) in raw.split('\n').enumerate() {
+                if index > 0 {
+                    text.push_str(self.newline);
+                }
+                text.push_str(part);
+            }
+            text.push_str(self.newline);
+        }
+        text
+    }
+}
+
+/// Scalar entries of one section, in data order.
+type Keys<'a> = Vec<(&'a String, &'a Value)>;
+
+/// The validated shape of the data to write.
+struct Target<'a> {
+    globals: Keys<'a>,
+    sections: Vec<(&'a String, Keys<'a>)>,
+}
+
+impl<'a> Target<'a> {
+    fn from_value(value: &'a Value, dialect: Dialect) -> Result<Self> {
+        let Value::Object(map) = value else {
+            return Err(Error::Storage(format!(
+                "{} top-level value must be an object",
+                dialect.name()
+            )));
+        };
+        let mut target = Target {
+            globals: Vec::new(),
+            sections: Vec::new(),
+        };
+        for (key, value) in map {
+            match value {
+                Value::Null => {}
+                Value::Object(entries) if dialect == Dialect::Ini => {
+                    validate_section(key)?;
+                    let mut keys = Vec::new();
+                    for (inner, value) in entries {
+                        match value {
+                            Value::Null => {}
+                            Value::Object(_) => {
+                                return Err(Error::Storage(format!(
+                                    "INI sections do not nest: '{}.{}' is an object",
+                                    key, inner
+                                )))
+                            }
+                            value => {
+                                validate_scalar(&format!("{}.{}", key, inner), value, dialect)?;
+                                validate_key(inner, dialect)?;
+                                keys.push((inner, value));
+                            }
+                        }
+                    }
+                    target.sections.push((key, keys));
+                }
+                Value::Object(_) => {
+                    return Err(Error::Storage(format!(
+                        ".env only holds flat key/value pairs: '{}' is an object",
+                        key
+                    )))
+                }
+                value => {
+                    validate_scalar(key, value, dialect)?;
+                    validate_key(key, dialect)?;
+                    target.globals.push((key, value));
+                }
+            }
+        }
+        Ok(target)
+    }
+
+    fn has_section(&self, name: &str) -> bool {
+        self.sections.iter().any(|(section, _)| *section == name)
+    }
+
+    fn sections_with_globals(&self) -> Vec<(Option<&'a String>, &Keys<'a>)> {
+        std::iter::once((None, &self.globals))
+            .chain(self.sections.iter().map(|(name, keys)| (Some(*name), keys)))
+            .collect()
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (Option<&'a String>, &'a String, &'a Value)> + '_ {
+        self.globals
+            .iter()
+            .map(|(key, value)| (None, *key, *value))
+            .chain(self.sections.iter().flat_map(|(name, keys)| {
+                keys.iter()
+                    .map(move |(key, value)| (Some(*name), *key, *value))
+            }))
+    }
+}
+
+fn validate_scalar(path: &str, value: &Value, dialect: Dialect) -> Result<()> {
+    if value.is_array() {
+        return Err(Error::Storage(format!(
+            "{} does not support arrays: '{}' is an array",
+            dialect.name(),
+            path
+        )));
+    }
+    Ok(())
+}
+
+fn validate_key(key: &str, dialect: Dialect) -> Result<()> {
+    let valid = match dialect {
+        Dialect::Ini => {
+            !key.is_empty()
+                && key == key.trim()
+                && !key.contains(['=', '\n', '\r'])
+                && !key.starts_with(['[', ';', '#'])
+        }
+        Dialect::Dotenv => {
+            let mut chars = key.chars();
+            chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        }
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Storage(format!(
+            "'{}' is not a valid {} key",
+            key,
+            dialect.name()
+        )))
+    }
+}
+
+fn validate_section(name: &str) -> Result<()> {
+    if name.is_empty() || name != name.trim() || name.contains(['[', ']', '\n', '\r']) {
+        return Err(Error::Storage(format!(
+            "'{}' is not a valid INI section name",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn new_entry(section: Option<&String>, key: &str, value: &Value, dialect: Dialect) -> Line {
+    let lead = match dialect {
+        Dialect::Ini => format!("{} = ", key),
+        Dialect::Dotenv => format!("{}=", key),
+    };
+    let raw = format!("{}{}", lead, render_scalar(value, dialect));
+    Line::Entry(Entry {
+        section: section.cloned(),
+        key: key.to_string(),
+        lead,
+        trailer: String::new(),
+        value: value.clone(),
+        raw,
+    })
+}
+
+fn line_error(dialect: Dialect, number: usize, message: String) -> Error {
+    Error::Storage(format!("{} line {}: {}", dialect.name(), number, message))
+}
+
+fn parse_line(
+    raw: &str,
+    section: &Option<String>,
+    dialect: Dialect,
+) -> std::result::Result<Line, String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() || trimmed.starts_with(|c| dialect.is_comment_char(c)) {
+        return Ok(Line::Other(raw.to_string()));
+    }
+    if dialect == Dialect::Ini && trimmed.starts_with('[') {
+        let end = trimmed.find(']').ok_or("unterminated section header")?;
+        let rest = trimmed[end + 1..].trim_start();
+        if !rest.is_empty() && !rest.starts_with(|c| dialect.is_comment_char(c)) {
+            return Err("unexpected text after section header".to_string());
+        }
+        return Ok(Line::Section {
+            name: trimmed[1..end].trim().to_string(),
+            raw: raw.to_string(),
+        });
+    }
+
+    let eq = raw.find('=').ok_or("expected 'key = value'")?;
+    let mut key = raw[..eq].trim();
+    if dialect == Dialect::Dotenv {
+        if let Some(rest) = key.strip_prefix("export") {
+            if rest.starts_with(char::is_whitespace) {
+                key = rest.trim_start();
+            }
+        }
+        if key.contains(char::is_whitespace) {
+            return Err(format!("invalid key '{}'", key));
+        }
+    }
+    if key.is_empty() {
+        return Err("missing key".to_string());
+    }
+    let (start, value, trailer) = parse_value(&raw[eq + 1..], dialect)?;
+    Ok(Line::Entry(Entry {
+        section: section.clone(),
+        key: key.to_string(),
+        lead: raw[..eq + 1 + start].to_string(),
+        trailer,
+        value,
+        raw: raw.to_string(),
+    }))
+}
+
+/// Parses the text after `=` into (offset of the value, value, trailer).
+fn parse_value(
+    after: &str,
+    dialect: Dialect,
+) -> std::result::Result<(usize, Value, String), String> {
+    let body = after.trim_start();
+    let start = after.len() - body.len();
+    let (value, rest) = if let Some(quoted) = body.strip_prefix('"') {
+        let (text, rest) = unescape_double(quoted)?;
+        (text, rest)
+    } else if let Some(quoted) = body.strip_prefix('\'') {
+        let end = quoted.find('\'').ok_or("unterminated single quote")?;
+        (quoted[..end].to_string(), &quoted[end + 1..])
+    } else {
+        let cut = body
+            .char_indices()
+            .find(|&(i, c)| {
+                dialect.is_comment_char(c)
+                    && if i == 0 {
+                        start > 0
+                    } else {
+                        body[..i].ends_with(char::is_whitespace)
+                    }
+            })
+            .map_or(body.len(), |(i, _)| i);
+        let text = body[..cut].trim_end();
+        return Ok((start, typed(text), body[text.len()..].to_string()));
+    };
+    let tail = rest.trim_start();
+    if !tail.is_empty() && !tail.starts_with(|c| dialect.is_comment_char(c)) {
+        return Err("unexpected text after closing quote".to_string());
+    }
+    Ok((start, Value::String(value), rest.to_string()))
+}
+
+fn unescape_double(quoted: &str) -> std::result::Result<(String, &str), String> {
+    let mut text = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((text, &quoted[i + 1..])),
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some('t') => text.push('\t'),
+                Some(c @ ('"' | '\\')) => text.push(c),
+                Some(c) => {
+                    text.push('\\');
+                    text.push(c);
+                }
+                None => break,
+            },
+            c => text.push(c),
+        }
+    }
+    Err(UNTERMINATED_DOUBLE_QUOTE.to_string())
+}
+
+/// Applies the typing rules to an unquoted value.
+pub(crate) fn typed(text: &str) -> Value {
+    if text.eq_ignore_ascii_case("true") {
+        return Value::Bool(true);
+    }
+    if text.eq_ignore_ascii_case("false") {
+        return Value::Bool(false);
+    }
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+        None => (unsigned, None),
+    };
+    let (whole, fraction) = match mantissa.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let shaped = digits(whole)
+        && (whole == "0" || !whole.starts_with('0'))
+        && fraction.is_none_or(digits)
+        && exponent.is_none_or(|e| digits(e.strip_prefix(['-', '+']).unwrap_or(e)));
+    if shaped {
+        let number = if fraction.is_none() && exponent.is_none() {
+            text.parse::<i64>().ok().map(Number::from)
+        } else {
+            text.parse::<f64>().ok().and_then(Number::from_f64)
+        };
+        // Only text that is written back unchanged becomes a number, so
+        // `1.10` (a version) or `-2e3` survive a load/save round trip.
+        if let Some(n) = number.filter(|n| n.to_string() == text) {
+            return Value::Number(n);
+        }
+    }
+    Value::String(text.to_string())
+}
+
+/// Whether `a` and `b` are the same value, treating equal integers and
+/// floats (`1` and `1.0`) as the same.
+fn same_scalar(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) if x.is_f64() || y.is_f64() => {
+            x.as_f64() == y.as_f64()
+        }
+        _ => a == b,
+    }
+}
+
+fn render_scalar(value: &Value, dialect: Dialect) -> String {
+    match value {
+        Value::String(s) => {
+            let bare = !s.contains(['\n', '\r'])
+                && matches!(
+                    parse_value(s, dialect),
+                    Ok((0, Value::String(v), trailer)) if v == *s && trailer.is_empty()
+                );
+            if bare {
+                s.clone()
+            } else if !s.contains(['\'', '\n', '\r', '\t']) {
+                format!("'{}'", s)
+            } else {
+                let mut quoted = String::from('"');
+                for c in s.chars() {
+                    match c {
+                        '\n' => quoted.push_str("\\n"),
+                        '\r' => quoted.push_str("\\r"),
+                        '\t' => quoted.push_str("\\t"),
+                        '"' => quoted.push_str("\\\""),
+                        '\\' => quoted.push_str("\\\\"),
+                        c => quoted.push(c),
+                    }
+                }
+                quoted.push('"');
+                quoted
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ini_maps_sections_and_applies_typing_rules() {
+        let text = "\
+; global settings
+name = My App
+debug = TRUE
+
+[server]
+port = 8080
+ratio = 0.75 ; inline comment
+zip = 01234
+quoted = \"8080\"
+literal = 'a # b'
+escaped = \"line\\nnext\"
+empty =
+";
+        let value = parse(text, Dialect::Ini).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": "My App",
+                "debug": true,
+                "server": {
+                    "port": 8080,
+                    "ratio": 0.75,
+                    "zip": "01234",
+                    "quoted": "8080",
+                    "literal": "a # b",
+                    "escaped": "line\nnext",
+                    "empty": "",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn rendered_values_read_back_with_the_same_types() {
+        let value = json!({
+            "title": "true",
+            "count": 3,
+            "big": 1e100,
+            "padded": "  spaced ",
+            "hash": "a #b",
+            "quote": "it's",
+            "multi": "a\n\"b\"\\",
+            "plain": "hello world",
+            "skipped": null,
+            "section": {"flag": false, "num": "-12", "neg": -1.5},
+        });
+        let text = render(&value, Dialect::Ini).unwrap();
+        let mut expected = value.clone();
+        expected.as_object_mut().unwrap().remove("skipped");
+        assert_eq!(parse(&text, Dialect::Ini).unwrap(), expected, "{}", text);
+        assert!(text.contains("plain = hello world\n"));
+    }
+
+    #[test]
+    fn rewrites_keep_comments_and_untouched_lines() {
+        let existing = "\
+# Application config
+name=app   # the name
+ level = 3
+
+[db]
+; connection
+host = localhost ; primary
+port = 5432
+
+[old]
+gone = 1
+";
+        let value = json!({
+            "name": "renamed",
+            "level": 3,
+            "added": true,
+            "db": {"host": "db.internal", "port": 5432, "user": "admin"},
+            "cache": {"ttl": 60},
+        });
+        let text = rewrite(existing, &value, Dialect::Ini).unwrap();
+        assert_eq!(
+            text,
+            "\
+# Application config
+name=renamed   # the name
+ level = 3
+added = true
+
+[db]
+; connection
+host = db.internal ; primary
+port = 5432
+user = admin
+
+[cache]
+ttl = 60
+"
+        );
+        assert_eq!(parse(&text, Dialect::Ini).unwrap(), value);
+    }
+
+    #[test]
+    fn dotenv_is_flat_and_keeps_export_prefixes() {
+        let existing = "\
+# secrets
+export API_URL=https://example.com
+PORT=3000
+DEBUG=false # local only
+";
+        let value = parse(existing, Dialect::Dotenv).unwrap();
+        assert_eq!(
+            value,
+            json!({"API_URL": "https://example.com", "PORT": 3000, "DEBUG": false})
+        );
+
+        let updated = json!({"API_URL": "https://example.org", "PORT": 3000, "NAME": "a b"});
+        let text = rewrite(existing, &updated, Dialect::Dotenv).unwrap();
+        assert_eq!(
+            text,
+            "# secrets\nexport API_URL=https://example.org\nPORT=3000\nNAME=a b\n"
+        );
+
+        let err = render(&json!({"nested": {"a": 1}}), Dialect::Dotenv).unwrap_err();
+        assert!(err.to_string().contains("flat"));
+        assert!(render(&json!({"bad key": 1}), Dialect::Dotenv).is_err());
+        assert!(render(&json!({"list": [1]}), Dialect::Ini).is_err());
+        assert!(render(&json!({"a": {"b": {"c": 1}}}), Dialect::Ini).is_err());
+    }
+
+    #[test]
+    fn numbers_keep_their_text_through_load_and_save() {
+        let existing = "version = 1.10\nratio = 1.0\nplus = +5\nexp = -2e3\nbig = 1e+100\n";
+        let value = parse(existing, Dialect::Ini).unwrap();
+        assert_eq!(
+            value,
+            json!({"version": "1.10", "ratio": 1.0, "plus": "+5", "exp": "-2e3", "big": 1e100})
+        );
+
+        // A webview save sends whole floats back as integers.
+        let mut saved = value.clone();
+        saved["ratio"] = json!(1);
+        assert_eq!(rewrite(existing, &saved, Dialect::Ini).unwrap(), existing);
+
+        saved["ratio"] = json!(2);
+        let text = rewrite(existing, &saved, Dialect::Ini).unwrap();
+        assert!(text.starts_with("version = 1.10\nratio = 2\n"), "{}", text);
+    }
+
+    #[test]
+    fn malformed_lines_report_their_line_number() {
+        let err = parse("a = 1\nnot an entry\n", Dialect::Ini).unwrap_err();
+        assert!(err.to_string().contains("INI line 2"), "{}", err);
+        let err = parse("A=\"open\nB=1\n", Dialect::Dotenv).unwrap_err();
+        assert!(err.to_string().contains(".env line 1"), "{}", err);
+        assert!(parse("a = 1\n[a]\n", Dialect::Ini).is_err());
+    }
+
+    #[test]
+    fn crlf_line_endings_are_kept() {
+        let existing = "; app\r\nname = app\r\n\r\n[db]\r\nport = 5432\r\n";
+        let value = json!({"name": "renamed", "db": {"port": 5432, "user": "admin"}});
+        let text = rewrite(existing, &value, Dialect::Ini).unwrap();
+        assert_eq!(
+            text,
+            "; app\r\nname = renamed\r\n\r\n[db]\r\nport = 5432\r\nuser = admin\r\n"
+        );
+        assert_eq!(parse(&text, Dialect::Ini).unwrap(), value);
+        assert_eq!(
+            render(&value, Dialect::Ini).unwrap().matches('\r').count(),
+            0
+        );
+    }
+
+    #[test]
+    fn dotenv_double_quoted_values_can_span_lines() {
+        let existing =
+            "KEY=\"-----BEGIN-----\r\nabc # not a comment\r\n-----END-----\" # pem\r\nNEXT=1\r\n";
+        let value = parse(existing, Dialect::Dotenv).unwrap();
+        assert_eq!(
+            value,
+            json!({"KEY": "-----BEGIN-----\nabc # not a comment\n-----END-----", "NEXT": 1})
+        );
+        let text = rewrite(
+            existing,
+            &json!({"KEY": value["KEY"], "NEXT": 2}),
+            Dialect::Dotenv,
+        )
+        .unwrap();
+        assert_eq!(text, existing.replace("NEXT=1", "NEXT=2"));
+    }
+}
@@ -0,0 +1,826 @@
+/// Layered configuration: an ordered list of sources deep-merged into one
+/// view, lowest priority first (e.g. shipped defaults → machine-wide admin
+/// overrides → user config → runtime overrides).
+///
+/// Every leaf of the merged result is attributed to the layer that supplied
+/// it (`provenance`).  Writes go to one designated layer and only store the
+/// values that differ from the layers below it, so defaults are not copied
+/// into the user's file.  Values supplied by the layers above it (runtime
+/// overrides) are not written either.
+///
+/// A managed policy that covers any config layer applies to the merged view
+/// like it does to a plain `load`, and writes to the layer are enforced like
+/// `save`.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::commands;
+use crate::config;
+use crate::dotpath::{self, Segment};
+use crate::error::{Error, Result};
+use crate::locker::{self, FileLock};
+use crate::models::{ConfiguratePayload, NormalizedConfiguratePayload, ProviderPayload};
+use crate::patch::{self, ArrayStrategies};
+use crate::policy::{self, Policy};
+use crate::scope::{Access, Scope};
+use crate::storage;
+
+/// Maximum number of layers in one layered config.
+pub const MAX_LAYERS: usize = 16;
+
+/// Where a layer's data comes from.
+#[derive(Debug)]
+pub enum LayerSource {
+    /// A config addressed like an IPC payload (`baseDir` + `fileName`),
+    /// subject to the plugin's base-directory policy.
+    Config(Box<ConfiguratePayload>),
+    /// A file at an absolute path, e.g. a machine-wide admin location
+    /// outside the app sandbox.  Only constructible from Rust.
+    File {
+        path: PathBuf,
+        provider: ProviderPayload,
+    },
+    /// In-memory data such as runtime overrides.  Never written.
+    Value(Value),
+}
+
+/// One source in a [`LayeredConfig`].
+#[derive(Debug)]
+pub struct Layer {
+    pub id: String,
+    pub source: LayerSource,
+    /// When false (the default), a missing file is skipped instead of failing.
+    pub required: bool,
+}
+
+impl Layer {
+    pub fn config(id: impl Into<String>, payload: ConfiguratePayload) -> Self {
+        Self::new(id, LayerSource::Config(Box::new(payload)))
+    }
+
+    pub fn file(
+        id: impl Into<String>,
+        path: impl Into<PathBuf>,
+        provider: ProviderPayload,
+    ) -> Self {
+        Self::new(
+            id,
+            LayerSource::File {
+                path: path.into(),
+                provider,
+            },
+        )
+    }
+
+    pub fn value(id: impl Into<String>, value: Value) -> Self {
+        Self::new(id, LayerSource::Value(value))
+    }
+
+    fn new(id: impl Into<String>, source: LayerSource) -> Self {
+        Self {
+            id: id.into(),
+            source,
+            required: false,
+        }
+    }
+
+    /// Fails the load when this layer's file does not exist.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+
+/// Ordered layers (lowest priority first) plus the layer writes go to.
+#[derive(Debug, Default)]
+pub struct LayeredConfig {
+    pub layers: Vec<Layer>,
+    pub write_layer: Option<String>,
+}
+
+impl LayeredConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a layer with higher priority than all previous ones.
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Sets the layer that [`save_layered`](crate::Configurate::save_layered) writes to.
+    pub fn write_layer(mut self, id: impl Into<String>) -> Self {
+        self.write_layer = Some(id.into());
+        self
+    }
+}
+
+/// Per-layer load status reported with the merged data.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerStatus {
+    pub id: String,
+    /// False when an optional layer's file does not exist.
+    pub loaded: bool,
+}
+
+/// Merged view of a [`LayeredConfig`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayeredData {
+    pub data: Value,
+    /// Dotpath of every leaf value → id of the layer that supplied it.
+    pub provenance: BTreeMap<String, String>,
+    pub layers: Vec<LayerStatus>,
+    pub write_layer: Option<String>,
+    /// Dotpaths locked by the managed policy.  Their values come from the
+    /// policy, whatever `provenance` says.
+    pub locked_keys: Vec<String>,
+}
+
+/// One layer of a `load_layered` / `save_layered` IPC payload.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayerPayload {
+    pub id: String,
+    pub source: ConfiguratePayload,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Payload for the `load_layered` and `save_layered` commands.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayeredPayload {
+    pub layers: Vec<LayerPayload>,
+    pub write_layer: Option<String>,
+    /// Full desired config for `save_layered`; ignored by `load_layered`.
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+impl LayeredPayload {
+    /// Splits the payload into the layered config and the `data` to save.
+    pub(crate) fn into_parts(self) -> (LayeredConfig, Option<Value>) {
+        let layers = self
+            .layers
+            .into_iter()
+            .map(|layer| Layer {
+                id: layer.id,
+                source: LayerSource::Config(Box::new(layer.source)),
+                required: layer.required,
+            })
+            .collect();
+        (
+            LayeredConfig {
+                layers,
+                write_layer: self.write_layer,
+            },
+            self.data,
+        )
+    }
+}
+
+/// A layer whose source has been validated and normalized.
+enum ResolvedSource {
+    Config(Box<NormalizedConfiguratePayload>),
+    File {
+        path: PathBuf,
+        provider: crate::models::NormalizedProvider,
+    },
+    Value(Value),
+}
+
+struct ResolvedLayer {
+    id: String,
+    source: ResolvedSource,
+    required: bool,
+}
+
+fn resolve_layers(config: LayeredConfig) -> Result<(Vec<ResolvedLayer>, Option<usize>)> {
+    if config.layers.is_empty() {
+        return Err(Error::InvalidPayload(
+            "layered config requires at least one layer".to_string(),
+        ));
+    }
+    if config.layers.len() > MAX_LAYERS {
+        return Err(Error::InvalidPayload(format!(
+            "layered config exceeds maximum of {} layers",
+            MAX_LAYERS
+        )));
+    }
+    let mut resolved = Vec::with_capacity(config.layers.len());
+    for layer in config.layers {
+        if layer.id.is_empty() {
+            return Err(Error::InvalidPayload(
+                "layer id must not be empty".to_string(),
+            ));
+        }
+        if resolved.iter().any(|r: &ResolvedLayer| r.id == layer.id) {
+            return Err(Error::InvalidPayload(format!(
+                "layer id '{}' is duplicated",
+                layer.id
+            )));
+        }
+        let source = match layer.source {
+            LayerSource::Config(payload) => {
+                let normalized = payload.normalize()?;
+                if normalized.keyring_entries.is_some() || normalized.keyring_options.is_some() {
+                    return Err(Error::InvalidPayload(format!(
+                        "layer '{}': layered configs cannot access the keyring",
+                        layer.id
+                    )));
+                }
+                ResolvedSource::Config(Box::new(normalized))
+            }
+            LayerSource::File { path, provider } => {
+                if !path.is_absolute() {
+                    return Err(Error::InvalidPayload(format!(
+                        "layer '{}': file path must be absolute",
+                        layer.id
+                    )));
+                }
+                ResolvedSource::File {
+                    path,
+                    provider: provider.normalize()?,
+                }
+            }
+            LayerSource::Value(value) => ResolvedSource::Value(value),
+        };
+        resolved.push(ResolvedLayer {
+            id: layer.id,
+            source,
+            required: layer.required,
+        });
+    }
+    let write_index = match &config.write_layer {
+        Some(id) => {
+            let index = resolved
+                .iter()
+                .position(|layer| &layer.id == id)
+                .ok_or_else(|| {
+                    Error::InvalidPayload(format!("write layer '{}' is not a layer", id))
+                })?;
+            if matches!(resolved[index].source, ResolvedSource::Value(_)) {
+                return Err(Error::InvalidPayload(format!(
+                    "write layer '{}' is an in-memory layer and cannot be written",
+                    id
+                )));
+            }
+            Some(index)
+        }
+        None => None,
+    };
+    Ok((resolved, write_index))
+}
+
+fn read_layer<R: Runtime>(app: &AppHandle<R>, layer: &ResolvedLayer) -> Result<Option<Value>> {
+    match read_source(app, &layer.source)? {
+        Some(value) => Ok(Some(value)),
+        None if layer.required => Err(Error::InvalidPayload(format!(
+            "required layer '{}' does not exist",
+            layer.id
+        ))),
+        None => Ok(None),
+    }
+}
+
+/// Reads `source`, or `None` when its file does not exist.
+fn read_source<R: Runtime>(app: &AppHandle<R>, source: &ResolvedSource) -> Result<Option<Value>> {
+    let result = match source {
+        ResolvedSource::Config(payload) => commands::load_plain_data(app, payload),
+        ResolvedSource::File { path, provider } => storage::file_backend_for(
+            provider,
+            false,
+            storage::read_only_registry(),
+            config::max_read_bytes(app),
+        )?
+        .read(path),
+        ResolvedSource::Value(value) => return Ok(Some(value.clone())),
+    };
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Writes the part of `data` that differs from `base` (the merged layers
+/// below) to `layer`.  The caller holds the layer's file lock.
+fn write_layer<R: Runtime>(
+    app: &AppHandle<R>,
+    layer: &ResolvedLayer,
+    base: &Value,
+    data: &Value,
+) -> Result<()> {
+    match &layer.source {
+        ResolvedSource::Config(payload) => {
+            let change_event = commands::build_change_event(payload, "save");
+            let policy = policy::for_payload(app, payload)?;
+            let stored = match &policy {
+                Some(policy) => commands::stored_for_write(app, payload, Some(policy))?,
+                None => None,
+            };
+            let own = layer_data(base, data, stored.as_ref(), policy.as_ref())?;
+            commands::save_plain_data(app, payload, &own)?;
+            commands::emit_change(app, change_event);
+            Ok(())
+        }
+        ResolvedSource::File { path, provider } => storage::file_backend_for(
+            provider,
+            false,
+            Arc::new(storage::BackupRegistry::new()),
+            config::max_read_bytes(app),
+        )?
+        .write(path, &layer_data(base, data, None, None)?),
+        ResolvedSource::Value(_) => unreachable!("value layers are rejected as write layers"),
+    }
+}
+
+fn load_resolved<R: Runtime>(
+    app: &AppHandle<R>,
+    layers: &[ResolvedLayer],
+    write_index: Option<usize>,
+) -> Result<LayeredData> {
+    let mut loaded = Vec::with_capacity(layers.len());
+    for layer in layers {
+        loaded.push((layer.id.clone(), read_layer(app, layer)?));
+    }
+    let (mut data, provenance) = merge_layers(&loaded);
+    let locked_keys = match layers_policy(app, layers)? {
+        Some(policy) => {
+            policy.apply(&mut data)?;
+            policy.locked_keys()
+        }
+        None => Vec::new(),
+    };
+    Ok(LayeredData {
+        data,
+        provenance,
+        locked_keys,
+        layers: loaded
+            .iter()
+            .map(|(id, value)| LayerStatus {
+                id: id.clone(),
+                loaded: value.is_some(),
+            })
+            .collect(),
+        write_layer: write_index.map(|i| layers[i].id.clone()),
+    })
+}
+
+/// The managed policy of the first config layer it covers.
+fn layers_policy<R: Runtime>(
+    app: &AppHandle<R>,
+    layers: &[ResolvedLayer],
+) -> Result<Option<Policy>> {
+    for layer in layers {
+        if let ResolvedSource::Config(payload) = &layer.source {
+            if let Some(policy) = policy::for_payload(app, payload)? {
+                return Ok(Some(policy));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// A layered config resolved and restricted to the caller's scope, ready to
+/// run once the locks of its files are held.
+pub(crate) struct PreparedLayers {
+    layers: Vec<ResolvedLayer>,
+    write_index: Option<usize>,
+}
+
+impl PreparedLayers {
+    /// Prepares `config` for [`PreparedLayers::load`].
+    pub(crate) fn for_load(config: LayeredConfig, scope: Option<&Scope>) -> Result<Self> {
+        let (mut layers, write_index) = resolve_layers(config)?;
+        if let Some(scope) = scope {
+            apply_scope(&mut layers, scope, None);
+        }
+        Ok(Self {
+            layers,
+            write_index,
+        })
+    }
+
+    /// Prepares `config` for [`PreparedLayers::save`]; it needs a writable
+    /// write layer.
+    pub(crate) fn for_save<R: Runtime>(
+        app: &AppHandle<R>,
+        config: LayeredConfig,
+        scope: Option<&Scope>,
+    ) -> Result<Self> {
+        let (mut layers, write_index) = resolve_layers(config)?;
+        let write_index = write_index.ok_or_else(|| {
+            Error::InvalidPayload("save_layered requires a writeLayer".to_string())
+        })?;
+        if let Some(scope) = scope {
+            apply_scope(&mut layers, scope, Some(write_index));
+        }
+        if let ResolvedSource::Config(payload) = &layers[write_index].source {
+            config::validate_writable(app, payload.base_dir)?;
+        }
+        Ok(Self {
+            layers,
+            write_index: Some(write_index),
+        })
+    }
+
+    /// The lock of every file layer, without duplicates and in path order, so
+    /// layered operations over overlapping files cannot deadlock.
+    /// Layers whose path cannot be resolved fail when they are read.
+    pub(crate) fn locks<R: Runtime>(&self, app: &AppHandle<R>) -> Vec<FileLock> {
+        let paths: BTreeSet<PathBuf> = self
+            .layers
+            .iter()
+            .filter_map(|layer| match &layer.source {
+                ResolvedSource::Config(payload) => commands::resolve_file_path(app, payload).ok(),
+                ResolvedSource::File { path, .. } => Some(path.clone()),
+                ResolvedSource::Value(_) => None,
+            })
+            .collect();
+        let registry = app.state::<locker::FileLockRegistry>();
+        paths
+            .into_iter()
+            .map(|path| registry.acquire(path))
+            .collect()
+    }
+
+    /// Loads and merges every layer.  The caller holds [`PreparedLayers::locks`].
+    pub(crate) fn load<R: Runtime>(&self, app: &AppHandle<R>) -> Result<LayeredData> {
+        load_resolved(app, &self.layers, self.write_index)
+    }
+
+    /// Stores `data` (the full desired config) in the write layer as the
+    /// difference from the layers below it, then returns the new merged view.
+    /// Values that still equal those of the layers above it are not written
+    /// (see `drop_higher_values`).  The caller holds
+    /// [`PreparedLayers::locks`].
+    ///
+    /// Keys present in lower layers cannot be removed this way; omitting them
+    /// from `data` leaves the lower layer's value in effect.
+    pub(crate) fn save<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        mut data: Value,
+    ) -> Result<LayeredData> {
+        let write_index = self.write_index.ok_or_else(|| {
+            Error::InvalidPayload("save_layered requires a writeLayer".to_string())
+        })?;
+        let mut below = Vec::with_capacity(write_index);
+        for layer in &self.layers[..write_index] {
+            below.push((layer.id.clone(), read_layer(app, layer)?));
+        }
+        let mut above = Vec::with_capacity(self.layers.len() - write_index - 1);
+        for layer in &self.layers[write_index + 1..] {
+            above.push((layer.id.clone(), read_layer(app, layer)?));
+        }
+        let write = &self.layers[write_index];
+        if above.iter().any(|(_, value)| value.is_some()) {
+            let (higher, _) = merge_layers(&above);
+            let stored = read_source(app, &write.source)?;
+            drop_higher_values(&mut data, &higher, stored.as_ref());
+        }
+        let (base, _) = merge_layers(&below);
+        write_layer(app, write, &base, &data)?;
+        load_resolved(app, &self.layers, Some(write_index))
+    }
+
+    /// Waits for [`PreparedLayers::locks`] from synchronous code (the Rust API).
+    fn lock_blocking<R: Runtime>(&self, app: &AppHandle<R>) -> Result<Vec<locker::FileGuard>> {
+        self.locks(app)
+            .into_iter()
+            .map(locker::lock_blocking)
+            .collect()
+    }
+}
+
+/// Restricts config layers to the invoking window's scope: read access for
+/// every layer, write access for `write_index`.
+fn apply_scope(layers: &mut [ResolvedLayer], scope: &Scope, write_index: Option<usize>) {
+    for (index, layer) in layers.iter_mut().enumerate() {
+        if let ResolvedSource::Config(payload) = &mut layer.source {
+            let access = if Some(index) == write_index {
+                Access::Write
+            } else {
+                Access::Read
+            };
+            **payload = (**payload).clone().scoped(scope, access);
+        }
+    }
+}
+
+/// Loads and merges every layer of `config`, blocking while waiting for
+/// the layers' file locks.
+pub(crate) fn load<R: Runtime>(app: &AppHandle<R>, config: LayeredConfig) -> Result<LayeredData> {
+    let prepared = PreparedLayers::for_load(config, None)?;
+    let _guards = prepared.lock_blocking(app)?;
+    prepared.load(app)
+}
+
+/// Stores `data` in the write layer of `config` (see [`PreparedLayers::save`]),
+/// blocking while waiting for the layers' file locks.
+pub(crate) fn save<R: Runtime>(
+    app: &AppHandle<R>,
+    config: LayeredConfig,
+    data: Value,
+) -> Result<LayeredData> {
+    let prepared = PreparedLayers::for_save(app, config, None)?;
+    let _guards = prepared.lock_blocking(app)?;
+    prepared.save(app, data)
+}
+
+/// Takes the values of the layers above the write layer back out of `data`,
+/// the merged view the caller edited, like `EnvOverrides::restore` does for
+/// environment overrides.  A value that still equals `higher`'s is reset to
+/// the write layer's `stored` value, or removed when the layer has none;
+/// edited values are kept.
+fn drop_higher_values(data: &mut Value, higher: &Value, stored: Option<&Value>) {
+    let (Value::Object(data_map), Value::Object(higher_map)) = (data, higher) else {
+        return;
+    };
+    for (key, higher_value) in higher_map {
+        let Some(current) = data_map.get_mut(key) else {
+            continue;
+        };
+        let original = stored.and_then(|stored| stored.get(key));
+        if higher_value.as_object().is_some_and(|map| !map.is_empty()) && current.is_object() {
+            drop_higher_values(current, higher_value, original);
+        } else if current == higher_value {
+            match original {
+                Some(original) => *current = original.clone(),
+                None => {
+                    data_map.remove(key);
+                }
+            }
+        }
+    }
+}
+
+/// The write layer's new content: `data` minus what `base` already supplies,
+/// with the policy enforced against the layer's `stored` content, as `save`
+/// does for a single file.
+///
+/// Environment overrides are not undone: layered loads never apply them, so
+/// `data` holds the values the webview actually saw and edited.
+fn layer_data(
+    base: &Value,
+    data: &Value,
+    stored: Option<&Value>,
+    policy: Option<&Policy>,
+) -> Result<Value> {
+    let mut own = diff(base, data).unwrap_or_else(|| Value::Object(Map::new()));
+    if let Some(policy) = policy {
+        policy.enforce(stored, &mut own)?;
+    }
+    Ok(own)
+}
+
+/// Deep-merges `layers` in order and attributes every leaf to a layer.
+pub(crate) fn merge_layers(
+    layers: &[(String, Option<Value>)],
+) -> (Value, BTreeMap<String, String>) {
+    let mut merged = Value::Object(Map::new());
+    let strategies = ArrayStrategies::default();
+    for value in layers.iter().filter_map(|(_, value)| value.as_ref()) {
+        patch::deep_merge(&mut merged, value.clone(), &strategies);
+    }
+    let mut provenance = BTreeMap::new();
+    collect_provenance(&merged, &mut Vec::new(), layers, &mut provenance);
+    (merged, provenance)
+}
+
+/// Leaves are non-object values and empty objects.  A leaf comes from the
+/// last layer that has a value at its path: deep merge lets later layers win.
+fn collect_provenance(
+    node: &Value,
+    path: &mut Vec<String>,
+    layers: &[(String, Option<Value>)],
+    out: &mut BTreeMap<String, String>,
+) {
+    if let Value::Object(map) = node {
+        if !map.is_empty() {
+            for (key, child) in map {
+                path.push(key.clone());
+                collect_provenance(child, path, layers, out);
+                path.pop();
+            }
+            return;
+        }
+    }
+    if path.is_empty() {
+        return;
+    }
+    let source = layers.iter().rev().find(|(_, value)| {
+        value
+            .as_ref()
+            .is_some_and(|value| value_at(value, path).is_some())
+    });
+    if let Some((id, _)) = source {
+        let segments: Vec<Segment> = path.iter().cloned().map(Segment::Key).collect();
+        out.insert(dotpath::format(&segments), id.clone());
+    }
+}
+
+fn value_at<'a>(root: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(root, |node, key| node.as_object()?.get(key))
+}
+
+/// Returns the parts of `target` that differ from `base`, as a deep-merge
+/// patch, or `None` when `target` adds nothing.
+pub(crate) fn diff(base: &Value, target: &Value) -> Option<Value> {
+    match (base, target) {
+        (Value::Object(base_map), Value::Object(target_map)) => {
+            let mut out = Map::new();
+            for (key, value) in target_map {
+                match base_map.get(key) {
+                    Some(base_value) => {
+                        if let Some(changed) = diff(base_value, value) {
+                            out.insert(key.clone(), changed);
+                        }
+                    }
+                    None => {
+                        out.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            (!out.is_empty()).then_some(Value::Object(out))
+        }
+        _ if base == target => None,
+        _ => Some(target.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn layers(values: Vec<(&str, Option<Value>)>) -> Vec<(String, Option<Value>)> {
+        values
+            .into_iter()
+            .map(|(id, value)| (id.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn merge_layers_tracks_provenance_per_leaf() {
+        let input = layers(vec![
+            (
+                "defaults",
+                Some(
+                    json!({"theme": "light", "db": {"host": "localhost", "port": 5432}, "tags": ["a"]}),
+                ),
+            ),
+            ("system", None),
+            (
+                "user",
+                Some(json!({"db": {"host": "db.example.com"}, "tags": ["b"], "plugins": {}})),
+            ),
+            ("runtime", Some(json!({"theme": "dark", "a.b": 1}))),
+        ]);
+        let (data, provenance) = merge_layers(&input);
+        assert_eq!(
+            data,
+            json!({
+                "theme": "dark",
+                "db": {"host": "db.example.com", "port": 5432},
+                "tags": ["b"],
+                "plugins": {},
+                "a.b": 1
+            })
+        );
+        let expected: BTreeMap<String, String> = [
+            ("theme", "runtime"),
+            ("db.host", "user"),
+            ("db.port", "defaults"),
+            ("tags", "user"),
+            ("plugins", "user"),
+            (r#"["a.b"]"#, "runtime"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        assert_eq!(provenance, expected);
+    }
+
+    #[test]
+    fn scalar_replaced_by_object_is_attributed_to_the_later_layer() {
+        let input = layers(vec![
+            ("a", Some(json!({"db": "sqlite://x"}))),
+            ("b", Some(json!({"db": {"host": "h"}}))),
+        ]);
+        let (_, provenance) = merge_layers(&input);
+        assert_eq!(provenance.len(), 1);
+        assert_eq!(provenance["db.host"], "b");
+    }
+
+    #[test]
+    fn diff_keeps_only_values_that_differ_from_lower_layers() {
+        let base =
+            json!({"theme": "light", "db": {"host": "localhost", "port": 5432}, "tags": ["a"]});
+        let target = json!({"theme": "light", "db": {"host": "prod", "port": 5432}, "tags": ["a"], "new": true});
+        assert_eq!(
+            diff(&base, &target),
+            Some(json!({"db": {"host": "prod"}, "new": true}))
+        );
+        assert_eq!(diff(&base, &base), None);
+    }
+
+    #[test]
+    fn layer_data_enforces_the_policy_on_the_write_layer() {
+        let base = json!({"theme": "light", "telemetry": false});
+        let stored = json!({"theme": "dark"});
+        let policy = Policy::new(json!({"telemetry": false})).unwrap();
+
+        let data = json!({"theme": "dark", "telemetry": true});
+        assert_eq!(
+            layer_data(&base, &data, Some(&stored), Some(&policy)).unwrap(),
+            json!({"theme": "dark", "telemetry": false})
+        );
+        assert_eq!(
+            layer_data(&base, &data, Some(&stored), None).unwrap(),
+            json!({"theme": "dark", "telemetry": true})
+        );
+    }
+
+    #[test]
+    fn values_from_layers_above_the_write_layer_are_not_saved() {
+        let below = layers(vec![(
+            "defaults",
+            Some(json!({"theme": "light", "size": 10})),
+        )]);
+        let stored = json!({"size": 12, "font": {"family": "Inter", "weight": 400}});
+        let above = layers(vec![(
+            "runtime",
+            Some(json!({"theme": "dark", "size": 14, "font": {"weight": 700}})),
+        )]);
+        let mut all = below.clone();
+        all.push(("user".to_string(), Some(stored.clone())));
+        all.extend(above.iter().cloned());
+        let (loaded, _) = merge_layers(&all);
+        assert_eq!(
+            loaded,
+            json!({"theme": "dark", "size": 14, "font": {"family": "Inter", "weight": 700}})
+        );
+
+        // The caller saves the merged view back with one edit.
+        let mut data = loaded;
+        data["font"]["family"] = json!("Mono");
+        let (higher, _) = merge_layers(&above);
+        drop_higher_values(&mut data, &higher, Some(&stored));
+        let (base, _) = merge_layers(&below);
+        assert_eq!(
+            layer_data(&base, &data, None, None).unwrap(),
+            json!({"size": 12, "font": {"family": "Mono", "weight": 400}})
+        );
+
+        // A value changed away from the higher layer's is the caller's own.
+        let mut data = json!({"theme": "solarized", "size": 14});
+        drop_higher_values(&mut data, &higher, None);
+        assert_eq!(data, json!({"theme": "solarized"}));
+    }
+
+    #[test]
+    fn resolve_layers_validates_ids_and_write_layer() {
+        let payload = || {
+            ConfiguratePayload::new(
+                "app.json",
+                tauri::path::BaseDirectory::AppConfig,
+                ProviderPayload::new(crate::models::ProviderKind::Json),
+            )
+        };
+        let duplicated = LayeredConfig::new()
+            .layer(Layer::config("user", payload()))
+            .layer(Layer::config("user", payload()));
+        assert!(resolve_layers(duplicated).is_err());
+
+        let value_write = LayeredConfig::new()
+            .layer(Layer::value("runtime", json!({})))
+            .write_layer("runtime");
+        assert!(resolve_layers(value_write).is_err());
+
+        let relative = LayeredConfig::new().layer(Layer::file(
+            "system",
+            "etc/app.json",
+            ProviderPayload::new(crate::models::ProviderKind::Json),
+        ));
+        assert!(resolve_layers(relative).is_err());
+
+        let ok = LayeredConfig::new()
+            .layer(Layer::value("defaults", json!({"a": 1})))
+            .layer(Layer::config("user", payload()).required())
+            .write_layer("user");
+        let (resolved, write_index) = resolve_layers(ok).unwrap();
+        assert_eq!(write_index, Some(1));
+        assert!(resolved[1].required);
+    }
+}
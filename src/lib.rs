@@ -10,20 +10,50 @@ mod desktop;
 #[cfg(mobile)]
 mod mobile;
 
+mod cache;
 mod commands;
 mod config;
 mod dotpath;
+mod encoding;
+mod env_overrides;
 mod error;
+mod key_cache;
+mod keyring_manifest;
 mod keyring_store;
+mod keyvalue;
+mod layered;
 mod locker;
 mod models;
+mod patch;
+mod policy;
+mod property_list;
+mod scope;
+mod secret_store;
 mod storage;
+mod streaming;
+mod tasks;
+mod toml_value;
 mod watcher;
+mod xml;
 
+pub use cache::CacheStats;
 pub use config::{
-    BaseDirPolicy, Builder, PluginConfig, PluginSettings, DEFAULT_MAX_READ_BYTES,
+    BaseDirAccess, BaseDirPolicy, Builder, PluginConfig, PluginSettings,
+    DEFAULT_BATCH_PARALLELISM, DEFAULT_MAX_READ_BYTES,
 };
+pub use env_overrides::EnvOverride;
 pub use error::{Error, Result};
+pub use keyring_manifest::KeyringManifestEntry;
+pub use layered::{
+    Layer, LayerPayload, LayerSource, LayerStatus, LayeredConfig, LayeredData, LayeredPayload,
+    MAX_LAYERS,
+};
+pub use policy::{ManagedPolicy, PolicyEnforcement};
+pub use secret_store::{
+    EncryptedFileSecretStore, FallbackSecretStore, MemorySecretStore, OsKeyringStore,
+    SecretStore, VaultKey,
+};
+pub use streaming::StreamChunk;
 
 #[cfg(desktop)]
 use desktop::Configurate;
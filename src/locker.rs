@@ -1,9 +1,10 @@
 /// Per-file advisory lock registry.
 ///
-/// Provides a per-path `Mutex<()>` so that read-then-write operations such as
-/// `patch` cannot interleave with concurrent writes to the same file within
-/// the same process.  All locks are advisory—they do not affect external
-/// processes.
+/// Provides a per-path async `Mutex<()>` so that read-then-write operations
+/// such as `patch` cannot interleave with concurrent writes to the same file
+/// within the same process.  Commands wait for it without blocking an async
+/// worker; synchronous callers use [`lock_blocking`].  All locks are
+/// advisory—they do not affect external processes.
 ///
 /// Stale entries (where the registry holds the only remaining `Arc` reference)
 /// are periodically purged to prevent unbounded memory growth.
@@ -11,11 +12,20 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+use crate::error::{Error, Result};
+
 /// Purge stale entries every N calls to `acquire`.
 const CLEANUP_INTERVAL: u32 = 64;
 
+/// The lock of one file.
+pub(crate) type FileLock = Arc<tokio::sync::Mutex<()>>;
+/// A held [`FileLock`]; can be moved to another thread.
+pub(crate) type FileGuard = tokio::sync::OwnedMutexGuard<()>;
+
 pub struct FileLockRegistry {
-    map: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+    map: Mutex<HashMap<PathBuf, FileLock>>,
     call_count: Mutex<u32>,
 }
 
@@ -27,11 +37,11 @@ impl FileLockRegistry {
         }
     }
 
-    /// Returns (or creates) the per-path `Arc<Mutex<()>>` for `path`.
+    /// Returns (or creates) the per-path lock for `path`.
     ///
     /// Every `CLEANUP_INTERVAL` calls, stale entries whose `Arc` is only held
     /// by the registry (strong_count == 1) are removed to reclaim memory.
-    pub fn acquire(&self, path: PathBuf) -> Arc<Mutex<()>> {
+    pub(crate) fn acquire(&self, path: PathBuf) -> FileLock {
         let mut map = self.map.lock().unwrap_or_else(|e| e.into_inner());
 
         // Periodic cleanup of stale entries.
@@ -45,11 +55,32 @@ impl FileLockRegistry {
         }
 
         map.entry(path)
-            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .or_insert_with(|| Arc::new(tokio::sync::Mutex::new(())))
             .clone()
     }
 }
 
+/// Waits for `lock` from synchronous code.  On a multi-thread runtime (a
+/// synchronous Rust API call made from async code) the worker is handed off
+/// with `block_in_place` first, since blocking it directly would panic.  A
+/// current-thread runtime cannot be handed off, so waiting there is an error
+/// instead of a panic.
+pub(crate) fn lock_blocking(lock: FileLock) -> Result<FileGuard> {
+    let Ok(handle) = Handle::try_current() else {
+        return Ok(lock.blocking_lock_owned());
+    };
+    match handle.runtime_flavor() {
+        RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| lock.blocking_lock_owned()))
+        }
+        _ => Err(Error::Storage(
+            "cannot wait for a file lock on a current-thread async runtime; call the \
+             synchronous API from a blocking thread or a multi-thread runtime"
+                .to_string(),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,4 +136,32 @@ mod tests {
             "actively held lock should survive cleanup"
         );
     }
+
+    #[test]
+    fn lock_blocking_works_on_async_workers() {
+        let registry = FileLockRegistry::new();
+        let lock = registry.acquire(PathBuf::from("sync.json"));
+        // Synchronous Rust API calls can come from async code.
+        let held = tauri::async_runtime::block_on(tauri::async_runtime::spawn(async move {
+            let guard = lock_blocking(lock.clone()).unwrap();
+            let held = lock.try_lock().is_err();
+            drop(guard);
+            held
+        }))
+        .unwrap();
+        assert!(held);
+    }
+
+    #[test]
+    fn lock_blocking_fails_instead_of_panicking_on_a_current_thread_runtime() {
+        let registry = FileLockRegistry::new();
+        let lock = registry.acquire(PathBuf::from("sync.json"));
+        drop(lock_blocking(lock.clone()).unwrap());
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let err = runtime.block_on(async { lock_blocking(lock).unwrap_err() });
+        assert!(matches!(err, Error::Storage(_)));
+    }
 }
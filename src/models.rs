@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use tauri::path::BaseDirectory;
 use zeroize::Zeroizing;
 
+use crate::env_overrides::EnvOverride;
 use crate::error::{Error, Result};
+use crate::scope::ScopeGrant;
 
 /// Supported provider kinds for the normalized runtime model.
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -12,6 +16,10 @@ pub enum ProviderKind {
     Yml,
     Binary,
     Toml,
+    Ini,
+    Dotenv,
+    Xml,
+    Plist,
 }
 
 /// Provider payload sent from the guest side.
@@ -21,6 +29,79 @@ pub struct ProviderPayload {
     pub kind: ProviderKind,
     pub encryption_key: Option<String>,
     pub kdf: Option<KeyDerivation>,
+    /// With `kdf: argon2`, writes keep the file's existing salt when its key
+    /// was already derived in this session instead of deriving a new one.
+    #[serde(default)]
+    pub reuse_salt: bool,
+    /// Compresses Binary files before encryption.  Reads detect compression
+    /// from the file header, so this only affects writes.
+    pub compression: Option<Compression>,
+    /// Encoding of Binary files (default JSON).  Like `compression`, reads
+    /// detect it from the file header.
+    pub encoding: Option<BinaryEncoding>,
+}
+
+impl ProviderPayload {
+    /// Provider payload without encryption.
+    pub fn new(kind: ProviderKind) -> Self {
+        Self {
+            kind,
+            encryption_key: None,
+            kdf: None,
+            reuse_salt: false,
+            compression: None,
+            encoding: None,
+        }
+    }
+
+    pub(crate) fn normalize(self) -> Result<NormalizedProvider> {
+        if !matches!(&self.kind, ProviderKind::Binary) && self.encryption_key.is_some() {
+            return Err(Error::InvalidPayload(
+                "encryptionKey is only supported with provider.kind='binary'".to_string(),
+            ));
+        }
+
+        if !matches!(&self.kind, ProviderKind::Binary) && self.kdf.is_some() {
+            return Err(Error::InvalidPayload(
+                "kdf is only supported with provider.kind='binary'".to_string(),
+            ));
+        }
+
+        if !matches!(&self.kind, ProviderKind::Binary) && self.compression.is_some() {
+            return Err(Error::InvalidPayload(
+                "compression is only supported with provider.kind='binary'".to_string(),
+            ));
+        }
+
+        if !matches!(&self.kind, ProviderKind::Binary) && self.encoding.is_some() {
+            return Err(Error::InvalidPayload(
+                "encoding is only supported with provider.kind='binary'".to_string(),
+            ));
+        }
+
+        if self.reuse_salt && !matches!(&self.kdf, Some(KeyDerivation::Argon2)) {
+            return Err(Error::InvalidPayload(
+                "reuseSalt is only supported with kdf='argon2'".to_string(),
+            ));
+        }
+
+        Ok(match self.kind {
+            ProviderKind::Json => NormalizedProvider::Json,
+            ProviderKind::Yml => NormalizedProvider::Yml,
+            ProviderKind::Toml => NormalizedProvider::Toml,
+            ProviderKind::Ini => NormalizedProvider::Ini,
+            ProviderKind::Dotenv => NormalizedProvider::Dotenv,
+            ProviderKind::Xml => NormalizedProvider::Xml,
+            ProviderKind::Plist => NormalizedProvider::Plist,
+            ProviderKind::Binary => NormalizedProvider::Binary {
+                encryption_key: self.encryption_key.map(Zeroizing::new),
+                kdf: self.kdf.unwrap_or(KeyDerivation::Sha256),
+                reuse_salt: self.reuse_salt,
+                compression: self.compression,
+                encoding: self.encoding.unwrap_or_default(),
+            },
+        })
+    }
 }
 
 /// Optional path options sent from the guest side.
@@ -39,8 +120,11 @@ pub struct KeyringEntry {
     pub id: String,
     /// Dot-separated path to this field inside the config object (e.g. `"database.password"`).
     pub dotpath: String,
-    /// Plaintext value to store in the OS keyring.
-    pub value: String,
+    /// Plaintext value to store in the OS keyring.  Any JSON type is accepted;
+    /// non-string values are restored with their original type on unlock.
+    /// Read operations send `null` (or the legacy empty string).
+    #[serde(default)]
+    pub value: serde_json::Value,
     /// When true, a "not found" keyring error on read is treated as absent (null) rather than an error.
     #[serde(default)]
     pub is_optional: bool,
@@ -82,10 +166,115 @@ pub struct ConfiguratePayload {
     /// not yet exist instead of returning an error.
     #[serde(default)]
     pub create_if_missing: bool,
+    /// How `patch` applies `data` to the existing config.  Defaults to
+    /// `deepMerge`.
+    #[serde(default)]
+    pub patch_format: PatchFormat,
+    /// Per-path array merge strategies for `deepMerge` patches, keyed by
+    /// dotpath (`*` matches any key or index).  Arrays without a strategy
+    /// are replaced.
+    #[serde(default)]
+    pub array_merge: BTreeMap<String, ArrayMergeStrategy>,
     /// When true, rolling backup files are created before each write.
     /// Defaults to false (opt-in).
     #[serde(default)]
     pub backup: bool,
+    /// When true, `load` returns `{ data, metadata }` instead of the bare
+    /// data, with metadata such as environment overrides.
+    #[serde(default)]
+    pub with_metadata: bool,
+    /// Template copied into place by `create` / `load` when the config does
+    /// not exist yet; `data` is deep-merged over it.
+    pub seed_from: Option<SeedFrom>,
+}
+
+/// Bundled template used to seed a missing config (`seedFrom`).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedFrom {
+    pub base_dir: BaseDirectory,
+    pub file_name: String,
+    pub options: Option<PathOptions>,
+    /// Provider of the template.  Inferred from the `fileName` extension
+    /// (`.json`, `.yml` / `.yaml`, `.toml`, `.ini`, `.env`, `.xml`, `.plist`)
+    /// when omitted, falling back to the target's provider.
+    pub provider: Option<ProviderPayload>,
+}
+
+impl SeedFrom {
+    /// Template at `{base_dir}/{file_name}`, for use from Rust.
+    pub fn new(file_name: impl Into<String>, base_dir: BaseDirectory) -> Self {
+        Self {
+            base_dir,
+            file_name: file_name.into(),
+            options: None,
+            provider: None,
+        }
+    }
+
+    fn normalize(self, target: &NormalizedProvider) -> Result<NormalizedConfiguratePayload> {
+        let provider = match self.provider {
+            Some(provider) => provider,
+            None => ProviderPayload::new(
+                provider_kind_for_extension(&self.file_name).unwrap_or(match target {
+                    NormalizedProvider::Json => ProviderKind::Json,
+                    NormalizedProvider::Yml => ProviderKind::Yml,
+                    NormalizedProvider::Toml => ProviderKind::Toml,
+                    NormalizedProvider::Ini => ProviderKind::Ini,
+                    NormalizedProvider::Dotenv => ProviderKind::Dotenv,
+                    NormalizedProvider::Xml => ProviderKind::Xml,
+                    NormalizedProvider::Plist => ProviderKind::Plist,
+                    NormalizedProvider::Binary { .. } => ProviderKind::Binary,
+                }),
+            ),
+        };
+        let mut payload = ConfiguratePayload::new(self.file_name, self.base_dir, provider);
+        payload.options = self.options;
+        payload.normalize()
+    }
+}
+
+fn provider_kind_for_extension(file_name: &str) -> Option<ProviderKind> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+    match extension.to_ascii_lowercase().as_str() {
+        "json" => Some(ProviderKind::Json),
+        "yml" | "yaml" => Some(ProviderKind::Yml),
+        "toml" => Some(ProviderKind::Toml),
+        "ini" => Some(ProviderKind::Ini),
+        "env" => Some(ProviderKind::Dotenv),
+        "xml" => Some(ProviderKind::Xml),
+        "plist" => Some(ProviderKind::Plist),
+        _ => None,
+    }
+}
+
+/// Format of the `data` sent to `patch`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatchFormat {
+    /// Objects are merged recursively; any other value replaces the existing one.
+    #[default]
+    DeepMerge,
+    /// RFC 7396 JSON Merge Patch: like `deepMerge`, but `null` deletes the key.
+    MergePatch,
+    /// RFC 6902 JSON Patch: `data` is an array of operations.
+    JsonPatch,
+}
+
+/// How a `deepMerge` patch combines an array with the stored array.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "strategy", rename_all = "camelCase")]
+pub enum ArrayMergeStrategy {
+    /// The patch array replaces the stored array (default).
+    Replace,
+    /// Patch elements are appended to the stored array.
+    Append,
+    /// Object elements whose `key` field equals a stored element's are
+    /// deep-merged into it; all other elements are appended.
+    UnionBy { key: String },
+    /// Element `i` of the patch is deep-merged into stored element `i`;
+    /// extra elements are appended.
+    ByIndex,
 }
 
 /// Key derivation function used by the Binary provider.
@@ -96,6 +285,23 @@ pub enum KeyDerivation {
     Argon2,
 }
 
+/// Compression applied to Binary files before encryption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    Deflate,
+}
+
+/// Encoding of Binary files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryEncoding {
+    #[default]
+    Json,
+    Cbor,
+    Msgpack,
+}
+
 /// Normalized provider used internally after payload normalization.
 ///
 /// Each variant carries only the fields that are meaningful for that provider,
@@ -105,9 +311,16 @@ pub enum NormalizedProvider {
     Json,
     Yml,
     Toml,
+    Ini,
+    Dotenv,
+    Xml,
+    Plist,
     Binary {
         encryption_key: Option<Zeroizing<String>>,
         kdf: KeyDerivation,
+        reuse_salt: bool,
+        compression: Option<Compression>,
+        encoding: BinaryEncoding,
     },
 }
 
@@ -119,13 +332,26 @@ impl std::fmt::Debug for NormalizedProvider {
             Self::Json => write!(f, "Json"),
             Self::Yml => write!(f, "Yml"),
             Self::Toml => write!(f, "Toml"),
-            Self::Binary { encryption_key, kdf } => f
+            Self::Ini => write!(f, "Ini"),
+            Self::Dotenv => write!(f, "Dotenv"),
+            Self::Xml => write!(f, "Xml"),
+            Self::Plist => write!(f, "Plist"),
+            Self::Binary {
+                encryption_key,
+                kdf,
+                reuse_salt,
+                compression,
+                encoding,
+            } => f
                 .debug_struct("Binary")
                 .field(
                     "encryption_key",
                     &encryption_key.as_ref().map(|_| "[REDACTED]"),
                 )
                 .field("kdf", kdf)
+                .field("reuse_salt", reuse_salt)
+                .field("compression", compression)
+                .field("encoding", encoding)
                 .finish(),
         }
     }
@@ -147,11 +373,47 @@ pub struct NormalizedConfiguratePayload {
     pub return_data: bool,
     /// When true, `patch` creates the config if it does not exist.
     pub create_if_missing: bool,
+    /// How `patch` applies `data`.
+    pub patch_format: PatchFormat,
+    /// Per-path array merge strategies for `deepMerge` patches.
+    pub array_merge: BTreeMap<String, ArrayMergeStrategy>,
     /// When true, rolling backup files are created before each write.
     pub backup: bool,
+    /// When true, `load` wraps its result with [`LoadMetadata`].
+    pub with_metadata: bool,
+    /// Normalized `seedFrom` template.
+    pub seed_from: Option<Box<NormalizedConfiguratePayload>>,
+    /// Capability scope of the invoking window, when called over IPC.
+    pub(crate) scope: Option<ScopeGrant>,
 }
 
 impl ConfiguratePayload {
+    /// Payload addressing `{base_dir}/{file_name}`, for use from Rust.
+    pub fn new(
+        file_name: impl Into<String>,
+        base_dir: BaseDirectory,
+        provider: ProviderPayload,
+    ) -> Self {
+        Self {
+            file_name: Some(file_name.into()),
+            base_dir: Some(base_dir),
+            options: None,
+            provider: Some(provider),
+            data: None,
+            keyring_entries: None,
+            keyring_options: None,
+            keyring_delete_ids: Vec::new(),
+            with_unlock: false,
+            return_data: None,
+            create_if_missing: false,
+            patch_format: PatchFormat::DeepMerge,
+            array_merge: BTreeMap::new(),
+            backup: false,
+            with_metadata: false,
+            seed_from: None,
+        }
+    }
+
     pub fn normalize(self) -> Result<NormalizedConfiguratePayload> {
         let file_name = self
             .file_name
@@ -176,33 +438,17 @@ impl ConfiguratePayload {
             ));
         }
 
-        if !matches!(&provider_payload.kind, ProviderKind::Binary)
-            && provider_payload.encryption_key.is_some()
-        {
-            return Err(Error::InvalidPayload(
-                "encryptionKey is only supported with provider.kind='binary'".to_string(),
-            ));
-        }
-
-        if !matches!(&provider_payload.kind, ProviderKind::Binary)
-            && provider_payload.kdf.is_some()
-        {
+        if !self.array_merge.is_empty() && self.patch_format != PatchFormat::DeepMerge {
             return Err(Error::InvalidPayload(
-                "kdf is only supported with provider.kind='binary'".to_string(),
+                "arrayMerge is only supported with patchFormat 'deepMerge'".to_string(),
             ));
         }
 
-        let provider = match provider_payload.kind {
-            ProviderKind::Json => NormalizedProvider::Json,
-            ProviderKind::Yml => NormalizedProvider::Yml,
-            ProviderKind::Toml => NormalizedProvider::Toml,
-            ProviderKind::Binary => NormalizedProvider::Binary {
-                encryption_key: provider_payload
-                    .encryption_key
-                    .map(Zeroizing::new),
-                kdf: provider_payload.kdf.unwrap_or(KeyDerivation::Sha256),
-            },
-        };
+        let provider = provider_payload.normalize()?;
+        let seed_from = self
+            .seed_from
+            .map(|seed| seed.normalize(&provider).map(Box::new))
+            .transpose()?;
 
         Ok(NormalizedConfiguratePayload {
             file_name,
@@ -217,7 +463,12 @@ impl ConfiguratePayload {
             with_unlock: self.with_unlock,
             return_data: self.return_data.unwrap_or(true),
             create_if_missing: self.create_if_missing,
+            patch_format: self.patch_format,
+            array_merge: self.array_merge,
             backup: self.backup,
+            with_metadata: self.with_metadata,
+            seed_from,
+            scope: None,
         })
     }
 }
@@ -226,11 +477,71 @@ impl ConfiguratePayload {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UnlockPayload {
+    /// Config the secrets belong to; the window needs `keyring` scope
+    /// access to it.
+    pub target: ConfiguratePayload,
     pub data: serde_json::Value,
     pub keyring_entries: Option<Vec<KeyringEntry>>,
     pub keyring_options: Option<KeyringOptions>,
 }
 
+/// Payload for the `get_value`, `set_value` and `remove_value` commands.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValuePayload {
+    /// Config the value is read from or written to.  `createIfMissing` lets
+    /// `set_value` create a missing file.
+    pub target: ConfiguratePayload,
+    /// Dotpath, bracket path or JSON Pointer of the value.  Wildcards are rejected.
+    pub dotpath: String,
+    /// New value for `set_value`; ignored by the other commands.
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+/// Payload for the `keyring_gc` command.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyringGcPayload {
+    /// Config whose keyring manifest is collected.  `keyringOptions`, when
+    /// set, restricts collection to that service/account.
+    pub target: ConfiguratePayload,
+    /// Keyring ids still declared in the schema; every other tracked id is
+    /// deleted.  Required: an empty list is refused unless `all` or
+    /// `dry_run` is set.
+    pub keep_ids: Vec<String>,
+    /// Confirms that an empty `keep_ids` should delete every tracked id.
+    #[serde(default)]
+    pub all: bool,
+    /// When true, only reports what would be removed.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Single id reported by `keyring_list`.
+#[derive(Debug, Serialize)]
+pub struct KeyringListEntry {
+    #[serde(flatten)]
+    pub entry: crate::keyring_manifest::KeyringManifestEntry,
+    /// Whether a secret is currently stored for this id.
+    pub exists: bool,
+}
+
+/// Entry that `keyring_gc` failed to delete.
+#[derive(Debug, Serialize)]
+pub struct KeyringGcFailure {
+    #[serde(flatten)]
+    pub entry: crate::keyring_manifest::KeyringManifestEntry,
+    pub error: serde_json::Value,
+}
+
+/// Response of the `keyring_gc` command.
+#[derive(Debug, Default, Serialize)]
+pub struct KeyringGcResult {
+    pub removed: Vec<crate::keyring_manifest::KeyringManifestEntry>,
+    pub failed: Vec<KeyringGcFailure>,
+}
+
 /// Payload for the `patch` command (reuses `ConfiguratePayload`).
 pub type PatchPayload = ConfiguratePayload;
 
@@ -249,11 +560,24 @@ pub struct BatchPayload {
     pub entries: Vec<BatchEntryPayload>,
 }
 
+/// Extra information about loaded data.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadMetadata {
+    /// Keys whose values were replaced by environment variables.
+    pub env_overrides: Vec<EnvOverride>,
+    /// Dotpaths locked by the managed policy.
+    pub locked_keys: Vec<String>,
+}
+
 /// Per-entry successful result.
 #[derive(Debug, Serialize)]
 pub struct BatchEntrySuccess {
     pub ok: bool,
     pub data: serde_json::Value,
+    /// Set for `load_all` entries with environment overrides or locked keys.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<LoadMetadata>,
 }
 
 /// Per-entry failed result.
@@ -282,20 +606,13 @@ mod tests {
     use super::*;
 
     fn base_payload() -> ConfiguratePayload {
-        ConfiguratePayload {
-            file_name: Some("app.json".to_string()),
-            base_dir: Some(BaseDirectory::AppConfig),
-            options: None,
-            provider: None,
-            data: None,
-            keyring_entries: None,
-            keyring_options: None,
-            keyring_delete_ids: Vec::new(),
-            with_unlock: false,
-            return_data: None,
-            create_if_missing: false,
-            backup: false,
-        }
+        let mut payload = ConfiguratePayload::new(
+            "app.json",
+            BaseDirectory::AppConfig,
+            ProviderPayload::new(ProviderKind::Json),
+        );
+        payload.provider = None;
+        payload
     }
 
     #[test]
@@ -305,6 +622,9 @@ mod tests {
             kind: ProviderKind::Json,
             encryption_key: Some("key".to_string()),
             kdf: None,
+            reuse_salt: false,
+            compression: None,
+            encoding: None,
         });
 
         let err = payload.normalize().expect_err("expected invalid payload");
@@ -326,11 +646,16 @@ mod tests {
             kind: ProviderKind::Binary,
             encryption_key: Some("my-key".to_string()),
             kdf: None,
+            reuse_salt: false,
+            compression: None,
+            encoding: None,
         });
 
         let normalized = payload.normalize().expect("expected valid payload");
         match normalized.provider {
-            NormalizedProvider::Binary { encryption_key, kdf } => {
+            NormalizedProvider::Binary {
+                encryption_key, kdf, ..
+            } => {
                 assert_eq!(encryption_key.as_ref().map(|k| k.as_str()), Some("my-key"));
                 assert!(matches!(kdf, KeyDerivation::Sha256), "expected default kdf to be Sha256");
             }
@@ -338,6 +663,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn normalize_rejects_reuse_salt_without_argon2() {
+        let mut payload = base_payload();
+        payload.provider = Some(ProviderPayload {
+            kind: ProviderKind::Binary,
+            encryption_key: Some("my-key".to_string()),
+            kdf: Some(KeyDerivation::Sha256),
+            reuse_salt: true,
+            compression: None,
+            encoding: None,
+        });
+
+        let err = payload.normalize().expect_err("expected invalid payload");
+        assert!(matches!(err, Error::InvalidPayload(_)));
+    }
+
+    #[test]
+    fn normalize_rejects_compression_with_non_binary_provider() {
+        let mut payload = base_payload();
+        let mut provider = ProviderPayload::new(ProviderKind::Toml);
+        provider.compression = Some(Compression::Deflate);
+        payload.provider = Some(provider);
+
+        let err = payload.normalize().expect_err("expected invalid payload");
+        assert!(matches!(err, Error::InvalidPayload(_)));
+    }
+
     #[test]
     fn normalize_rejects_keyring_delete_ids_without_keyring_options() {
         let mut payload = base_payload();
@@ -345,6 +697,9 @@ mod tests {
             kind: ProviderKind::Json,
             encryption_key: None,
             kdf: None,
+            reuse_salt: false,
+            compression: None,
+            encoding: None,
         });
         payload.keyring_delete_ids = vec!["tok".to_string()];
 
@@ -356,4 +711,30 @@ mod tests {
             _ => panic!("unexpected error variant"),
         }
     }
+
+    #[test]
+    fn seed_from_infers_template_provider_from_extension() {
+        let seeded = |seed: SeedFrom| {
+            let mut payload = base_payload();
+            payload.provider = Some(ProviderPayload::new(ProviderKind::Toml));
+            payload.seed_from = Some(seed);
+            *payload
+                .normalize()
+                .expect("expected valid payload")
+                .seed_from
+                .expect("expected a template")
+        };
+
+        let template = seeded(SeedFrom::new("defaults.JSON", BaseDirectory::Resource));
+        assert_eq!(template.file_name, "defaults.JSON");
+        assert!(matches!(template.base_dir, BaseDirectory::Resource));
+        assert!(matches!(template.provider, NormalizedProvider::Json));
+
+        let template = seeded(SeedFrom::new("defaults", BaseDirectory::Resource));
+        assert!(matches!(template.provider, NormalizedProvider::Toml));
+
+        let mut explicit = SeedFrom::new("defaults.json", BaseDirectory::Resource);
+        explicit.provider = Some(ProviderPayload::new(ProviderKind::Yml));
+        assert!(matches!(seeded(explicit).provider, NormalizedProvider::Yml));
+    }
 }
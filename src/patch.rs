@@ -0,0 +1,784 @@
+/// Patch formats accepted by the `patch` command.
+///
+/// - [`PatchFormat::DeepMerge`]: objects are merged recursively, every other
+///   value (including `null`) replaces the existing one.  Arrays are replaced
+///   unless an [`ArrayMergeStrategy`] is configured for their path;
+/// - [`PatchFormat::MergePatch`]: RFC 7396 JSON Merge Patch — like deep merge,
+///   but a `null` member deletes the key;
+/// - [`PatchFormat::JsonPatch`]: RFC 6902 JSON Patch — an array of
+///   `add` / `remove` / `replace` / `move` / `copy` / `test` operations whose
+///   paths are RFC 6901 JSON Pointers.
+///
+/// JSON Patch documents are applied all-or-nothing: the first failing
+/// operation aborts the patch with an [`Error::Patch`] naming the operation.
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::dotpath::{self, Segment, MAX_ARRAY_INDEX, MAX_PATH_SEGMENTS};
+use crate::error::{Error, Result};
+use crate::models::{ArrayMergeStrategy, PatchFormat};
+
+/// Maximum number of `arrayMerge` entries in one patch.
+const MAX_ARRAY_STRATEGIES: usize = 64;
+
+/// Applies `patch` to `base` using `format`.
+///
+/// `base` may be left partially modified when an error is returned; callers
+/// must discard it instead of persisting it.
+pub fn apply(
+    base: &mut Value,
+    patch: Value,
+    format: PatchFormat,
+    strategies: &ArrayStrategies,
+) -> Result<()> {
+    match format {
+        PatchFormat::DeepMerge => deep_merge(base, patch, strategies),
+        PatchFormat::MergePatch => merge_patch(base, patch),
+        PatchFormat::JsonPatch => apply_json_patch(base, &patch)?,
+    }
+    Ok(())
+}
+
+/// Parsed `arrayMerge` table: dotpath patterns and their strategies.
+#[derive(Debug, Default)]
+pub struct ArrayStrategies {
+    entries: Vec<(Vec<Segment>, ArrayMergeStrategy)>,
+}
+
+/// One concrete step of the path being merged.
+#[derive(Debug, Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+impl ArrayStrategies {
+    /// Parses and validates the `arrayMerge` payload field.
+    pub fn parse(table: &BTreeMap<String, ArrayMergeStrategy>) -> Result<Self> {
+        if table.len() > MAX_ARRAY_STRATEGIES {
+            return Err(Error::InvalidPayload(format!(
+                "arrayMerge exceeds maximum of {} entries",
+                MAX_ARRAY_STRATEGIES
+            )));
+        }
+        let mut entries = Vec::with_capacity(table.len());
+        for (path, strategy) in table {
+            if let ArrayMergeStrategy::UnionBy { key } = strategy {
+                if key.is_empty() {
+                    return Err(Error::InvalidPayload(format!(
+                        "arrayMerge '{}': unionBy key must not be empty",
+                        path
+                    )));
+                }
+            }
+            entries.push((dotpath::parse(path)?, strategy.clone()));
+        }
+        // Most specific pattern first: fewer wildcards win.
+        entries.sort_by_key(|(segments, _)| {
+            segments.iter().filter(|s| **s == Segment::Wildcard).count()
+        });
+        Ok(Self { entries })
+    }
+
+    fn lookup(&self, path: &[Step]) -> &ArrayMergeStrategy {
+        self.entries
+            .iter()
+            .find(|(pattern, _)| {
+                pattern.len() == path.len()
+                    && pattern
+                        .iter()
+                        .zip(path)
+                        .all(|(segment, step)| match (segment, step) {
+                            (Segment::Wildcard, _) => true,
+                            (Segment::Key(k) | Segment::Auto(k), Step::Key(key)) => k == key,
+                            (Segment::Index(i), Step::Index(idx)) => i == idx,
+                            (Segment::Auto(raw), Step::Index(idx)) => {
+                                raw.parse::<usize>().is_ok_and(|i| i == *idx)
+                            }
+                            _ => false,
+                        })
+            })
+            .map(|(_, strategy)| strategy)
+            .unwrap_or(&ArrayMergeStrategy::Replace)
+    }
+}
+
+/// Deep-merges `patch` into `base`. Object keys are merged recursively;
+/// arrays follow `strategies` and all other values are replaced.
+pub fn deep_merge(base: &mut Value, patch: Value, strategies: &ArrayStrategies) {
+    merge_at(base, patch, strategies, &mut Vec::new());
+}
+
+fn merge_at(base: &mut Value, patch: Value, strategies: &ArrayStrategies, path: &mut Vec<Step>) {
+    match (base, patch) {
+        (Value::Object(base_map), Value::Object(patch_map)) => {
+            for (key, patch_val) in patch_map {
+                path.push(Step::Key(key.clone()));
+                let entry = base_map.entry(key).or_insert(Value::Null);
+                merge_at(entry, patch_val, strategies, path);
+                path.pop();
+            }
+        }
+        (Value::Array(base_arr), Value::Array(patch_arr)) if !strategies.entries.is_empty() => {
+            merge_array(base_arr, patch_arr, strategies, path);
+        }
+        (base, patch) => {
+            *base = patch;
+        }
+    }
+}
+
+fn merge_array(
+    base: &mut Vec<Value>,
+    patch: Vec<Value>,
+    strategies: &ArrayStrategies,
+    path: &mut Vec<Step>,
+) {
+    match strategies.lookup(path) {
+        ArrayMergeStrategy::Replace => *base = patch,
+        ArrayMergeStrategy::Append => base.extend(patch),
+        ArrayMergeStrategy::ByIndex => {
+            for (idx, element) in patch.into_iter().enumerate() {
+                if idx < base.len() {
+                    path.push(Step::Index(idx));
+                    merge_at(&mut base[idx], element, strategies, path);
+                    path.pop();
+                } else {
+                    base.push(element);
+                }
+            }
+        }
+        ArrayMergeStrategy::UnionBy { key } => {
+            let key = key.clone();
+            for element in patch {
+                let matched = element.get(&key).filter(|id| !id.is_null()).and_then(|id| {
+                    base.iter()
+                        .position(|existing| existing.get(&key) == Some(id))
+                });
+                match matched {
+                    Some(idx) => {
+                        path.push(Step::Index(idx));
+                        merge_at(&mut base[idx], element, strategies, path);
+                        path.pop();
+                    }
+                    None => base.push(element),
+                }
+            }
+        }
+    }
+}
+
+/// Applies an RFC 7396 JSON Merge Patch: `null` members delete keys, objects
+/// are merged recursively and everything else replaces the target.
+pub fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(patch_map) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    let Value::Object(target_map) = target else {
+        unreachable!("target was just made an object");
+    };
+    for (key, patch_val) in patch_map {
+        if patch_val.is_null() {
+            target_map.remove(&key);
+        } else {
+            merge_patch(target_map.entry(key).or_insert(Value::Null), patch_val);
+        }
+    }
+}
+
+/// One parsed RFC 6902 operation.
+#[derive(Debug)]
+enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+impl Operation {
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Add { .. } => "add",
+            Operation::Remove { .. } => "remove",
+            Operation::Replace { .. } => "replace",
+            Operation::Move { .. } => "move",
+            Operation::Copy { .. } => "copy",
+            Operation::Test { .. } => "test",
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self {
+            Operation::Add { path, .. }
+            | Operation::Remove { path }
+            | Operation::Replace { path, .. }
+            | Operation::Move { path, .. }
+            | Operation::Copy { path, .. }
+            | Operation::Test { path, .. } => path,
+        }
+    }
+}
+
+fn parse_operation(raw: &Value) -> std::result::Result<Operation, String> {
+    let obj = raw
+        .as_object()
+        .ok_or_else(|| "operation must be an object".to_string())?;
+    let member = |name: &str| -> std::result::Result<String, String> {
+        match obj.get(name) {
+            Some(Value::String(s)) => Ok(s.clone()),
+            Some(_) => Err(format!("'{}' must be a string", name)),
+            None => Err(format!("missing '{}'", name)),
+        }
+    };
+    let value = || {
+        obj.get("value")
+            .cloned()
+            .ok_or_else(|| "missing 'value'".to_string())
+    };
+    let op = member("op")?;
+    let path = member("path")?;
+    Ok(match op.as_str() {
+        "add" => Operation::Add {
+            path,
+            value: value()?,
+        },
+        "remove" => Operation::Remove { path },
+        "replace" => Operation::Replace {
+            path,
+            value: value()?,
+        },
+        "move" => Operation::Move {
+            from: member("from")?,
+            path,
+        },
+        "copy" => Operation::Copy {
+            from: member("from")?,
+            path,
+        },
+        "test" => Operation::Test {
+            path,
+            value: value()?,
+        },
+        other => return Err(format!("unknown op '{}'", other)),
+    })
+}
+
+/// Applies an RFC 6902 JSON Patch document to `root`.
+pub fn apply_json_patch(root: &mut Value, patch: &Value) -> Result<()> {
+    let ops = patch
+        .as_array()
+        .ok_or_else(|| Error::Patch("jsonPatch data must be an array of operations".to_string()))?;
+    for (index, raw) in ops.iter().enumerate() {
+        let op = parse_operation(raw)
+            .map_err(|reason| Error::Patch(format!("operation {}: {}", index, reason)))?;
+        apply_operation(root, &op).map_err(|reason| {
+            Error::Patch(format!(
+                "operation {} ({} '{}'): {}",
+                index,
+                op.name(),
+                op.path(),
+                reason
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+type OpResult<T> = std::result::Result<T, String>;
+
+fn apply_operation(root: &mut Value, op: &Operation) -> OpResult<()> {
+    match op {
+        Operation::Add { path, value } => add(root, &pointer(path)?, value.clone()),
+        Operation::Remove { path } => remove(root, &pointer(path)?).map(drop),
+        Operation::Replace { path, value } => {
+            let target = resolve_mut(root, &pointer(path)?)
+                .ok_or_else(|| "path does not exist".to_string())?;
+            *target = value.clone();
+            Ok(())
+        }
+        Operation::Move { from, path } => {
+            let from_segments = pointer(from)?;
+            let to_segments = pointer(path)?;
+            if from_segments == to_segments {
+                return resolve(root, &from_segments)
+                    .map(drop)
+                    .ok_or_else(|| format!("'from' path '{}' does not exist", from));
+            }
+            if to_segments.starts_with(&from_segments) {
+                return Err(format!(
+                    "cannot move '{}' into one of its own children",
+                    from
+                ));
+            }
+            let value = remove(root, &from_segments)
+                .map_err(|reason| format!("'from' path '{}': {}", from, reason))?;
+            add(root, &to_segments, value)
+        }
+        Operation::Copy { from, path } => {
+            let value = resolve(root, &pointer(from)?)
+                .cloned()
+                .ok_or_else(|| format!("'from' path '{}' does not exist", from))?;
+            add(root, &pointer(path)?, value)
+        }
+        Operation::Test { path, value } => {
+            let actual = resolve(root, &pointer(path)?)
+                .ok_or_else(|| "test failed: path does not exist".to_string())?;
+            if json_equal(actual, value) {
+                Ok(())
+            } else {
+                Err(format!(
+                    "test failed: expected {}, found {}",
+                    preview(value),
+                    preview(actual)
+                ))
+            }
+        }
+    }
+}
+
+/// Parses an RFC 6901 JSON Pointer.  `""` is the whole document.
+fn pointer(path: &str) -> OpResult<Vec<String>> {
+    let segments = dotpath::pointer_tokens(path).map_err(str::to_string)?;
+    if segments.len() > MAX_PATH_SEGMENTS {
+        return Err(format!(
+            "path exceeds maximum of {} segments",
+            MAX_PATH_SEGMENTS
+        ));
+    }
+    Ok(segments)
+}
+
+/// Parses an array index segment: digits without leading zeros.
+fn array_index(segment: &str) -> OpResult<usize> {
+    let valid = !segment.is_empty()
+        && segment.bytes().all(|b| b.is_ascii_digit())
+        && (segment == "0" || !segment.starts_with('0'));
+    if !valid {
+        return Err(format!("'{}' is not a valid array index", segment));
+    }
+    segment
+        .parse()
+        .map_err(|_| format!("array index '{}' is too large", segment))
+}
+
+fn child<'a>(node: &'a Value, segment: &str) -> Option<&'a Value> {
+    match node {
+        Value::Object(map) => map.get(segment),
+        Value::Array(arr) => arr.get(array_index(segment).ok()?),
+        _ => None,
+    }
+}
+
+fn resolve<'a>(root: &'a Value, segments: &[String]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(root, |node, segment| child(node, segment))
+}
+
+fn resolve_mut<'a>(root: &'a mut Value, segments: &[String]) -> Option<&'a mut Value> {
+    let mut node = root;
+    for segment in segments {
+        node = match node {
+            Value::Object(map) => map.get_mut(segment)?,
+            Value::Array(arr) => arr.get_mut(array_index(segment).ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+fn add(root: &mut Value, segments: &[String], value: Value) -> OpResult<()> {
+    let Some((last, parent)) = segments.split_last() else {
+        *root = value;
+        return Ok(());
+    };
+    let parent =
+        resolve_mut(root, parent).ok_or_else(|| "parent path does not exist".to_string())?;
+    match parent {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Value::Array(arr) => {
+            let idx = if last == "-" {
+                arr.len()
+            } else {
+                array_index(last)?
+            };
+            if idx > arr.len() {
+                return Err(format!(
+                    "index {} is out of bounds (array length {})",
+                    idx,
+                    arr.len()
+                ));
+            }
+            if arr.len() > MAX_ARRAY_INDEX {
+                return Err(format!(
+                    "array growth exceeds maximum index {}",
+                    MAX_ARRAY_INDEX
+                ));
+            }
+            arr.insert(idx, value);
+            Ok(())
+        }
+        _ => Err("parent is not an object or array".to_string()),
+    }
+}
+
+fn remove(root: &mut Value, segments: &[String]) -> OpResult<Value> {
+    let Some((last, parent)) = segments.split_last() else {
+        return Err("cannot remove the whole document".to_string());
+    };
+    let parent = resolve_mut(root, parent).ok_or_else(|| "path does not exist".to_string())?;
+    match parent {
+        Value::Object(map) => map
+            .remove(last)
+            .ok_or_else(|| "path does not exist".to_string()),
+        Value::Array(arr) => {
+            let idx = array_index(last)?;
+            if idx >= arr.len() {
+                return Err(format!(
+                    "index {} is out of bounds (array length {})",
+                    idx,
+                    arr.len()
+                ));
+            }
+            Ok(arr.remove(idx))
+        }
+        _ => Err("path does not exist".to_string()),
+    }
+}
+
+/// JSON equality per RFC 6902 §4.6: numbers compare by value, so `1` equals `1.0`.
+/// Two integers compare exactly, even beyond the 2^53 that `f64` can hold.
+fn json_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) if x.is_f64() || y.is_f64() => {
+            matches!((x.as_f64(), y.as_f64()), (Some(x), Some(y)) if x == y)
+        }
+        (Value::Number(x), Value::Number(y)) => {
+            x.as_i64() == y.as_i64() && x.as_u64() == y.as_u64()
+        }
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| json_equal(x, y))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(key, x)| y.get(key).is_some_and(|y| json_equal(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Short JSON rendering of a value for error messages.
+fn preview(value: &Value) -> String {
+    const MAX_CHARS: usize = 64;
+    let text = value.to_string();
+    if text.chars().count() <= MAX_CHARS {
+        return text;
+    }
+    let truncated: String = text.chars().take(MAX_CHARS).collect();
+    format!("{}…", truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn deep_merge_adds_new_keys() {
+        let mut base = json!({"a": 1});
+        deep_merge(&mut base, json!({"b": 2}), &ArrayStrategies::default());
+        assert_eq!(base, json!({"a": 1, "b": 2}));
+    }
+
+    #[test]
+    fn deep_merge_overwrites_scalar() {
+        let mut base = json!({"a": 1});
+        deep_merge(&mut base, json!({"a": 99}), &ArrayStrategies::default());
+        assert_eq!(base, json!({"a": 99}));
+    }
+
+    #[test]
+    fn deep_merge_nested_objects() {
+        let mut base = json!({"db": {"host": "localhost", "port": 5432}});
+        deep_merge(
+            &mut base,
+            json!({"db": {"port": 3306, "name": "mydb"}}),
+            &ArrayStrategies::default(),
+        );
+        assert_eq!(
+            base,
+            json!({"db": {"host": "localhost", "port": 3306, "name": "mydb"}})
+        );
+    }
+
+    #[test]
+    fn deep_merge_replaces_non_object_with_object() {
+        let mut base = json!({"a": "string"});
+        deep_merge(
+            &mut base,
+            json!({"a": {"nested": true}}),
+            &ArrayStrategies::default(),
+        );
+        assert_eq!(base, json!({"a": {"nested": true}}));
+    }
+
+    #[test]
+    fn merge_patch_follows_rfc7396_examples() {
+        let mut target = json!({
+            "title": "Goodbye!",
+            "author": {"givenName": "John", "familyName": "Doe"},
+            "tags": ["example", "sample"],
+            "content": "This will be unchanged"
+        });
+        merge_patch(
+            &mut target,
+            json!({
+                "title": "Hello!",
+                "phoneNumber": "+01-123-456-7890",
+                "author": {"familyName": null},
+                "tags": ["example"]
+            }),
+        );
+        assert_eq!(
+            target,
+            json!({
+                "title": "Hello!",
+                "author": {"givenName": "John"},
+                "tags": ["example"],
+                "content": "This will be unchanged",
+                "phoneNumber": "+01-123-456-7890"
+            })
+        );
+
+        let mut scalar = json!("old");
+        merge_patch(&mut scalar, json!({"a": {"b": null, "c": 1}}));
+        assert_eq!(scalar, json!({"a": {"c": 1}}));
+    }
+
+    #[test]
+    fn json_patch_applies_every_operation() {
+        let mut doc = json!({"a": {"b": 1}, "list": [1, 2], "old": "x"});
+        apply_json_patch(
+            &mut doc,
+            &json!([
+                {"op": "test", "path": "/a/b", "value": 1.0},
+                {"op": "add", "path": "/list/-", "value": 3},
+                {"op": "add", "path": "/list/0", "value": 0},
+                {"op": "replace", "path": "/a/b", "value": 2},
+                {"op": "move", "from": "/old", "path": "/new"},
+                {"op": "copy", "from": "/a", "path": "/a~1copy"},
+                {"op": "remove", "path": "/list/1"}
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            doc,
+            json!({"a": {"b": 2}, "a/copy": {"b": 2}, "list": [0, 2, 3], "new": "x"})
+        );
+    }
+
+    #[test]
+    fn json_patch_test_compares_large_integers_exactly() {
+        let mut doc = json!({"id": 9007199254740992u64, "neg": -9007199254740992i64});
+        for (path, value) in [
+            ("/id", json!(9007199254740993u64)),
+            ("/neg", json!(-9007199254740993i64)),
+        ] {
+            let patch = json!([{"op": "test", "path": path, "value": value}]);
+            assert!(apply_json_patch(&mut doc, &patch).is_err(), "{}", path);
+        }
+        let patch = json!([
+            {"op": "test", "path": "/id", "value": 9007199254740992u64},
+            {"op": "test", "path": "/id", "value": 9007199254740992.0}
+        ]);
+        apply_json_patch(&mut doc, &patch).unwrap();
+    }
+
+    #[test]
+    fn json_patch_errors_name_the_failing_operation() {
+        let cases = [
+            (
+                json!([{"op": "remove", "path": "/missing"}]),
+                "operation 0 (remove '/missing'): path does not exist",
+            ),
+            (
+                json!([{"op": "add", "path": "/a/b/c", "value": 1}]),
+                "operation 0 (add '/a/b/c'): parent path does not exist",
+            ),
+            (
+                json!([{"op": "add", "path": "/list/5", "value": 1}]),
+                "index 5 is out of bounds (array length 1)",
+            ),
+            (
+                json!([{"op": "test", "path": "/a", "value": 2}]),
+                "test failed: expected 2, found 1",
+            ),
+            (
+                json!([{"op": "move", "from": "/list", "path": "/list/0"}]),
+                "into one of its own children",
+            ),
+            (
+                json!([{"op": "add", "path": "a", "value": 1}]),
+                "must be a JSON Pointer",
+            ),
+            (
+                json!([{"op": "add", "path": "/x"}]),
+                "operation 0: missing 'value'",
+            ),
+            (
+                json!([{"op": "frobnicate", "path": "/x"}]),
+                "operation 0: unknown op 'frobnicate'",
+            ),
+            (
+                json!([{"op": "test", "path": "/a", "value": 1}, {"op": "remove", "path": "/list/01"}]),
+                "operation 1 (remove '/list/01'): '01' is not a valid array index",
+            ),
+            (json!({"op": "add"}), "must be an array of operations"),
+        ];
+        for (patch, expected) in cases {
+            let mut doc = json!({"a": 1, "list": [1]});
+            let err = apply_json_patch(&mut doc, &patch).unwrap_err();
+            assert!(matches!(err, Error::Patch(_)));
+            assert!(
+                err.to_string().contains(expected),
+                "expected '{}' in '{}'",
+                expected,
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn json_patch_pointer_edge_cases() {
+        let mut doc = json!({"": 1, "*": {"~": 2}});
+        apply_json_patch(
+            &mut doc,
+            &json!([
+                {"op": "test", "path": "/", "value": 1},
+                {"op": "test", "path": "/*/~0", "value": 2},
+                {"op": "replace", "path": "", "value": {"root": true}}
+            ]),
+        )
+        .unwrap();
+        assert_eq!(doc, json!({"root": true}));
+    }
+
+    #[test]
+    fn apply_dispatches_on_format() {
+        let mut doc = json!({"a": 1, "b": 2});
+        let none = ArrayStrategies::default();
+        apply(&mut doc, json!({"a": null}), PatchFormat::DeepMerge, &none).unwrap();
+        assert_eq!(doc, json!({"a": null, "b": 2}));
+        apply(&mut doc, json!({"b": null}), PatchFormat::MergePatch, &none).unwrap();
+        assert_eq!(doc, json!({"a": null}));
+        apply(
+            &mut doc,
+            json!([{"op": "remove", "path": "/a"}]),
+            PatchFormat::JsonPatch,
+            &none,
+        )
+        .unwrap();
+        assert_eq!(doc, json!({}));
+    }
+
+    fn strategies(table: serde_json::Value) -> ArrayStrategies {
+        let table: BTreeMap<String, ArrayMergeStrategy> = serde_json::from_value(table).unwrap();
+        ArrayStrategies::parse(&table).unwrap()
+    }
+
+    #[test]
+    fn array_strategies_replace_append_and_by_index() {
+        let mut doc =
+            json!({"tags": ["a"], "ports": [80, 443], "hosts": [{"n": 1, "x": 1}], "raw": [1]});
+        let table = strategies(json!({
+            "tags": {"strategy": "append"},
+            "ports": {"strategy": "replace"},
+            "hosts": {"strategy": "byIndex"}
+        }));
+        deep_merge(
+            &mut doc,
+            json!({"tags": ["b"], "ports": [8080], "hosts": [{"x": 2}, {"n": 2}], "raw": [2]}),
+            &table,
+        );
+        assert_eq!(
+            doc,
+            json!({
+                "tags": ["a", "b"],
+                "ports": [8080],
+                "hosts": [{"n": 1, "x": 2}, {"n": 2}],
+                "raw": [2]
+            })
+        );
+    }
+
+    #[test]
+    fn array_strategy_union_by_merges_matching_elements() {
+        let mut doc = json!({"plugins": [
+            {"id": "a", "enabled": true, "opts": {"level": 1}},
+            {"id": "b", "enabled": true}
+        ]});
+        let table = strategies(json!({"plugins": {"strategy": "unionBy", "key": "id"}}));
+        deep_merge(
+            &mut doc,
+            json!({"plugins": [
+                {"id": "b", "enabled": false},
+                {"id": "c", "enabled": true},
+                {"name": "no-id"}
+            ]}),
+            &table,
+        );
+        assert_eq!(
+            doc,
+            json!({"plugins": [
+                {"id": "a", "enabled": true, "opts": {"level": 1}},
+                {"id": "b", "enabled": false},
+                {"id": "c", "enabled": true},
+                {"name": "no-id"}
+            ]})
+        );
+    }
+
+    #[test]
+    fn array_strategies_match_nested_paths_with_wildcards() {
+        let mut doc =
+            json!({"servers": [{"name": "a", "tags": ["x"]}, {"name": "b", "tags": ["y"]}]});
+        let table = strategies(json!({
+            "servers": {"strategy": "unionBy", "key": "name"},
+            "servers.*.tags": {"strategy": "append"},
+            "servers.1.tags": {"strategy": "replace"}
+        }));
+        deep_merge(
+            &mut doc,
+            json!({"servers": [{"name": "a", "tags": ["x2"]}, {"name": "b", "tags": ["y2"]}]}),
+            &table,
+        );
+        assert_eq!(
+            doc,
+            json!({"servers": [{"name": "a", "tags": ["x", "x2"]}, {"name": "b", "tags": ["y2"]}]})
+        );
+    }
+
+    #[test]
+    fn array_strategies_reject_invalid_tables() {
+        let table: BTreeMap<String, ArrayMergeStrategy> =
+            serde_json::from_value(json!({"list": {"strategy": "unionBy", "key": ""}})).unwrap();
+        assert!(ArrayStrategies::parse(&table).is_err());
+        let table: BTreeMap<String, ArrayMergeStrategy> =
+            serde_json::from_value(json!({"a..b": {"strategy": "append"}})).unwrap();
+        assert!(ArrayStrategies::parse(&table).is_err());
+        assert!(
+            serde_json::from_value::<ArrayMergeStrategy>(json!({"strategy": "unionBy"})).is_err()
+        );
+    }
+}
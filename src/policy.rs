@@ -0,0 +1,406 @@
+/// Managed policy (`Builder::managed_policy`): admin-enforced values that
+/// the webview cannot change, e.g. `{"telemetry": {"enabled": false}}`.
+///
+/// Every leaf of the policy document (a non-object value or an empty object)
+/// is a locked key.  Loads return the policy values and report the locked
+/// keys; writes either reject changes to locked keys or silently put the
+/// policy values back, depending on [`PolicyEnforcement`].
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::config;
+use crate::dotpath::{self, Segment};
+use crate::error::{Error, Result};
+use crate::models::{NormalizedConfiguratePayload, NormalizedProvider, ProviderPayload};
+use crate::scope;
+use crate::storage;
+
+/// What happens when a write changes a locked key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PolicyEnforcement {
+    /// Write the policy value instead, without reporting an error.
+    #[default]
+    Reapply,
+    /// Fail the write with error kind `policy`.
+    Reject,
+}
+
+#[derive(Debug, Clone)]
+enum PolicySource {
+    File {
+        path: PathBuf,
+        provider: ProviderPayload,
+    },
+    Value(Value),
+}
+
+/// Admin-enforced config values, set with
+/// [`Builder::managed_policy`](crate::Builder::managed_policy).
+#[derive(Debug, Clone)]
+pub struct ManagedPolicy {
+    source: PolicySource,
+    enforcement: PolicyEnforcement,
+    file_names: Vec<String>,
+}
+
+impl ManagedPolicy {
+    /// Policy read from a file at an absolute path, typically a system
+    /// location only administrators can write (e.g. `/etc/myapp/policy.json`).
+    ///
+    /// The file is read on every operation, so policy changes apply without a
+    /// restart.  A missing file means no keys are locked; an unreadable or
+    /// malformed file fails the operation.
+    pub fn file(path: impl Into<PathBuf>, provider: ProviderPayload) -> Self {
+        Self::new(PolicySource::File {
+            path: path.into(),
+            provider,
+        })
+    }
+
+    /// Policy provided by the application.
+    pub fn value(value: Value) -> Self {
+        Self::new(PolicySource::Value(value))
+    }
+
+    fn new(source: PolicySource) -> Self {
+        Self {
+            source,
+            enforcement: PolicyEnforcement::default(),
+            file_names: Vec::new(),
+        }
+    }
+
+    /// Sets how writes to locked keys are handled (default: [`PolicyEnforcement::Reapply`]).
+    pub fn enforcement(mut self, enforcement: PolicyEnforcement) -> Self {
+        self.enforcement = enforcement;
+        self
+    }
+
+    /// Applies the policy to configs whose `fileName` matches this glob (`*`
+    /// any sequence, `?` one character).  May be called repeatedly; at least
+    /// one file name is required, so locked keys never spread into unrelated
+    /// configs such as `.env` files or other layers.
+    pub fn for_file(mut self, file_name: impl Into<String>) -> Self {
+        self.file_names.push(file_name.into());
+        self
+    }
+}
+
+/// Validated policy stored in Tauri state.
+pub(crate) struct PolicyState {
+    source: ResolvedSource,
+    enforcement: PolicyEnforcement,
+    file_names: Vec<String>,
+}
+
+enum ResolvedSource {
+    File {
+        path: PathBuf,
+        provider: NormalizedProvider,
+    },
+    Value(Value),
+}
+
+impl PolicyState {
+    pub(crate) fn new(policy: &ManagedPolicy) -> Result<Self> {
+        let source = match &policy.source {
+            PolicySource::File { path, provider } => {
+                if !path.is_absolute() {
+                    return Err(Error::InvalidPayload(format!(
+                        "managed policy path '{}' must be absolute",
+                        path.display()
+                    )));
+                }
+                ResolvedSource::File {
+                    path: path.clone(),
+                    provider: provider.clone().normalize()?,
+                }
+            }
+            PolicySource::Value(value) => {
+                if !value.is_object() {
+                    return Err(Error::InvalidPayload(
+                        "managed policy must be a JSON object".to_string(),
+                    ));
+                }
+                ResolvedSource::Value(value.clone())
+            }
+        };
+        if policy.file_names.is_empty() {
+            return Err(Error::InvalidPayload(
+                "managed policy must name the configs it applies to with for_file".to_string(),
+            ));
+        }
+        Ok(Self {
+            source,
+            enforcement: policy.enforcement,
+            file_names: policy.file_names.clone(),
+        })
+    }
+
+    fn applies_to(&self, file_name: &str) -> bool {
+        self.file_names
+            .iter()
+            .any(|pattern| scope::glob_match(pattern, file_name))
+    }
+
+    fn read<R: Runtime>(&self, app: &AppHandle<R>) -> Result<Option<Policy>> {
+        let value = match &self.source {
+            ResolvedSource::Value(value) => value.clone(),
+            ResolvedSource::File { path, provider } => {
+                let backend = storage::file_backend_for(
+                    provider,
+                    false,
+                    storage::read_only_registry(),
+                    config::max_read_bytes(app),
+                )?;
+                match backend.read(path) {
+                    Ok(value) => value,
+                    Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                        return Ok(None)
+                    }
+                    Err(e) => {
+                        return Err(Error::Policy(format!(
+                            "failed to read managed policy '{}': {}",
+                            path.display(),
+                            e
+                        )))
+                    }
+                }
+            }
+        };
+        Ok(Some(Policy::new(value)?))
+    }
+}
+
+/// Returns the policy that applies to `payload`, if any.
+pub(crate) fn for_payload<R: Runtime>(
+    app: &AppHandle<R>,
+    payload: &NormalizedConfiguratePayload,
+) -> Result<Option<Policy>> {
+    let Some(state) = app.try_state::<PolicyState>() else {
+        return Ok(None);
+    };
+    if !state.applies_to(&payload.file_name) {
+        return Ok(None);
+    }
+    let Some(mut policy) = state.read(app)? else {
+        return Ok(None);
+    };
+    policy.enforcement = state.enforcement;
+    Ok(Some(policy))
+}
+
+/// A policy document read for one operation.
+pub(crate) struct Policy {
+    /// Locked leaves as `(path, value)`, in document order.
+    leaves: Vec<(Vec<String>, Value)>,
+    enforcement: PolicyEnforcement,
+}
+
+impl Policy {
+    pub(crate) fn new(value: Value) -> Result<Self> {
+        if !value.is_object() {
+            return Err(Error::Policy(
+                "managed policy must be a JSON object".to_string(),
+            ));
+        }
+        let mut leaves = Vec::new();
+        collect_leaves(&value, &mut Vec::new(), &mut leaves);
+        Ok(Self {
+            leaves,
+            enforcement: PolicyEnforcement::default(),
+        })
+    }
+
+    /// Dotpaths of the locked keys.
+    pub(crate) fn locked_keys(&self) -> Vec<String> {
+        self.leaves
+            .iter()
+            .map(|(path, _)| format_path(path))
+            .collect()
+    }
+
+    /// Writes every policy value into `data`, creating missing objects.
+    pub(crate) fn apply(&self, data: &mut Value) -> Result<()> {
+        for (path, value) in &self.leaves {
+            set(data, path, value.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Enforces the policy on `after`, the data about to be written.
+    /// `before` is the currently stored data.  In reject mode, a locked key
+    /// that the write changes to a value other than the policy value is an
+    /// error; otherwise (and for keys the write removes) the policy value is
+    /// put back.
+    pub(crate) fn enforce(&self, before: Option<&Value>, after: &mut Value) -> Result<()> {
+        if self.enforcement == PolicyEnforcement::Reject {
+            for (path, value) in &self.leaves {
+                let Some(new) = lookup(after, path) else {
+                    continue;
+                };
+                let old = before.and_then(|before| lookup(before, path));
+                if new != value && Some(new) != old {
+                    return Err(Error::Policy(format!(
+                        "'{}' is locked by the managed policy",
+                        format_path(path)
+                    )));
+                }
+            }
+        }
+        self.apply(after)
+    }
+}
+
+fn collect_leaves(node: &Value, path: &mut Vec<String>, out: &mut Vec<(Vec<String>, Value)>) {
+    match node {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                path.push(key.clone());
+                collect_leaves(child, path, out);
+                path.pop();
+            }
+        }
+        _ if !path.is_empty() => out.push((path.clone(), node.clone())),
+        _ => {}
+    }
+}
+
+fn format_path(path: &[String]) -> String {
+    let segments: Vec<Segment> = path.iter().cloned().map(Segment::Key).collect();
+    dotpath::format(&segments)
+}
+
+fn lookup<'a>(root: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter()
+        .try_fold(root, |node, key| node.as_object()?.get(key))
+}
+
+/// Sets `path` to `value`, replacing non-object intermediates with objects.
+fn set(root: &mut Value, path: &[String], value: Value) -> Result<()> {
+    let Some((last, parents)) = path.split_last() else {
+        return Ok(());
+    };
+    let mut node = root;
+    for key in parents {
+        node = object_mut(node, path)?
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    object_mut(node, path)?.insert(last.clone(), value);
+    Ok(())
+}
+
+/// Replaces a non-object `node` with an empty object and returns its map.
+fn object_mut<'a>(node: &'a mut Value, path: &[String]) -> Result<&'a mut Map<String, Value>> {
+    if !node.is_object() {
+        *node = Value::Object(Map::new());
+    }
+    node.as_object_mut().ok_or_else(|| {
+        Error::Policy(format!(
+            "cannot write '{}': parent is not an object",
+            format_path(path)
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(value: Value, enforcement: PolicyEnforcement) -> Policy {
+        let mut policy = Policy::new(value).unwrap();
+        policy.enforcement = enforcement;
+        policy
+    }
+
+    #[test]
+    fn locked_keys_are_policy_leaves() {
+        let policy = policy(
+            json!({"telemetry": {"enabled": false}, "update": {"channel": "stable"}, "a.b": 1}),
+            PolicyEnforcement::Reapply,
+        );
+        let mut keys = policy.locked_keys();
+        keys.sort();
+        assert_eq!(keys, [r#"["a.b"]"#, "telemetry.enabled", "update.channel"]);
+    }
+
+    #[test]
+    fn reapply_overwrites_locked_keys_and_restores_removed_ones() {
+        let policy = policy(
+            json!({"telemetry": {"enabled": false}, "channel": "stable"}),
+            PolicyEnforcement::Reapply,
+        );
+        let mut data = json!({"telemetry": {"enabled": true, "level": 2}, "theme": "dark"});
+        policy.enforce(None, &mut data).unwrap();
+        assert_eq!(
+            data,
+            json!({
+                "telemetry": {"enabled": false, "level": 2},
+                "theme": "dark",
+                "channel": "stable"
+            })
+        );
+    }
+
+    #[test]
+    fn reject_fails_only_when_a_write_changes_a_locked_key() {
+        let policy = policy(
+            json!({"telemetry": {"enabled": false}}),
+            PolicyEnforcement::Reject,
+        );
+        let before = json!({"telemetry": {"enabled": false}});
+
+        let mut changed = json!({"telemetry": {"enabled": true}});
+        let err = policy.enforce(Some(&before), &mut changed).unwrap_err();
+        assert!(matches!(err, Error::Policy(_)));
+        assert!(err.to_string().contains("telemetry.enabled"));
+
+        // Unchanged stale values and removed keys are put back silently.
+        let stale = json!({"telemetry": {"enabled": true}});
+        let mut unchanged = stale.clone();
+        policy.enforce(Some(&stale), &mut unchanged).unwrap();
+        assert_eq!(unchanged, json!({"telemetry": {"enabled": false}}));
+
+        let mut removed = json!({"theme": "dark"});
+        policy.enforce(Some(&before), &mut removed).unwrap();
+        assert_eq!(
+            removed,
+            json!({"theme": "dark", "telemetry": {"enabled": false}})
+        );
+    }
+
+    #[test]
+    fn policy_must_be_an_object() {
+        assert!(Policy::new(json!([1])).is_err());
+        assert!(PolicyState::new(&ManagedPolicy::value(json!("x")).for_file("a.json")).is_err());
+        assert!(PolicyState::new(
+            &ManagedPolicy::file(
+                "policy.json",
+                ProviderPayload::new(crate::models::ProviderKind::Json)
+            )
+            .for_file("a.json")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn policy_applies_only_to_named_files() {
+        let value = json!({"telemetry": false});
+        assert!(PolicyState::new(&ManagedPolicy::value(value.clone())).is_err());
+
+        let state = PolicyState::new(
+            &ManagedPolicy::value(value)
+                .for_file("settings.json")
+                .for_file("profile-*.toml"),
+        )
+        .unwrap();
+        assert!(state.applies_to("settings.json"));
+        assert!(state.applies_to("profile-work.toml"));
+        assert!(!state.applies_to("secrets.env"));
+        assert!(!state.applies_to("settings.json.bak"));
+    }
+}
@@ -0,0 +1,277 @@
+/// Apple property lists (XML and binary).
+///
+/// Property list values map to JSON as follows:
+///
+/// - dictionaries, arrays, strings and booleans map directly;
+/// - integers become JSON integers (`i64` / `u64` range) and reals become
+///   JSON floats.  A whole number written over a stored `<real>` stays a
+///   real, since the webview sends `1.0` back as `1`;
+/// - dates become `{"$date": "2024-01-31T12:00:00Z"}` (UTC, whole seconds);
+/// - data becomes `{"$bytes": "<base64>"}`, as in the Binary provider's CBOR
+///   and MessagePack encodings;
+/// - `UID` values (keyed archives) and non-finite reals are rejected.
+///
+/// On write, `null` dictionary values are omitted and `null` array elements
+/// are rejected, since property lists have no null.
+use std::io::Cursor;
+
+use serde_json::{Map, Number, Value};
+
+use crate::encoding;
+use crate::error::{Error, Result};
+
+/// Object key marking a date in the JSON view of a property list.
+pub(crate) const DATE_KEY: &str = "$date";
+
+/// Leading bytes of a binary property list.
+const BINARY_MAGIC: &[u8] = b"bplist";
+
+/// Whether `bytes` hold a binary (rather than XML) property list.
+pub(crate) fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(BINARY_MAGIC)
+}
+
+/// Parses an XML or binary property list into a JSON value.
+pub(crate) fn parse(bytes: &[u8]) -> Result<Value> {
+    to_json(parse_document(bytes)?)
+}
+
+/// Parses `bytes` into a `plist::Value`, for use as the `existing` document.
+pub(crate) fn parse_document(bytes: &[u8]) -> Result<plist::Value> {
+    plist::Value::from_reader(Cursor::new(bytes))
+        .map_err(|e| Error::Storage(format!("invalid property list: {}", e)))
+}
+
+/// Renders `value` as an XML property list, or a binary one if `binary`.
+/// Whole numbers at paths where `existing` holds a real are written as reals.
+pub(crate) fn render(
+    value: &Value,
+    existing: Option<&plist::Value>,
+    binary: bool,
+) -> Result<Vec<u8>> {
+    let value = from_json(value, existing)?;
+    let mut out = Vec::new();
+    let written = match binary {
+        true => value.to_writer_binary(&mut out),
+        false => value.to_writer_xml(&mut out),
+    };
+    written.map_err(|e| Error::Storage(format!("failed to write property list: {}", e)))?;
+    if !binary && !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    Ok(out)
+}
+
+fn to_json(value: plist::Value) -> Result<Value> {
+    Ok(match value {
+        plist::Value::Dictionary(dict) => {
+            let mut map = Map::new();
+            for (key, value) in dict {
+                map.insert(key, to_json(value)?);
+            }
+            Value::Object(map)
+        }
+        plist::Value::Array(items) => {
+            Value::Array(items.into_iter().map(to_json).collect::<Result<_>>()?)
+        }
+        plist::Value::Boolean(b) => Value::Bool(b),
+        plist::Value::String(s) => Value::String(s),
+        plist::Value::Integer(i) => match (i.as_signed(), i.as_unsigned()) {
+            (Some(i), _) => Value::from(i),
+            (_, Some(u)) => Value::from(u),
+            _ => {
+                return Err(Error::Storage(format!(
+                    "property list integer {:?} is out of range",
+                    i
+                )))
+            }
+        },
+        plist::Value::Real(f) => Number::from_f64(f)
+            .map(Value::Number)
+            .ok_or_else(|| Error::Storage(format!("{} cannot be represented in config data", f)))?,
+        plist::Value::Date(date) => {
+            let mut map = Map::new();
+            map.insert(DATE_KEY.to_string(), Value::String(date.to_xml_format()));
+            Value::Object(map)
+        }
+        plist::Value::Data(bytes) => encoding::bytes_object(&bytes),
+        _ => {
+            return Err(Error::Storage(
+                "property list UID values are not supported".to_string(),
+            ))
+        }
+    })
+}
+
+fn from_json(value: &Value, existing: Option<&plist::Value>) -> Result<plist::Value> {
+    Ok(match value {
+        Value::Null => {
+            return Err(Error::Storage(
+                "property lists do not support null values in this position".to_string(),
+            ))
+        }
+        Value::Bool(b) => plist::Value::Boolean(*b),
+        Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) if matches!(existing, Some(plist::Value::Real(_))) => {
+                plist::Value::Real(i as f64)
+            }
+            (Some(i), _) => plist::Value::Integer(i.into()),
+            (_, Some(u)) => plist::Value::Integer(u.into()),
+            _ => plist::Value::Real(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(s) => plist::Value::String(s.clone()),
+        Value::Array(items) => plist::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| match item {
+                    Value::Null => Err(Error::Storage(
+                        "property lists do not support null values inside arrays".to_string(),
+                    )),
+                    item => {
+                        let hint = match existing {
+                            Some(plist::Value::Array(items)) => items.get(index),
+                            _ => None,
+                        };
+                        from_json(item, hint)
+                    }
+                })
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(map) => {
+            if let Some(date) = date_string(map) {
+                let date = plist::Date::from_xml_format(date).map_err(|e| {
+                    Error::InvalidPayload(format!("invalid date in '{}': {}", DATE_KEY, e))
+                })?;
+                return Ok(plist::Value::Date(date));
+            }
+            if let Some(bytes) = encoding::byte_string(map)? {
+                return Ok(plist::Value::Data(bytes));
+            }
+            let mut dict = plist::Dictionary::new();
+            for (key, value) in map {
+                if !value.is_null() {
+                    let hint = match existing {
+                        Some(plist::Value::Dictionary(dict)) => dict.get(key),
+                        _ => None,
+                    };
+                    dict.insert(key.clone(), from_json(value, hint)?);
+                }
+            }
+            plist::Value::Dictionary(dict)
+        }
+    })
+}
+
+/// The date text of a `{"$date": "…"}` object, if `map` is one.
+fn date_string(map: &Map<String, Value>) -> Option<&str> {
+    match (map.len(), map.get(DATE_KEY)) {
+        (1, Some(Value::String(date))) => Some(date),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>Name</key>
+	<string>My App</string>
+	<key>Count</key>
+	<integer>3</integer>
+	<key>Ratio</key>
+	<real>1</real>
+	<key>Enabled</key>
+	<true/>
+	<key>Created</key>
+	<date>2024-01-31T12:00:00Z</date>
+	<key>Icon</key>
+	<data>AAEC</data>
+	<key>Recent</key>
+	<array>
+		<string>a.txt</string>
+	</array>
+</dict>
+</plist>
+"#;
+
+    #[test]
+    fn maps_every_value_type() {
+        let value = parse(PLIST.as_bytes()).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "Name": "My App",
+                "Count": 3,
+                "Ratio": 1.0,
+                "Enabled": true,
+                "Created": {"$date": "2024-01-31T12:00:00Z"},
+                "Icon": {"$bytes": "AAEC"},
+                "Recent": ["a.txt"],
+            })
+        );
+        assert!(value["Ratio"].is_f64());
+    }
+
+    #[test]
+    fn xml_and_binary_renders_read_back_unchanged() {
+        let mut value = parse(PLIST.as_bytes()).unwrap();
+        value["Big"] = json!(u64::MAX);
+        for binary in [false, true] {
+            let bytes = render(&value, None, binary).unwrap();
+            assert_eq!(is_binary(&bytes), binary);
+            assert_eq!(parse(&bytes).unwrap(), value);
+        }
+        let text = String::from_utf8(render(&value, None, false).unwrap()).unwrap();
+        assert!(
+            text.contains("<date>2024-01-31T12:00:00Z</date>"),
+            "{}",
+            text
+        );
+    }
+
+    #[test]
+    fn rejects_nulls_in_arrays_and_invalid_dates() {
+        let omitted = render(&json!({"a": null, "b": 1}), None, false).unwrap();
+        assert_eq!(parse(&omitted).unwrap(), json!({"b": 1}));
+        assert!(render(&json!({"a": [null]}), None, false).is_err());
+        assert!(render(&json!({"a": {"$date": "yesterday"}}), None, false).is_err());
+    }
+
+    #[test]
+    fn whole_numbers_stay_reals_where_the_document_has_reals() {
+        let existing = parse_document(PLIST.as_bytes()).unwrap();
+        // The webview sends `1.0` back as `1`.
+        let saved = json!({"Ratio": 1, "Count": 4, "Sizes": [2, 3]});
+        let mut with_reals = existing.clone();
+        with_reals.as_dictionary_mut().unwrap().insert(
+            "Sizes".to_string(),
+            plist::Value::Array(vec![
+                plist::Value::Real(1.5),
+                plist::Value::Integer(1i64.into()),
+            ]),
+        );
+
+        let bytes = render(&saved, Some(&with_reals), false).unwrap();
+        let written = parse_document(&bytes).unwrap();
+        let written = written.as_dictionary().unwrap();
+        assert_eq!(written.get("Ratio"), Some(&plist::Value::Real(1.0)));
+        assert_eq!(
+            written.get("Count"),
+            Some(&plist::Value::Integer(4i64.into()))
+        );
+        assert_eq!(
+            written.get("Sizes"),
+            Some(&plist::Value::Array(vec![
+                plist::Value::Real(2.0),
+                plist::Value::Integer(3i64.into())
+            ]))
+        );
+        assert!(parse(&bytes).unwrap()["Ratio"].is_f64());
+    }
+}
@@ -0,0 +1,448 @@
+/// Capability scopes: restrict which configs a window may read, write or
+/// unlock, in the style of Tauri's fs scope.
+///
+/// Scope entries are declared in capability files on a command permission
+/// (`{ "identifier": "configurate:allow-save", "allow": [...] }`) or on the
+/// plugin's global scope.  An entry matches a config by base directory,
+/// `fileName` / relative-path glob and provider, and grants (or, in `deny`,
+/// revokes) the listed `access` rights.  Deny entries win.  When no entry applies to the
+/// invoking command, access is not restricted.
+use std::sync::Arc;
+
+use serde::{Deserialize, Deserializer};
+use tauri::ipc::{CommandArg, CommandItem, CommandScope, GlobalScope, InvokeError};
+use tauri::path::BaseDirectory;
+use tauri::Runtime;
+
+use crate::error::{Error, Result};
+use crate::models::NormalizedConfiguratePayload;
+
+/// Right required by an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Access {
+    /// Load, export, watch, list and read single values.
+    Read,
+    /// Create, save, patch, import, reset, delete and value writes.
+    Write,
+    /// Any operation that reads or writes keyring secrets.
+    Keyring,
+}
+
+impl Access {
+    fn as_str(self) -> &'static str {
+        match self {
+            Access::Read => "read",
+            Access::Write => "write",
+            Access::Keyring => "keyring",
+        }
+    }
+}
+
+fn all_access() -> Vec<Access> {
+    vec![Access::Read, Access::Write, Access::Keyring]
+}
+
+/// One `allow` / `deny` scope entry.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScopeEntry {
+    /// Glob for the config file name (`*` any sequence, `?` one character).
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// Base directories as Tauri path variables (`$APPCONFIG`, `$HOME`, ...);
+    /// any when omitted.
+    #[serde(default, deserialize_with = "deserialize_base_dirs")]
+    pub base_dir: Option<Vec<BaseDirectory>>,
+    /// Glob for the config's directory relative to its base directory:
+    /// `dirName` and `currentPath` joined with `/` (`""` for the base
+    /// directory itself).  `*` also matches `/`.
+    #[serde(default)]
+    pub dir_name: Option<String>,
+    /// Provider kinds (`json`, `yml`, `toml`, `ini`, `dotenv`, `xml`,
+    /// `plist`, `binary`); any when omitted.
+    #[serde(default)]
+    pub providers: Option<Vec<String>>,
+    /// Rights granted or revoked; all rights when omitted.
+    #[serde(default = "all_access")]
+    pub access: Vec<Access>,
+}
+
+impl ScopeEntry {
+    fn matches(&self, target: &Target<'_>, access: Access) -> bool {
+        self.access.contains(&access)
+            && self
+                .base_dir
+                .as_ref()
+                .is_none_or(|dirs| dirs.iter().any(|dir| *dir as u16 == target.base_dir as u16))
+            && self
+                .file_name
+                .as_deref()
+                .is_none_or(|pattern| glob_match(pattern, target.file_name))
+            && self
+                .dir_name
+                .as_deref()
+                .is_none_or(|pattern| glob_match(pattern, &target.dir_path))
+            && self
+                .providers
+                .as_ref()
+                .is_none_or(|kinds| kinds.iter().any(|kind| kind == target.provider))
+    }
+}
+
+struct Target<'a> {
+    base_dir: BaseDirectory,
+    file_name: &'a str,
+    /// Directory relative to the base directory, `/`-separated.
+    dir_path: String,
+    provider: &'a str,
+}
+
+impl<'a> Target<'a> {
+    fn new(payload: &'a NormalizedConfiguratePayload, file_name: &'a str) -> Self {
+        let dir_path = [payload.dir_name.as_deref(), payload.current_path.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("/")
+            .replace('\\', "/");
+        Self {
+            base_dir: payload.base_dir,
+            file_name,
+            dir_path,
+            provider: crate::commands::provider_kind(&payload.provider),
+        }
+    }
+}
+
+fn deserialize_base_dirs<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Option<Vec<BaseDirectory>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(variables) = Option::<Vec<String>>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    variables
+        .iter()
+        .map(|variable| {
+            BaseDirectory::from_variable(variable).ok_or_else(|| {
+                serde::de::Error::custom(format!(
+                    "unknown base directory '{variable}' (expected a path variable \
+                     such as $APPCONFIG)"
+                ))
+            })
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// The scope entries that apply to one command invocation (command-specific
+/// and global entries combined).
+#[derive(Debug, Clone, Default)]
+pub struct Scope {
+    allow: Vec<Arc<ScopeEntry>>,
+    deny: Vec<Arc<ScopeEntry>>,
+}
+
+impl Scope {
+    fn is_unrestricted(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    fn permits(&self, target: &Target<'_>, access: Access) -> bool {
+        if self.deny.iter().any(|entry| entry.matches(target, access)) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(|entry| entry.matches(target, access))
+    }
+
+    /// Checks `access` (plus [`Access::Keyring`] when the payload carries
+    /// keyring options) on the config addressed by `payload`.
+    pub(crate) fn check(
+        &self,
+        payload: &NormalizedConfiguratePayload,
+        access: Access,
+    ) -> Result<()> {
+        if self.is_unrestricted() {
+            return Ok(());
+        }
+        let target = Target::new(payload, &payload.file_name);
+        let mut required = vec![access];
+        if payload.keyring_options.is_some() {
+            required.push(Access::Keyring);
+        }
+        for access in required {
+            if !self.permits(&target, access) {
+                return Err(Error::Forbidden(format!(
+                    "{} access to '{}' is not allowed for this window",
+                    access.as_str(),
+                    payload.file_name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a listed file may be read; used to filter `list_configs`.
+    pub(crate) fn permits_listing(
+        &self,
+        payload: &NormalizedConfiguratePayload,
+        file_name: &str,
+    ) -> bool {
+        self.is_unrestricted() || self.permits(&Target::new(payload, file_name), Access::Read)
+    }
+}
+
+/// A scope attached to a payload together with the right the operation
+/// needs; checked by `resolve_file_path` for every file the payload touches.
+#[derive(Debug, Clone)]
+pub(crate) struct ScopeGrant {
+    scope: Scope,
+    access: Access,
+}
+
+impl ScopeGrant {
+    pub(crate) fn check(&self, payload: &NormalizedConfiguratePayload) -> Result<()> {
+        self.scope.check(payload, self.access)
+    }
+}
+
+impl NormalizedConfiguratePayload {
+    /// Restricts this payload to what `scope` permits for `access`.
+    /// A `seedFrom` template is restricted to [`Access::Read`].
+    pub(crate) fn scoped(mut self, scope: &Scope, access: Access) -> Self {
+        self.seed_from = self
+            .seed_from
+            .map(|template| Box::new(template.scoped(scope, Access::Read)));
+        self.scope = Some(ScopeGrant {
+            scope: scope.clone(),
+            access,
+        });
+        self
+    }
+}
+
+impl<'a, R: Runtime> CommandArg<'a, R> for Scope {
+    fn from_command(command: CommandItem<'a, R>) -> std::result::Result<Self, InvokeError> {
+        let item = || CommandItem {
+            plugin: command.plugin,
+            name: command.name,
+            key: command.key,
+            message: command.message,
+            acl: command.acl,
+        };
+        let command_scope = CommandScope::<ScopeEntry>::from_command(item())?;
+        let global_scope = GlobalScope::<ScopeEntry>::from_command(item())?;
+        Ok(Self {
+            allow: command_scope
+                .allows()
+                .iter()
+                .chain(global_scope.allows())
+                .cloned()
+                .collect(),
+            deny: command_scope
+                .denies()
+                .iter()
+                .chain(global_scope.denies())
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+/// Matches `text` against a glob where `*` is any sequence and `?` any
+/// single character.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ConfiguratePayload, ProviderKind, ProviderPayload};
+    use serde_json::json;
+    use tauri::path::BaseDirectory;
+
+    fn entry(value: serde_json::Value) -> Arc<ScopeEntry> {
+        Arc::new(serde_json::from_value(value).unwrap())
+    }
+
+    fn payload(file_name: &str, dir_name: Option<&str>) -> NormalizedConfiguratePayload {
+        let mut payload = ConfiguratePayload::new(
+            file_name,
+            BaseDirectory::AppConfig,
+            ProviderPayload::new(ProviderKind::Json),
+        );
+        payload.options = dir_name.map(|dir| crate::models::PathOptions {
+            dir_name: Some(dir.to_string()),
+            current_path: None,
+        });
+        payload.normalize().unwrap()
+    }
+
+    #[test]
+    fn glob_matches_wildcards() {
+        assert!(glob_match("*.json", "app.json"));
+        assert!(glob_match("app-?.json", "app-1.json"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXXbYc"));
+        assert!(!glob_match("*.json", "app.yml"));
+        assert!(!glob_match("app-?.json", "app-10.json"));
+    }
+
+    #[test]
+    fn empty_scope_is_unrestricted() {
+        let scope = Scope::default();
+        scope
+            .check(&payload("app.json", None), Access::Write)
+            .unwrap();
+    }
+
+    #[test]
+    fn allow_entries_grant_matching_files_and_rights() {
+        let scope = Scope {
+            allow: vec![
+                entry(json!({"fileName": "settings*.json", "access": ["read", "write"]})),
+                entry(json!({"dirName": "shared", "providers": ["json"], "access": ["read"]})),
+            ],
+            deny: Vec::new(),
+        };
+        scope
+            .check(&payload("settings.json", None), Access::Write)
+            .unwrap();
+        scope
+            .check(&payload("other.json", Some("shared")), Access::Read)
+            .unwrap();
+
+        let err = scope
+            .check(&payload("other.json", Some("shared")), Access::Write)
+            .unwrap_err();
+        assert!(matches!(err, Error::Forbidden(_)));
+        assert!(scope
+            .check(&payload("secrets.json", None), Access::Read)
+            .is_err());
+
+        let mut keyring = payload("settings.json", None);
+        keyring.keyring_options = Some(crate::models::KeyringOptions {
+            service: "app".to_string(),
+            account: "default".to_string(),
+        });
+        assert!(scope.check(&keyring, Access::Read).is_err());
+    }
+
+    #[test]
+    fn unlock_is_checked_against_its_target_config() {
+        let scope = Scope {
+            allow: vec![entry(json!({"fileName": "a.json"}))],
+            deny: Vec::new(),
+        };
+        let target = |file_name: &str| {
+            ConfiguratePayload::new(
+                file_name,
+                BaseDirectory::AppConfig,
+                ProviderPayload::new(ProviderKind::Json),
+            )
+        };
+        crate::commands::authorize_unlock(target("a.json"), &scope).unwrap();
+        let err = crate::commands::authorize_unlock(target("b.json"), &scope).unwrap_err();
+        assert!(matches!(err, Error::Forbidden(_)));
+
+        // A deny entry limited to one file revokes unlocking that file only.
+        let scope = Scope {
+            allow: Vec::new(),
+            deny: vec![entry(json!({"fileName": "b.json", "access": ["keyring"]}))],
+        };
+        crate::commands::authorize_unlock(target("a.json"), &scope).unwrap();
+        assert!(crate::commands::authorize_unlock(target("b.json"), &scope).is_err());
+    }
+
+    #[test]
+    fn deny_entries_win_over_allow_entries() {
+        let scope = Scope {
+            allow: vec![entry(json!({}))],
+            deny: vec![entry(
+                json!({"fileName": "admin.json", "access": ["write"]}),
+            )],
+        };
+        scope
+            .check(&payload("admin.json", None), Access::Read)
+            .unwrap();
+        assert!(scope
+            .check(&payload("admin.json", None), Access::Write)
+            .is_err());
+        assert!(scope.permits_listing(&payload("x.json", None), "admin.json"));
+    }
+
+    #[test]
+    fn current_path_is_part_of_the_matched_directory() {
+        let scope = Scope {
+            allow: vec![entry(json!({}))],
+            deny: vec![entry(json!({"dirName": "secrets"}))],
+        };
+        let mut nested = payload("app.json", None);
+        nested.current_path = Some("secrets".to_string());
+        assert!(scope.check(&nested, Access::Read).is_err());
+        assert!(!scope.permits_listing(&nested, "app.json"));
+
+        let mut split = payload("app.json", Some("shared"));
+        split.current_path = Some("inner".to_string());
+        let scope = Scope {
+            allow: vec![entry(json!({"dirName": "shared/inner"}))],
+            deny: Vec::new(),
+        };
+        scope.check(&split, Access::Read).unwrap();
+    }
+
+    #[test]
+    fn entries_can_be_limited_to_base_directories_and_the_root_path() {
+        let scope = Scope {
+            allow: vec![entry(json!({
+                "fileName": "settings.json",
+                "baseDir": ["$APPCONFIG"],
+                "dirName": "",
+            }))],
+            deny: Vec::new(),
+        };
+        scope
+            .check(&payload("settings.json", None), Access::Write)
+            .unwrap();
+
+        let mut home = payload("settings.json", None);
+        home.base_dir = BaseDirectory::Home;
+        assert!(scope.check(&home, Access::Write).is_err());
+
+        let mut nested = payload("settings.json", None);
+        nested.current_path = Some("other".to_string());
+        assert!(scope.check(&nested, Access::Write).is_err());
+
+        let err =
+            serde_json::from_value::<ScopeEntry>(json!({"baseDir": ["AppConfig"]})).unwrap_err();
+        assert!(err.to_string().contains("unknown base directory"));
+    }
+}
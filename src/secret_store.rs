@@ -0,0 +1,779 @@
+/// Pluggable storage for secrets referenced by `keyring(...)` schema fields.
+///
+/// Secrets are addressed by `(service, user)` where `user` is the
+/// `{account}/{id}` string built by `keyring_store`.  The plugin ships three
+/// implementations:
+///
+/// - [`OsKeyringStore`] — the OS keyring via the `keyring` crate (default).
+/// - [`MemorySecretStore`] — process-local map, intended for tests and CI.
+/// - [`EncryptedFileSecretStore`] — a single XChaCha20-Poly1305 vault file
+///   whose key is derived with Argon2id (same format as the Binary provider).
+///
+/// [`FallbackSecretStore`] chains two stores so that the vault takes writes
+/// only while the OS keyring reports itself unavailable.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use crate::config::DEFAULT_MAX_READ_BYTES;
+use crate::error::{Error, Result};
+use crate::storage::{self, StorageBackend};
+
+/// Backend that stores keyring-field secrets.
+///
+/// Implementations must be thread-safe: commands may call them concurrently.
+pub trait SecretStore: Send + Sync {
+    /// Returns the secret for `service`/`user`, or `Ok(None)` when it does not exist.
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>>;
+
+    /// Stores `secret`, overwriting any existing value.
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<()>;
+
+    /// Removes the secret.  Returns `Ok(())` when the entry does not exist.
+    fn delete(&self, service: &str, user: &str) -> Result<()>;
+
+    /// Lists the user strings stored under `service`.
+    fn list(&self, service: &str) -> Result<Vec<String>>;
+
+    /// Whether [`SecretStore::list`] can enumerate entries at all.  Stores
+    /// that cannot return an error from `list`.
+    fn supports_list(&self) -> bool {
+        true
+    }
+
+    /// Largest secret this store accepts, measured by [`SecretStore::secret_size`].
+    /// `None` means unlimited.
+    fn max_secret_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Size of `secret` as counted against [`SecretStore::max_secret_size`].
+    /// Defaults to the UTF-8 byte length.
+    fn secret_size(&self, secret: &str) -> usize {
+        secret.len()
+    }
+}
+
+/// Windows Credential Manager caps the credential blob at 2560 bytes and the
+/// `keyring` crate stores passwords there as UTF-16.
+#[cfg(windows)]
+const WINDOWS_CREDENTIAL_BLOB_MAX: usize = 2560;
+
+/// Shared handle stored in Tauri state and in the plugin [`Builder`](crate::Builder).
+#[derive(Clone)]
+pub(crate) struct SharedSecretStore(pub(crate) Arc<dyn SecretStore>);
+
+impl std::fmt::Debug for SharedSecretStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedSecretStore(..)")
+    }
+}
+
+/// Secret store backed by the OS keyring (Keychain, Credential Manager,
+/// Secret Service).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsKeyringStore;
+
+impl SecretStore for OsKeyringStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+        let entry = keyring::Entry::new(service, user)?;
+        match entry.get_password() {
+            Ok(pwd) => Ok(Some(pwd)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+        let entry = keyring::Entry::new(service, user)?;
+        entry.set_password(secret)?;
+        Ok(())
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        let entry = keyring::Entry::new(service, user)?;
+        match entry.delete_credential() {
+            Ok(_) => Ok(()),
+            Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn list(&self, _service: &str) -> Result<Vec<String>> {
+        Err(Error::Keyring(
+            "the OS keyring does not support listing entries".to_string(),
+        ))
+    }
+
+    fn supports_list(&self) -> bool {
+        false
+    }
+
+    #[cfg(windows)]
+    fn max_secret_size(&self) -> Option<usize> {
+        Some(WINDOWS_CREDENTIAL_BLOB_MAX)
+    }
+
+    #[cfg(windows)]
+    fn secret_size(&self, secret: &str) -> usize {
+        secret.encode_utf16().count() * 2
+    }
+}
+
+/// In-memory secret store.  Contents are lost when the process exits.
+#[derive(Default)]
+pub struct MemorySecretStore {
+    entries: Mutex<BTreeMap<(String, String), Zeroizing<String>>>,
+}
+
+impl MemorySecretStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SecretStore for MemorySecretStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(entries
+            .get(&(service.to_string(), user.to_string()))
+            .map(|secret| secret.to_string()))
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(
+            (service.to_string(), user.to_string()),
+            Zeroizing::new(secret.to_string()),
+        );
+        Ok(())
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.remove(&(service.to_string(), user.to_string()));
+        Ok(())
+    }
+
+    fn list(&self, service: &str) -> Result<Vec<String>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        Ok(entries
+            .keys()
+            .filter(|(s, _)| s == service)
+            .map(|(_, user)| user.clone())
+            .collect())
+    }
+}
+
+/// Secret store persisted in a single encrypted vault file.
+///
+/// The vault is a JSON object `{ service: { user: secret } }` written with
+/// the same XChaCha20-Poly1305 + Argon2id format as
+/// `BinaryProvider({ kdf: "argon2" })`.  Every operation re-reads the vault
+/// so multiple handles to the same file stay consistent within the process.
+pub struct EncryptedFileSecretStore {
+    path: PathBuf,
+    backend: storage::BinaryArgon2Backend,
+    lock: Mutex<()>,
+}
+
+impl EncryptedFileSecretStore {
+    /// Opens (or lazily creates) the vault at `path`, deriving the cipher key
+    /// from `passphrase`.
+    pub fn new(path: impl Into<PathBuf>, passphrase: &str) -> Self {
+        Self {
+            path: path.into(),
+            backend: storage::BinaryArgon2Backend::new(
+                passphrase,
+                false,
+                storage::read_only_registry(),
+                DEFAULT_MAX_READ_BYTES,
+            )
+            // Every vault write follows a read, so the key is already cached.
+            .with_salt_reuse(true),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the vault file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read_vault(&self) -> Result<serde_json::Map<String, Value>> {
+        match self.backend.read(&self.path) {
+            Ok(Value::Object(map)) => Ok(map),
+            Ok(_) => Err(Error::Keyring(format!(
+                "secret vault '{}' is malformed",
+                self.path.display()
+            ))),
+            Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(serde_json::Map::new())
+            }
+            Err(e) => Err(Error::Keyring(format!(
+                "failed to read secret vault '{}': {}",
+                self.path.display(),
+                e
+            ))),
+        }
+    }
+
+    fn write_vault(&self, vault: serde_json::Map<String, Value>) -> Result<()> {
+        self.backend
+            .write(&self.path, &Value::Object(vault))
+            .map_err(|e| {
+                Error::Keyring(format!(
+                    "failed to write secret vault '{}': {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+}
+
+impl SecretStore for EncryptedFileSecretStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let vault = self.read_vault()?;
+        Ok(vault
+            .get(service)
+            .and_then(|users| users.get(user))
+            .and_then(Value::as_str)
+            .map(str::to_string))
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut vault = self.read_vault()?;
+        let users = vault
+            .entry(service.to_string())
+            .or_insert_with(|| Value::Object(serde_json::Map::new()));
+        match users {
+            Value::Object(users) => {
+                users.insert(user.to_string(), Value::String(secret.to_string()));
+            }
+            _ => {
+                return Err(Error::Keyring(format!(
+                    "secret vault '{}' is malformed",
+                    self.path.display()
+                )))
+            }
+        }
+        self.write_vault(vault)
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut vault = self.read_vault()?;
+        let removed = match vault.get_mut(service) {
+            Some(Value::Object(users)) => users.remove(user).is_some(),
+            _ => false,
+        };
+        if !removed {
+            return Ok(());
+        }
+        if vault
+            .get(service)
+            .and_then(Value::as_object)
+            .is_some_and(|users| users.is_empty())
+        {
+            vault.remove(service);
+        }
+        self.write_vault(vault)
+    }
+
+    fn list(&self, service: &str) -> Result<Vec<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let vault = self.read_vault()?;
+        Ok(vault
+            .get(service)
+            .and_then(Value::as_object)
+            .map(|users| users.keys().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+/// Stores secrets in `primary`, switching to `fallback` for any operation
+/// where `primary` fails with [`Error::KeyringUnavailable`] (the OS keyring
+/// reported `PlatformFailure` or `NoStorageAccess`).
+///
+/// Other errors are returned as-is so a working keyring is never silently
+/// bypassed.  Secrets written to `fallback` while `primary` was unavailable
+/// stay reachable once it is back: a `get` that misses in `primary` reads
+/// `fallback` and moves a secret found there into `primary`, `delete`
+/// removes the secret from both stores, and `list` includes `fallback`'s
+/// entries.  When `primary` cannot enumerate entries (the OS keyring),
+/// `list` returns `fallback`'s entries only.
+pub struct FallbackSecretStore {
+    primary: Arc<dyn SecretStore>,
+    fallback: Arc<dyn SecretStore>,
+}
+
+impl FallbackSecretStore {
+    pub fn new(primary: Arc<dyn SecretStore>, fallback: Arc<dyn SecretStore>) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl SecretStore for FallbackSecretStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+        match self.primary.get(service, user) {
+            Err(Error::KeyringUnavailable(_)) => self.fallback.get(service, user),
+            Ok(None) => {
+                let Some(secret) = self.fallback.get(service, user)? else {
+                    return Ok(None);
+                };
+                // The secret stays readable from `fallback` if moving it
+                // fails, so a failed move does not fail the read.
+                if self.primary.set(service, user, &secret).is_ok() {
+                    let _ = self.fallback.delete(service, user);
+                }
+                Ok(Some(secret))
+            }
+            other => other,
+        }
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+        match self.primary.set(service, user, secret) {
+            Err(Error::KeyringUnavailable(_)) => self.fallback.set(service, user, secret),
+            other => other,
+        }
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        match self.primary.delete(service, user) {
+            Ok(()) | Err(Error::KeyringUnavailable(_)) => self.fallback.delete(service, user),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn list(&self, service: &str) -> Result<Vec<String>> {
+        if !self.primary.supports_list() {
+            return self.fallback.list(service);
+        }
+        let mut users = match self.primary.list(service) {
+            Ok(users) => users,
+            Err(Error::KeyringUnavailable(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        for user in self.fallback.list(service)? {
+            if !users.contains(&user) {
+                users.push(user);
+            }
+        }
+        Ok(users)
+    }
+
+    /// Limits follow the primary store so secrets stay portable once the
+    /// OS keyring becomes available again.
+    fn max_secret_size(&self) -> Option<usize> {
+        self.primary.max_secret_size()
+    }
+
+    fn secret_size(&self, secret: &str) -> usize {
+        self.primary.secret_size(secret)
+    }
+}
+
+/// Fallback vault whose key is resolved on first use, so a missing machine id
+/// surfaces as a keyring error on that operation instead of failing plugin
+/// setup.  Only a successfully opened vault is kept; after a failure the key
+/// is resolved again on the next operation.
+pub(crate) struct LazyVaultStore {
+    path: PathBuf,
+    key: VaultKey,
+    app_identifier: String,
+    vault: OnceLock<EncryptedFileSecretStore>,
+}
+
+impl LazyVaultStore {
+    pub(crate) fn new(path: PathBuf, key: VaultKey, app_identifier: impl Into<String>) -> Self {
+        Self {
+            path,
+            key,
+            app_identifier: app_identifier.into(),
+            vault: OnceLock::new(),
+        }
+    }
+
+    /// The vault, or `None` when it was never opened and no vault file
+    /// exists, so reads on a machine that never needed the vault do not
+    /// resolve the key.
+    fn existing_vault(&self) -> Result<Option<&EncryptedFileSecretStore>> {
+        if self.vault.get().is_none() && !self.path.exists() {
+            return Ok(None);
+        }
+        self.vault().map(Some)
+    }
+
+    fn vault(&self) -> Result<&EncryptedFileSecretStore> {
+        if let Some(vault) = self.vault.get() {
+            return Ok(vault);
+        }
+        let passphrase = self.key.resolve(&self.app_identifier)?;
+        Ok(self
+            .vault
+            .get_or_init(|| EncryptedFileSecretStore::new(&self.path, &passphrase)))
+    }
+}
+
+impl SecretStore for LazyVaultStore {
+    fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+        match self.existing_vault()? {
+            Some(vault) => vault.get(service, user),
+            None => Ok(None),
+        }
+    }
+
+    fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+        self.vault()?.set(service, user, secret)
+    }
+
+    fn delete(&self, service: &str, user: &str) -> Result<()> {
+        match self.existing_vault()? {
+            Some(vault) => vault.delete(service, user),
+            None => Ok(()),
+        }
+    }
+
+    fn list(&self, service: &str) -> Result<Vec<String>> {
+        match self.existing_vault()? {
+            Some(vault) => vault.list(service),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// File name of the fallback vault created under `AppLocalData`.
+pub(crate) const FALLBACK_VAULT_FILE: &str = "configurate-secrets.vault";
+
+/// Source of the key protecting the fallback vault enabled via
+/// [`Builder::keyring_fallback`](crate::Builder::keyring_fallback).
+#[derive(Clone)]
+pub enum VaultKey {
+    /// Derive the key from the machine id (`/etc/machine-id` on Linux, the
+    /// registry `MachineGuid` on Windows, `IOPlatformUUID` on macOS) and the
+    /// app identifier.  Protects against copying the vault to another
+    /// machine, not against other processes running as the same user.  The
+    /// id is read on first vault use; if none is available, that operation
+    /// fails with a keyring error.
+    MachineBound,
+    /// Derive the key from a user-supplied passphrase.
+    Passphrase(Zeroizing<String>),
+}
+
+impl VaultKey {
+    /// Convenience constructor for [`VaultKey::Passphrase`].
+    pub fn passphrase(passphrase: impl Into<String>) -> Self {
+        Self::Passphrase(Zeroizing::new(passphrase.into()))
+    }
+
+    /// Resolves the Argon2id password used for the vault.
+    pub(crate) fn resolve(&self, app_identifier: &str) -> Result<Zeroizing<String>> {
+        match self {
+            Self::Passphrase(passphrase) => Ok(passphrase.clone()),
+            Self::MachineBound => Ok(Zeroizing::new(format!(
+                "configurate-vault:{}:{}",
+                app_identifier,
+                machine_id()?
+            ))),
+        }
+    }
+}
+
+/// Redacts the passphrase so it never appears in logs.
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MachineBound => write!(f, "MachineBound"),
+            Self::Passphrase(_) => write!(f, "Passphrase([REDACTED])"),
+        }
+    }
+}
+
+fn machine_id() -> Result<String> {
+    match platform_machine_id().filter(|id| !id.is_empty()) {
+        Some(id) => Ok(id),
+        None => Err(Error::Keyring(
+            "no machine id is available on this platform; use VaultKey::Passphrase for the \
+             keyring fallback vault"
+                .to_string(),
+        )),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn platform_machine_id() -> Option<String> {
+    // `MachineGuid` is set by Windows setup and survives user changes.
+    let output = std::process::Command::new("reg")
+        .args([
+            "query",
+            r"HKLM\SOFTWARE\Microsoft\Cryptography",
+            "/v",
+            "MachineGuid",
+        ])
+        .output()
+        .ok()?;
+    parse_reg_machine_guid(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "macos")]
+fn platform_machine_id() -> Option<String> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-rd1", "-c", "IOPlatformExpertDevice"])
+        .output()
+        .ok()?;
+    parse_ioreg_platform_uuid(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_machine_id() -> Option<String> {
+    ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+}
+
+/// Extracts the value from `reg query … /v MachineGuid` output, e.g.
+/// `    MachineGuid    REG_SZ    6f1c…`.
+#[cfg_attr(not(any(target_os = "windows", test)), allow(dead_code))]
+fn parse_reg_machine_guid(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some("MachineGuid"), Some("REG_SZ"), Some(id)) => Some(id.to_string()),
+            _ => None,
+        }
+    })
+}
+
+/// Extracts the value from `ioreg` output, e.g.
+/// `      "IOPlatformUUID" = "1A2B…"`.
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_ioreg_platform_uuid(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "\"IOPlatformUUID\"").then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn memory_store_roundtrip_and_list() {
+        let store = MemorySecretStore::new();
+        store.set("svc", "acc/a", "one").unwrap();
+        store.set("svc", "acc/b", "two").unwrap();
+        store.set("other", "acc/c", "three").unwrap();
+
+        assert_eq!(store.get("svc", "acc/a").unwrap().as_deref(), Some("one"));
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/a", "acc/b"]);
+
+        store.delete("svc", "acc/a").unwrap();
+        store.delete("svc", "acc/missing").unwrap();
+        assert_eq!(store.get("svc", "acc/a").unwrap(), None);
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/b"]);
+    }
+
+    #[test]
+    fn encrypted_file_store_persists_across_handles() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secrets.vault");
+
+        let store = EncryptedFileSecretStore::new(&path, "vault-pass");
+        assert_eq!(store.get("svc", "acc/a").unwrap(), None);
+        store.set("svc", "acc/a", "secret").unwrap();
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(6).any(|w| w == b"secret"));
+
+        let reopened = EncryptedFileSecretStore::new(&path, "vault-pass");
+        assert_eq!(reopened.get("svc", "acc/a").unwrap().as_deref(), Some("secret"));
+        assert_eq!(reopened.list("svc").unwrap(), vec!["acc/a"]);
+
+        reopened.delete("svc", "acc/a").unwrap();
+        assert!(store.list("svc").unwrap().is_empty());
+    }
+
+    struct UnavailableStore;
+
+    impl SecretStore for UnavailableStore {
+        fn get(&self, _: &str, _: &str) -> Result<Option<String>> {
+            Err(Error::KeyringUnavailable("no secret service".to_string()))
+        }
+        fn set(&self, _: &str, _: &str, _: &str) -> Result<()> {
+            Err(Error::KeyringUnavailable("no secret service".to_string()))
+        }
+        fn delete(&self, _: &str, _: &str) -> Result<()> {
+            Err(Error::KeyringUnavailable("no secret service".to_string()))
+        }
+        fn list(&self, _: &str) -> Result<Vec<String>> {
+            Err(Error::KeyringUnavailable("no secret service".to_string()))
+        }
+    }
+
+    #[test]
+    fn fallback_store_is_used_when_primary_is_unavailable() {
+        let fallback = Arc::new(MemorySecretStore::new());
+        let store = FallbackSecretStore::new(Arc::new(UnavailableStore), fallback.clone());
+
+        store.set("svc", "acc/a", "secret").unwrap();
+        assert_eq!(fallback.get("svc", "acc/a").unwrap().as_deref(), Some("secret"));
+        assert_eq!(store.get("svc", "acc/a").unwrap().as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn fallback_store_prefers_working_primary() {
+        let primary = Arc::new(MemorySecretStore::new());
+        let fallback = Arc::new(MemorySecretStore::new());
+        let store = FallbackSecretStore::new(primary.clone(), fallback.clone());
+
+        store.set("svc", "acc/a", "fresh").unwrap();
+        assert_eq!(primary.get("svc", "acc/a").unwrap().as_deref(), Some("fresh"));
+        assert_eq!(fallback.get("svc", "acc/a").unwrap(), None);
+        assert_eq!(store.get("svc", "acc/b").unwrap(), None);
+    }
+
+    #[test]
+    fn vault_secrets_stay_reachable_once_the_primary_is_back() {
+        let primary = Arc::new(MemorySecretStore::new());
+        let fallback = Arc::new(MemorySecretStore::new());
+        fallback.set("svc", "acc/a", "from-outage").unwrap();
+        fallback.set("svc", "acc/b", "doomed").unwrap();
+        let store = FallbackSecretStore::new(primary.clone(), fallback.clone());
+
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/a", "acc/b"]);
+
+        // A read moves the secret into the primary store.
+        assert_eq!(store.get("svc", "acc/a").unwrap().as_deref(), Some("from-outage"));
+        assert_eq!(primary.get("svc", "acc/a").unwrap().as_deref(), Some("from-outage"));
+        assert_eq!(fallback.get("svc", "acc/a").unwrap(), None);
+
+        // Deletes clear the vault copy as well.
+        store.delete("svc", "acc/b").unwrap();
+        assert_eq!(fallback.get("svc", "acc/b").unwrap(), None);
+        assert_eq!(store.get("svc", "acc/b").unwrap(), None);
+    }
+
+    /// Works like [`OsKeyringStore`]: everything but listing.
+    struct UnlistableStore(MemorySecretStore);
+
+    impl SecretStore for UnlistableStore {
+        fn get(&self, service: &str, user: &str) -> Result<Option<String>> {
+            self.0.get(service, user)
+        }
+        fn set(&self, service: &str, user: &str, secret: &str) -> Result<()> {
+            self.0.set(service, user, secret)
+        }
+        fn delete(&self, service: &str, user: &str) -> Result<()> {
+            self.0.delete(service, user)
+        }
+        fn list(&self, service: &str) -> Result<Vec<String>> {
+            OsKeyringStore.list(service)
+        }
+        fn supports_list(&self) -> bool {
+            false
+        }
+    }
+
+    /// Lists with an error that is not about availability.
+    struct BrokenListStore;
+
+    impl SecretStore for BrokenListStore {
+        fn get(&self, _: &str, _: &str) -> Result<Option<String>> {
+            Ok(None)
+        }
+        fn set(&self, _: &str, _: &str, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn delete(&self, _: &str, _: &str) -> Result<()> {
+            Ok(())
+        }
+        fn list(&self, _: &str) -> Result<Vec<String>> {
+            Err(Error::Keyring("permission denied".to_string()))
+        }
+    }
+
+    #[test]
+    fn fallback_store_lists_the_vault_when_the_primary_cannot_list() {
+        let fallback = Arc::new(MemorySecretStore::new());
+        fallback.set("svc", "acc/a", "secret").unwrap();
+        let store = FallbackSecretStore::new(
+            Arc::new(UnlistableStore(MemorySecretStore::new())),
+            fallback,
+        );
+        assert_eq!(store.list("svc").unwrap(), vec!["acc/a"]);
+
+        let store = FallbackSecretStore::new(
+            Arc::new(UnavailableStore),
+            Arc::new(MemorySecretStore::new()),
+        );
+        assert!(store.list("svc").unwrap().is_empty());
+    }
+
+    #[test]
+    fn fallback_store_returns_primary_list_errors() {
+        let store = FallbackSecretStore::new(
+            Arc::new(BrokenListStore),
+            Arc::new(MemorySecretStore::new()),
+        );
+        assert!(matches!(store.list("svc"), Err(Error::Keyring(_))));
+    }
+
+    #[test]
+    fn vault_key_debug_redacts_passphrase() {
+        let key = VaultKey::passphrase("hunter2");
+        assert_eq!(format!("{:?}", key), "Passphrase([REDACTED])");
+        assert_eq!(key.resolve("com.example.app").unwrap().as_str(), "hunter2");
+    }
+
+    #[test]
+    fn platform_machine_id_outputs_are_parsed() {
+        let reg = "\r\nHKEY_LOCAL_MACHINE\\SOFTWARE\\Microsoft\\Cryptography\r\n    \
+                   MachineGuid    REG_SZ    6f1c2d3e-0000-4a5b-8c9d-0123456789ab\r\n";
+        assert_eq!(
+            parse_reg_machine_guid(reg).as_deref(),
+            Some("6f1c2d3e-0000-4a5b-8c9d-0123456789ab")
+        );
+        let ioreg = "+-o J314sAP  <class IOPlatformExpertDevice>\n    {\n      \
+                     \"IOPlatformUUID\" = \"1A2B3C4D-0000-1111-2222-333344445555\"\n";
+        assert_eq!(
+            parse_ioreg_platform_uuid(ioreg).as_deref(),
+            Some("1A2B3C4D-0000-1111-2222-333344445555")
+        );
+        assert_eq!(parse_reg_machine_guid("ERROR: not found"), None);
+    }
+
+    #[test]
+    fn lazy_vault_is_opened_on_first_use() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secrets.vault");
+        let store = LazyVaultStore::new(path.clone(), VaultKey::passphrase("pass"), "app");
+        assert_eq!(store.get("svc", "acc/a").unwrap(), None);
+        assert!(store.list("svc").unwrap().is_empty());
+        assert!(store.vault.get().is_none());
+        assert!(!path.exists());
+        store.set("svc", "acc/a", "secret").unwrap();
+        assert_eq!(store.get("svc", "acc/a").unwrap().as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn encrypted_file_store_rejects_wrong_passphrase() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("secrets.vault");
+        EncryptedFileSecretStore::new(&path, "right")
+            .set("svc", "acc/a", "secret")
+            .unwrap();
+
+        let err = EncryptedFileSecretStore::new(&path, "wrong")
+            .get("svc", "acc/a")
+            .unwrap_err();
+        assert!(matches!(err, Error::Keyring(_)));
+    }
+}
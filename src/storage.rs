@@ -1,4 +1,4 @@
-﻿/// Storage backend trait and concrete implementations for JSON, YAML, Binary, and EncryptedBinary.
+﻿/// Storage backend trait and concrete implementations for JSON, YAML, TOML, INI, .env, XML, plist, Binary, and EncryptedBinary.
 use std::collections::HashSet;
 use std::io::Read;
 use std::path::Path;
@@ -9,8 +9,14 @@ use zeroize::Zeroizing;
 
 use serde_json::Value;
 
+use crate::encoding;
 use crate::error::{Error, Result};
-use crate::models::NormalizedProvider;
+use crate::key_cache;
+use crate::keyvalue::{self, Dialect};
+use crate::models::{BinaryEncoding, Compression, NormalizedProvider};
+use crate::property_list;
+use crate::toml_value;
+use crate::xml;
 
 /// Tracks paths for which backup files have been created so they can be
 /// cleaned up when the application exits.
@@ -137,7 +143,7 @@ fn create_backup(path: &Path, registry: &BackupRegistry) {
 ///
 /// This minimizes the chance of leaving a partially-written destination file
 /// when a write is interrupted.
-fn write_file_safely(path: &Path, bytes: &[u8]) -> Result<()> {
+pub(crate) fn write_file_safely(path: &Path, bytes: &[u8]) -> Result<()> {
     use std::io::Write;
 
     let parent = path
@@ -222,15 +228,81 @@ impl StorageBackend for YamlBackend {
     }
 }
 
+/// Marks a compressed Binary payload.  JSON text never starts with a NUL
+/// byte, so uncompressed payloads from earlier versions are still read.
+const COMPRESSED_MAGIC: &[u8; 4] = b"\0CFZ";
+/// Algorithm byte following `COMPRESSED_MAGIC`.
+const DEFLATE_ALGORITHM: u8 = 1;
+
+/// Serializes `value` in `encoding` (see `encoding::encode_stored`),
+/// compressed when `compression` is set.
+///
+/// Compressed format: `[b"\0CFZ"][1-byte algorithm][compressed payload]`.
+fn encode_binary_payload(
+    value: &Value,
+    encoding: BinaryEncoding,
+    compression: Option<Compression>,
+) -> Result<Vec<u8>> {
+    let json = encoding::encode_stored(value, encoding)?;
+    match compression {
+        None => Ok(json),
+        Some(Compression::Deflate) => {
+            use flate2::write::DeflateEncoder;
+            use std::io::Write;
+
+            let mut output = Vec::with_capacity(json.len() / 2 + 8);
+            output.extend_from_slice(COMPRESSED_MAGIC);
+            output.push(DEFLATE_ALGORITHM);
+            let mut encoder = DeflateEncoder::new(output, flate2::Compression::default());
+            encoder.write_all(&json)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+/// Parses a Binary payload written by `encode_binary_payload`.  Compressed
+/// payloads may expand to at most `max_bytes`; anything larger is rejected
+/// without being inflated in full.
+fn decode_binary_payload(bytes: &[u8], max_bytes: usize) -> Result<Value> {
+    let Some(rest) = bytes.strip_prefix(COMPRESSED_MAGIC) else {
+        return encoding::decode_stored(bytes);
+    };
+    let (algorithm, compressed) = rest
+        .split_first()
+        .ok_or_else(|| Error::Storage("compressed file is missing its header".to_string()))?;
+    if *algorithm != DEFLATE_ALGORITHM {
+        return Err(Error::Storage(format!(
+            "unsupported compression algorithm {}",
+            algorithm
+        )));
+    }
+
+    let mut json = Vec::new();
+    flate2::read::DeflateDecoder::new(compressed)
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut json)
+        .map_err(|e| Error::Storage(format!("decompression failed: {}", e)))?;
+    if json.len() > max_bytes {
+        return Err(Error::InvalidPayload(format!(
+            "decompressed file exceeds maximum size of {} bytes",
+            max_bytes
+        )));
+    }
+    encoding::decode_stored(&json)
+}
+
 /// Unencrypted binary storage backend.
 ///
-/// Stores a compact (non-pretty) JSON representation of the value.
+/// Stores the value as compact JSON, CBOR or MessagePack, optionally
+/// compressed.
 /// Use `BinaryEncryptedBackend` when confidentiality is required.
 ///
 /// NOTE: This format differs from the bincode-wrapped format used before v0.2.3.
 /// Existing unencrypted binary files written by earlier versions must be
 /// re-created after upgrading.
 pub struct BinaryBackend {
+    encoding: BinaryEncoding,
+    compression: Option<Compression>,
     backup: bool,
     registry: Arc<BackupRegistry>,
     max_read_bytes: usize,
@@ -239,16 +311,14 @@ pub struct BinaryBackend {
 impl StorageBackend for BinaryBackend {
     fn read(&self, path: &Path) -> Result<Value> {
         let bytes = read_file_bounded(path, self.max_read_bytes)?;
-        let value: Value =
-            serde_json::from_slice(&bytes).map_err(|e| Error::Storage(e.to_string()))?;
-        Ok(value)
+        decode_binary_payload(&bytes, self.max_read_bytes)
     }
 
     fn write(&self, path: &Path, value: &Value) -> Result<()> {
         if self.backup {
             create_backup(path, &self.registry);
         }
-        let bytes = serde_json::to_vec(value)?;
+        let bytes = encode_binary_payload(value, self.encoding, self.compression)?;
         write_file_safely(path, &bytes)
     }
 }
@@ -260,10 +330,13 @@ impl StorageBackend for BinaryBackend {
 /// The 32-byte cipher key is derived from the caller-supplied key string via
 /// SHA-256, so any high-entropy string (e.g. a random key stored in the OS
 /// keyring) is suitable. The Poly1305 tag provides authenticated encryption:
-/// any tampering with the ciphertext is detected at read time.
+/// any tampering with the ciphertext is detected at read time.  With
+/// compression, the plaintext is compressed before it is encrypted.
 pub struct BinaryEncryptedBackend {
     /// Derived 32-byte cipher key, zeroed on drop via `Zeroizing`.
     key: Zeroizing<[u8; 32]>,
+    encoding: BinaryEncoding,
+    compression: Option<Compression>,
     backup: bool,
     registry: Arc<BackupRegistry>,
     max_read_bytes: usize,
@@ -283,11 +356,25 @@ impl BinaryEncryptedBackend {
         key.copy_from_slice(&hash);
         Self {
             key: Zeroizing::new(key),
+            encoding: BinaryEncoding::Json,
+            compression: None,
             backup,
             registry,
             max_read_bytes,
         }
     }
+
+    /// Writes the plaintext in `encoding`.
+    pub fn with_encoding(mut self, encoding: BinaryEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Compresses the plaintext with `compression` on writes.
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
 }
 
 impl StorageBackend for BinaryEncryptedBackend {
@@ -310,9 +397,7 @@ impl StorageBackend for BinaryEncryptedBackend {
             Error::Storage("decryption failed: wrong key or corrupted data".to_string())
         })?;
 
-        let value: Value =
-            serde_json::from_slice(&plaintext).map_err(|e| Error::Storage(e.to_string()))?;
-        Ok(value)
+        decode_binary_payload(&plaintext, self.max_read_bytes)
     }
 
     fn write(&self, path: &Path, value: &Value) -> Result<()> {
@@ -322,7 +407,7 @@ impl StorageBackend for BinaryEncryptedBackend {
         use chacha20poly1305::aead::{Aead, KeyInit};
         use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
 
-        let json_bytes = serde_json::to_vec(value)?;
+        let json_bytes = encode_binary_payload(value, self.encoding, self.compression)?;
 
         let mut nonce_bytes = [0u8; 24];
         rand::rng().fill_bytes(&mut nonce_bytes);
@@ -348,11 +433,17 @@ impl StorageBackend for BinaryEncryptedBackend {
 /// On-disk format: `[16-byte salt][24-byte nonce][ciphertext + 16-byte tag]`
 ///
 /// The 32-byte cipher key is derived via Argon2id(password, salt) with moderate
-/// parameters (m=19456 KiB, t=2, p=1). A random 16-byte salt is generated on
-/// every write so that identical passwords produce different ciphertext.
+/// parameters (m=19456 KiB, t=2, p=1) and kept in the process-wide key cache.
+/// A random 16-byte salt is generated on every write so that identical
+/// passwords produce different ciphertext, unless salt reuse is enabled: then
+/// a write keeps the file's existing salt when its key was already derived in
+/// this session (the nonce is still fresh on every write).
 pub struct BinaryArgon2Backend {
     /// Raw password string, zeroed on drop via `Zeroizing`.
     password: Zeroizing<String>,
+    reuse_salt: bool,
+    encoding: BinaryEncoding,
+    compression: Option<Compression>,
     backup: bool,
     registry: Arc<BackupRegistry>,
     max_read_bytes: usize,
@@ -367,22 +458,77 @@ impl BinaryArgon2Backend {
     ) -> Self {
         Self {
             password: Zeroizing::new(password.to_string()),
+            reuse_salt: false,
+            encoding: BinaryEncoding::Json,
+            compression: None,
             backup,
             registry,
             max_read_bytes,
         }
     }
 
-    fn derive_key(&self, salt: &[u8]) -> std::result::Result<Zeroizing<[u8; 32]>, Error> {
-        use argon2::Argon2;
+    /// Keeps the existing file's salt on writes when its key is cached.
+    pub fn with_salt_reuse(mut self, reuse_salt: bool) -> Self {
+        self.reuse_salt = reuse_salt;
+        self
+    }
+
+    /// Writes the plaintext in `encoding`.
+    pub fn with_encoding(mut self, encoding: BinaryEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Compresses the plaintext with `compression` on writes.
+    pub fn with_compression(mut self, compression: Option<Compression>) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    fn write_salt(&self, path: &Path) -> [u8; key_cache::SALT_LEN] {
+        if self.reuse_salt {
+            if let Some(salt) = read_argon2_salt(path) {
+                if key_cache::cached(&self.password, &salt).is_some() {
+                    return salt;
+                }
+            }
+        }
+        key_cache::fresh_salt(&self.password)
+    }
+}
 
-        let argon2 = Argon2::default();
-        let mut key = Zeroizing::new([0u8; 32]);
-        argon2
-            .hash_password_into(self.password.as_bytes(), salt, &mut *key)
-            .map_err(|e| Error::Storage(format!("argon2 key derivation failed: {}", e)))?;
-        Ok(key)
+/// Reads the salt header of an Argon2-encrypted file, if it has one.
+fn read_argon2_salt(path: &Path) -> Option<[u8; key_cache::SALT_LEN]> {
+    let mut file = std::fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() < 56 {
+        return None;
     }
+    let mut salt = [0u8; key_cache::SALT_LEN];
+    file.read_exact(&mut salt).ok()?;
+    Some(salt)
+}
+
+/// Derives the Argon2 keys an upcoming read (`write == false`) or write of
+/// `path` needs on the blocking thread pool, so the synchronous backend
+/// finds them in the key cache.  A no-op for other providers.
+pub(crate) async fn prefetch_keys(
+    provider: &NormalizedProvider,
+    path: &Path,
+    write: bool,
+) -> Result<()> {
+    let NormalizedProvider::Binary {
+        encryption_key: Some(password),
+        kdf: crate::models::KeyDerivation::Argon2,
+        reuse_salt,
+        ..
+    } = provider
+    else {
+        return Ok(());
+    };
+    let existing = read_argon2_salt(path);
+    // A reusing write needs no fresh salt once the existing one is derived.
+    let fresh = write && !(*reuse_salt && existing.is_some());
+    key_cache::prefetch(password.clone(), existing, fresh).await
 }
 
 impl StorageBackend for BinaryArgon2Backend {
@@ -402,16 +548,14 @@ impl StorageBackend for BinaryArgon2Backend {
         let nonce = XNonce::from_slice(&bytes[16..40]);
         let ciphertext = &bytes[40..];
 
-        let key = self.derive_key(salt)?;
+        let key = key_cache::derive(&self.password, salt)?;
         let cipher = XChaCha20Poly1305::new(Key::from_slice(&*key));
         drop(key);
         let plaintext = cipher.decrypt(nonce, ciphertext).map_err(|_| {
             Error::Storage("decryption failed: wrong key or corrupted data".to_string())
         })?;
 
-        let value: Value =
-            serde_json::from_slice(&plaintext).map_err(|e| Error::Storage(e.to_string()))?;
-        Ok(value)
+        decode_binary_payload(&plaintext, self.max_read_bytes)
     }
 
     fn write(&self, path: &Path, value: &Value) -> Result<()> {
@@ -421,12 +565,10 @@ impl StorageBackend for BinaryArgon2Backend {
         if self.backup {
             create_backup(path, &self.registry);
         }
-        let json_bytes = serde_json::to_vec(value)?;
-
-        let mut salt = [0u8; 16];
-        rand::rng().fill_bytes(&mut salt);
+        let json_bytes = encode_binary_payload(value, self.encoding, self.compression)?;
 
-        let key = self.derive_key(&salt)?;
+        let salt = self.write_salt(path);
+        let key = key_cache::derive(&self.password, &salt)?;
 
         let mut nonce_bytes = [0u8; 24];
         rand::rng().fill_bytes(&mut nonce_bytes);
@@ -447,104 +589,168 @@ impl StorageBackend for BinaryArgon2Backend {
     }
 }
 
-/// Converts a `serde_json::Value` into a `toml::Value`.
-///
-/// `null` object fields are silently omitted because TOML has no null type.
-/// `null` values inside arrays are rejected to avoid silently changing array
-/// length and element positions.
-fn json_to_toml_value(value: &Value) -> Result<toml::Value> {
-    match value {
-        Value::Null => Err(Error::Storage(
-            "TOML does not support null values in this position".to_string(),
-        )),
-        Value::Bool(b) => Ok(toml::Value::Boolean(*b)),
-        Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                Ok(toml::Value::Integer(i))
-            } else if let Some(u) = n.as_u64() {
-                // u64 values that fit in i64 range
-                if u <= i64::MAX as u64 {
-                    Ok(toml::Value::Integer(u as i64))
-                } else {
-                    Err(Error::Storage(format!(
-                        "TOML cannot represent unsigned integer {} (exceeds i64::MAX)",
-                        u
-                    )))
-                }
-            } else {
-                n.as_f64()
-                    .filter(|f| f.is_finite())
-                    .map(toml::Value::Float)
-                    .ok_or_else(|| {
-                        Error::Storage("TOML does not support this numeric value".to_string())
-                    })
-            }
-        }
-        Value::String(s) => Ok(toml::Value::String(s.clone())),
-        Value::Array(arr) => {
-            let mut items = Vec::with_capacity(arr.len());
-            for value in arr {
-                if value.is_null() {
-                    return Err(Error::Storage(
-                        "TOML does not support null values inside arrays".to_string(),
-                    ));
-                }
-                items.push(json_to_toml_value(value)?);
-            }
-            Ok(toml::Value::Array(items))
-        }
-        Value::Object(map) => {
-            let mut toml_map = toml::map::Map::new();
-            for (k, v) in map {
-                if v.is_null() {
-                    continue;
-                }
-                toml_map.insert(k.clone(), json_to_toml_value(v)?);
-            }
-            Ok(toml::Value::Table(toml_map))
-        }
-    }
-}
-
-/// TOML storage backend.
+/// TOML storage backend (see `toml_value` for the JSON mapping).
 ///
 /// Reads and writes configs in TOML format.  TOML has no null type so `null`
 /// JSON values are silently omitted on write and will be absent on the next
 /// read.  Use `optional()` schema fields to express nullable config values.
+/// Datetimes, large integers and non-finite floats are tagged objects, and
+/// writes keep the float kind of numbers already stored as floats.
 pub struct TomlBackend {
     backup: bool,
     registry: Arc<BackupRegistry>,
     max_read_bytes: usize,
 }
 
+impl TomlBackend {
+    fn read_text(&self, path: &Path) -> Result<String> {
+        let bytes = read_file_bounded(path, self.max_read_bytes)?;
+        String::from_utf8(bytes)
+            .map_err(|e| Error::Storage(format!("TOML file is not valid UTF-8: {}", e)))
+    }
+}
+
 impl StorageBackend for TomlBackend {
     fn read(&self, path: &Path) -> Result<Value> {
+        toml_value::parse(&self.read_text(path)?)
+    }
+
+    fn write(&self, path: &Path, value: &Value) -> Result<()> {
+        // Numeric kinds are taken from the existing file when it parses;
+        // otherwise numbers are written as they are.
+        let existing = match path.exists() {
+            true => self
+                .read_text(path)
+                .and_then(|text| toml_value::parse_document(&text))
+                .ok(),
+            false => None,
+        };
+        let text = toml_value::render(value, existing.as_ref())?;
+        if self.backup {
+            create_backup(path, &self.registry);
+        }
+        write_file_safely(path, text.as_bytes())
+    }
+}
+
+/// INI / `.env` storage backend (see `keyvalue` for the JSON mapping).
+///
+/// Writes update the existing file in place, so comments, blank lines and
+/// the text of unchanged entries survive saves and patches.  A malformed
+/// existing file fails the write.
+pub struct KeyValueBackend {
+    dialect: Dialect,
+    backup: bool,
+    registry: Arc<BackupRegistry>,
+    max_read_bytes: usize,
+}
+
+impl KeyValueBackend {
+    fn read_text(&self, path: &Path) -> Result<String> {
         let bytes = read_file_bounded(path, self.max_read_bytes)?;
-        let text = String::from_utf8(bytes)
-            .map_err(|e| Error::Storage(format!("TOML file is not valid UTF-8: {}", e)))?;
-        let toml_val: toml::Value =
-            toml::from_str(&text).map_err(|e| Error::Storage(e.to_string()))?;
-        let json_val =
-            serde_json::to_value(toml_val).map_err(|e| Error::Storage(e.to_string()))?;
-        Ok(json_val)
+        String::from_utf8(bytes).map_err(|e| {
+            Error::Storage(format!(
+                "{} file is not valid UTF-8: {}",
+                self.dialect.name(),
+                e
+            ))
+        })
+    }
+}
+
+impl StorageBackend for KeyValueBackend {
+    fn read(&self, path: &Path) -> Result<Value> {
+        keyvalue::parse(&self.read_text(path)?, self.dialect)
     }
 
     fn write(&self, path: &Path, value: &Value) -> Result<()> {
+        // An unreadable or malformed existing file fails the write instead
+        // of being replaced, which would lose its comments and layout.
+        let text = match path.exists() {
+            true => keyvalue::rewrite(&self.read_text(path)?, value, self.dialect)?,
+            false => keyvalue::render(value, self.dialect)?,
+        };
         if self.backup {
             create_backup(path, &self.registry);
         }
-        let toml_val = json_to_toml_value(value)?;
-        if !matches!(toml_val, toml::Value::Table(_)) {
-            return Err(Error::Storage(
-                "TOML top-level value must be a table (object)".to_string(),
-            ));
+        write_file_safely(path, text.as_bytes())
+    }
+}
+
+/// Simple XML storage backend (see `xml` for the JSON mapping).
+///
+/// Writes regenerate the document, keeping the existing root element name.
+/// A malformed existing file fails the write.
+pub struct XmlBackend {
+    backup: bool,
+    registry: Arc<BackupRegistry>,
+    max_read_bytes: usize,
+}
+
+impl XmlBackend {
+    fn read_text(&self, path: &Path) -> Result<String> {
+        let bytes = read_file_bounded(path, self.max_read_bytes)?;
+        String::from_utf8(bytes)
+            .map_err(|e| Error::Storage(format!("XML file is not valid UTF-8: {}", e)))
+    }
+}
+
+impl StorageBackend for XmlBackend {
+    fn read(&self, path: &Path) -> Result<Value> {
+        xml::parse(&self.read_text(path)?)
+    }
+
+    fn write(&self, path: &Path, value: &Value) -> Result<()> {
+        // An unreadable or malformed existing file fails the write instead
+        // of being replaced with a `<config>` document.
+        let text = match path.exists() {
+            true => xml::rewrite(&self.read_text(path)?, value)?,
+            false => xml::render(value)?,
+        };
+        if self.backup {
+            create_backup(path, &self.registry);
         }
-        let text =
-            toml::to_string_pretty(&toml_val).map_err(|e| Error::Storage(e.to_string()))?;
         write_file_safely(path, text.as_bytes())
     }
 }
 
+/// Property list storage backend (see `property_list` for the JSON mapping).
+///
+/// Reads XML and binary plists.  Writes keep the binary form when the
+/// existing file is a binary plist and produce XML otherwise, and keep
+/// stored reals reals.  A malformed existing file fails the write.
+pub struct PlistBackend {
+    backup: bool,
+    registry: Arc<BackupRegistry>,
+    max_read_bytes: usize,
+}
+
+impl StorageBackend for PlistBackend {
+    fn read(&self, path: &Path) -> Result<Value> {
+        property_list::parse(&read_file_bounded(path, self.max_read_bytes)?)
+    }
+
+    fn write(&self, path: &Path, value: &Value) -> Result<()> {
+        // An unreadable or malformed existing file fails the write instead
+        // of being replaced (and losing its binary form).
+        let (document, binary) = match path.exists() {
+            true => {
+                let existing = read_file_bounded(path, self.max_read_bytes)?;
+                (
+                    Some(property_list::parse_document(&existing)?),
+                    property_list::is_binary(&existing),
+                )
+            }
+            false => (None, false),
+        };
+        let bytes = property_list::render(value, document.as_ref(), binary)?;
+        if self.backup {
+            create_backup(path, &self.registry);
+        }
+        write_file_safely(path, &bytes)
+    }
+}
+
 /// Returns a boxed file backend for the given normalized provider.
 pub fn file_backend_for(
     provider: &NormalizedProvider,
@@ -569,25 +775,51 @@ pub fn file_backend_for(
             registry,
             max_read_bytes,
         })),
+        NormalizedProvider::Ini => Ok(Box::new(KeyValueBackend {
+            dialect: Dialect::Ini,
+            backup,
+            registry,
+            max_read_bytes,
+        })),
+        NormalizedProvider::Dotenv => Ok(Box::new(KeyValueBackend {
+            dialect: Dialect::Dotenv,
+            backup,
+            registry,
+            max_read_bytes,
+        })),
+        NormalizedProvider::Xml => Ok(Box::new(XmlBackend {
+            backup,
+            registry,
+            max_read_bytes,
+        })),
+        NormalizedProvider::Plist => Ok(Box::new(PlistBackend {
+            backup,
+            registry,
+            max_read_bytes,
+        })),
         NormalizedProvider::Binary {
             encryption_key,
             kdf,
+            reuse_salt,
+            compression,
+            encoding,
         } => match encryption_key.as_ref().map(|key| key.as_str()) {
             Some(key) => match kdf {
-                KeyDerivation::Argon2 => Ok(Box::new(BinaryArgon2Backend::new(
-                    key,
-                    backup,
-                    registry,
-                    max_read_bytes,
-                ))),
-                KeyDerivation::Sha256 => Ok(Box::new(BinaryEncryptedBackend::new(
-                    key,
-                    backup,
-                    registry,
-                    max_read_bytes,
-                ))),
+                KeyDerivation::Argon2 => Ok(Box::new(
+                    BinaryArgon2Backend::new(key, backup, registry, max_read_bytes)
+                        .with_salt_reuse(*reuse_salt)
+                        .with_encoding(*encoding)
+                        .with_compression(*compression),
+                )),
+                KeyDerivation::Sha256 => Ok(Box::new(
+                    BinaryEncryptedBackend::new(key, backup, registry, max_read_bytes)
+                        .with_encoding(*encoding)
+                        .with_compression(*compression),
+                )),
             },
             None => Ok(Box::new(BinaryBackend {
+                encoding: *encoding,
+                compression: *compression,
                 backup,
                 registry,
                 max_read_bytes,
@@ -596,11 +828,6 @@ pub fn file_backend_for(
     }
 }
 
-/// Public wrapper for `json_to_toml_value`, used by the export command.
-pub fn json_to_toml(value: &Value) -> Result<toml::Value> {
-    json_to_toml_value(value)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -650,6 +877,95 @@ mod tests {
         assert_eq!(loaded, data);
     }
 
+    #[test]
+    fn ini_writes_keep_existing_comments() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "tool.ini");
+        std::fs::write(&path, "; managed by tool\n[core]\nlevel = 1 ; default\n").unwrap();
+        let backend = KeyValueBackend {
+            dialect: Dialect::Ini,
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+
+        let mut data = backend.read(&path).unwrap();
+        assert_eq!(data, json!({"core": {"level": 1}}));
+        data["core"]["level"] = json!(2);
+        backend.write(&path, &data).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "; managed by tool\n[core]\nlevel = 2 ; default\n"
+        );
+
+        let broken = "; hand edited\n[core\nlevel = 1\n";
+        std::fs::write(&path, broken).unwrap();
+        assert!(backend.write(&path, &data).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+    }
+
+    #[test]
+    fn xml_writes_over_a_malformed_file_fail() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "tool.xml");
+        let backend = XmlBackend {
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+
+        std::fs::write(&path, "<prefs><a>1</a></prefs>").unwrap();
+        backend.write(&path, &json!({"a": 2})).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("<prefs>"));
+
+        let broken = "<prefs><a>1</prefs>";
+        std::fs::write(&path, broken).unwrap();
+        assert!(backend.write(&path, &json!({"a": 2})).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+    }
+
+    #[test]
+    fn plist_writes_keep_the_binary_format() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "com.example.app.plist");
+        let data = json!({"Created": {"$date": "2024-01-31T12:00:00Z"}, "Width": 800});
+        std::fs::write(&path, property_list::render(&data, None, true).unwrap()).unwrap();
+        let backend = PlistBackend {
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+
+        let mut loaded = backend.read(&path).unwrap();
+        assert_eq!(loaded, data);
+        loaded["Width"] = json!(1024);
+        backend.write(&path, &loaded).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(property_list::is_binary(&bytes));
+        assert_eq!(backend.read(&path).unwrap(), loaded);
+
+        let xml_path = tmp_path(&dir, "new.plist");
+        backend.write(&xml_path, &loaded).unwrap();
+        assert!(std::fs::read_to_string(&xml_path).unwrap().starts_with("<?xml"));
+    }
+
+    #[test]
+    fn plist_writes_over_a_malformed_file_fail() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "com.example.app.plist");
+        let backend = PlistBackend {
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+
+        let mut broken = property_list::render(&json!({"Width": 800}), None, true).unwrap();
+        broken.truncate(broken.len() / 2);
+        std::fs::write(&path, &broken).unwrap();
+        assert!(backend.write(&path, &json!({"Width": 1024})).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), broken);
+    }
+
     #[test]
     fn toml_roundtrip() {
         let dir = TempDir::new().unwrap();
@@ -669,6 +985,28 @@ mod tests {
         assert_eq!(loaded, data);
     }
 
+    #[test]
+    fn toml_writes_keep_stored_floats_and_datetimes() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "kinds.toml");
+        std::fs::write(&path, "ratio = 1.0\nupdated = 2024-01-31\n").unwrap();
+        let backend = TomlBackend {
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+
+        // A float sent back from the webview as a whole number.
+        let mut data = backend.read(&path).unwrap();
+        assert_eq!(data["updated"], json!({"$datetime": "2024-01-31"}));
+        data["ratio"] = json!(2);
+        backend.write(&path, &data).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "ratio = 2.0\nupdated = 2024-01-31\n"
+        );
+    }
+
     #[test]
     fn toml_array_null_is_rejected() {
         let dir = TempDir::new().unwrap();
@@ -692,6 +1030,8 @@ mod tests {
         let dir = TempDir::new().unwrap();
         let path = tmp_path(&dir, "test.bin");
         let backend = BinaryBackend {
+            encoding: BinaryEncoding::Json,
+            compression: None,
             backup: false,
             registry: reg(),
             max_read_bytes: max_read(),
@@ -702,6 +1042,97 @@ mod tests {
         assert_eq!(loaded, data);
     }
 
+    #[test]
+    fn compressed_binary_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "test.bin");
+        let compressed = BinaryBackend {
+            encoding: BinaryEncoding::Json,
+            compression: Some(Compression::Deflate),
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+        let data = json!({"items": vec!["repeated value"; 200]});
+        compressed.write(&path, &data).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(COMPRESSED_MAGIC));
+        assert!(bytes.len() < serde_json::to_vec(&data).unwrap().len() / 4);
+        assert_eq!(compressed.read(&path).unwrap(), data);
+
+        // Reads detect the header, whatever the backend's own setting.
+        let plain = BinaryBackend {
+            encoding: BinaryEncoding::Json,
+            compression: None,
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+        assert_eq!(plain.read(&path).unwrap(), data);
+    }
+
+    #[test]
+    fn encoded_binary_files_are_detected_on_read() {
+        let dir = TempDir::new().unwrap();
+        let data = json!({"name": "app", "blob": {"$bytes": "3q2+7w=="}});
+        for encoding in [BinaryEncoding::Cbor, BinaryEncoding::Msgpack] {
+            let path = tmp_path(&dir, "encoded.bin");
+            let backend = BinaryArgon2Backend::new("pw", false, reg(), max_read())
+                .with_encoding(encoding)
+                .with_compression(Some(Compression::Deflate));
+            backend.write(&path, &data).unwrap();
+            // A JSON-configured backend still reads the encoded file.
+            let json_backend = BinaryArgon2Backend::new("pw", false, reg(), max_read());
+            assert_eq!(json_backend.read(&path).unwrap(), data, "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn compressed_encrypted_roundtrips() {
+        let dir = TempDir::new().unwrap();
+        let data = json!({"items": vec!["repeated value"; 200]});
+
+        let path = tmp_path(&dir, "sha.bin");
+        let backend = BinaryEncryptedBackend::new("my-test-key", false, reg(), max_read())
+            .with_compression(Some(Compression::Deflate));
+        backend.write(&path, &data).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < 1000);
+        assert_eq!(backend.read(&path).unwrap(), data);
+
+        let path = tmp_path(&dir, "argon.bin");
+        let backend = BinaryArgon2Backend::new("my-password", false, reg(), max_read())
+            .with_compression(Some(Compression::Deflate));
+        backend.write(&path, &data).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < 1000);
+        assert_eq!(backend.read(&path).unwrap(), data);
+    }
+
+    #[test]
+    fn decompression_is_bounded_by_max_read_bytes() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "bomb.bin");
+        let writer = BinaryBackend {
+            encoding: BinaryEncoding::Json,
+            compression: Some(Compression::Deflate),
+            backup: false,
+            registry: reg(),
+            max_read_bytes: max_read(),
+        };
+        writer.write(&path, &json!("0".repeat(1 << 20))).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < 4096);
+
+        let reader = BinaryBackend {
+            encoding: BinaryEncoding::Json,
+            compression: None,
+            backup: false,
+            registry: reg(),
+            max_read_bytes: 64 * 1024,
+        };
+        let err = reader.read(&path).unwrap_err();
+        assert!(err.to_string().contains("decompressed file exceeds maximum size"));
+    }
+
     #[test]
     fn encrypted_binary_roundtrip() {
         let dir = TempDir::new().unwrap();
@@ -736,6 +1167,34 @@ mod tests {
         assert!(wrong_backend.read(&path).is_err());
     }
 
+    #[test]
+    fn argon2_salt_reuse_keeps_the_cached_salt() {
+        let dir = TempDir::new().unwrap();
+        let path = tmp_path(&dir, "test.argon2-reuse.bin");
+        let salt = |path: &Path| std::fs::read(path).unwrap()[..16].to_vec();
+
+        let backend = BinaryArgon2Backend::new("reuse-password", false, reg(), max_read());
+        backend.write(&path, &json!({"v": 1})).unwrap();
+        let first = salt(&path);
+        backend.write(&path, &json!({"v": 2})).unwrap();
+        assert_ne!(salt(&path), first, "a fresh salt is used by default");
+
+        let reusing = BinaryArgon2Backend::new("reuse-password", false, reg(), max_read())
+            .with_salt_reuse(true);
+        let second = salt(&path);
+        reusing.write(&path, &json!({"v": 3})).unwrap();
+        assert_eq!(salt(&path), second);
+        assert_eq!(reusing.read(&path).unwrap(), json!({"v": 3}));
+
+        // A file whose key was never derived in this session gets a new salt.
+        let other = tmp_path(&dir, "test.argon2-other.bin");
+        let mut foreign = vec![9u8; 16];
+        foreign.extend_from_slice(&std::fs::read(&path).unwrap()[16..]);
+        std::fs::write(&other, &foreign).unwrap();
+        reusing.write(&other, &json!({"v": 4})).unwrap();
+        assert_ne!(salt(&other), vec![9u8; 16]);
+    }
+
     #[test]
     fn backup_is_created_on_write() {
         let dir = TempDir::new().unwrap();
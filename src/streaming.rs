@@ -0,0 +1,304 @@
+/// Chunked IPC transfer for large configs.
+///
+/// `load` and `export_config` can send their response through a [`Channel`]
+/// in pieces of at most [`CHUNK_BYTES`] instead of a single IPC message, and
+/// `import_chunk` collects import content piece by piece.  Uploads are
+/// checked against `max_read_bytes` on every chunk, so oversized content is
+/// rejected as soon as it crosses the limit rather than after it has been
+/// buffered in full.
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+use rand::Rng;
+use serde::Serialize;
+use tauri::ipc::Channel;
+
+use crate::error::{Error, Result};
+
+/// Maximum size of one streamed chunk in bytes.
+pub(crate) const CHUNK_BYTES: usize = 256 * 1024;
+/// Maximum number of unfinished imports per window.
+const MAX_UPLOADS_PER_WINDOW: usize = 4;
+
+/// One piece of a streamed `load` / `export_config` response.  The pieces
+/// concatenated in `index` order form the response text; the final one has
+/// `last: true`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamChunk {
+    pub index: u32,
+    pub data: String,
+    pub last: bool,
+}
+
+/// Serializes `value` as JSON straight into `channel`, so the response is
+/// never held in memory as one string.
+pub(crate) fn send_json(channel: &Channel<StreamChunk>, value: &impl Serialize) -> Result<()> {
+    let mut writer = ChunkWriter::new(channel);
+    serde_json::to_writer(&mut writer, value).map_err(|e| stream_error(e.into()))?;
+    writer.finish()
+}
+
+fn stream_error(e: io::Error) -> Error {
+    Error::Storage(format!("failed to stream response: {}", e))
+}
+
+/// [`Write`] adapter that sends UTF-8 output through a channel as soon as a
+/// full chunk is buffered.  Chunks are split on character boundaries, and
+/// [`ChunkWriter::finish`] sends the remainder as the `last` chunk, so an
+/// empty output still ends the stream.
+pub(crate) struct ChunkWriter<'a> {
+    channel: &'a Channel<StreamChunk>,
+    buffer: Vec<u8>,
+    index: u32,
+}
+
+impl<'a> ChunkWriter<'a> {
+    pub(crate) fn new(channel: &'a Channel<StreamChunk>) -> Self {
+        Self {
+            channel,
+            buffer: Vec::new(),
+            index: 0,
+        }
+    }
+
+    /// Sends the buffered rest of the output and ends the stream.
+    pub(crate) fn finish(mut self) -> Result<()> {
+        let rest = std::mem::take(&mut self.buffer);
+        self.send(rest, true).map_err(stream_error)
+    }
+
+    fn send(&mut self, bytes: Vec<u8>, last: bool) -> io::Result<()> {
+        let data =
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.channel
+            .send(StreamChunk {
+                index: self.index,
+                data,
+                last,
+            })
+            .map_err(|e| io::Error::other(e.to_string()))?;
+        self.index += 1;
+        Ok(())
+    }
+}
+
+impl Write for ChunkWriter<'_> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(bytes);
+        // A full chunk is only sent once more output follows, so the final
+        // chunk is never empty unless the whole output is.
+        while self.buffer.len() > CHUNK_BYTES {
+            let mut end = CHUNK_BYTES;
+            // Back off continuation bytes (`0b10xx_xxxx`) to a char boundary.
+            while end > 0 && self.buffer[end] & 0xC0 == 0x80 {
+                end -= 1;
+            }
+            let rest = self.buffer.split_off(end);
+            let chunk = std::mem::replace(&mut self.buffer, rest);
+            self.send(chunk, false)?;
+        }
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Upload {
+    window: String,
+    content: String,
+}
+
+/// Import content received through `import_chunk`, in Tauri state.  An
+/// upload belongs to the window that started it and is dropped when that
+/// window closes.
+#[derive(Default)]
+pub(crate) struct ImportUploads {
+    uploads: Mutex<HashMap<String, Upload>>,
+}
+
+impl ImportUploads {
+    /// Appends `chunk` to upload `id`, or starts a new upload when `id` is
+    /// `None`, and returns the upload id.  An upload that would exceed
+    /// `max_bytes` is discarded.
+    pub(crate) fn append(
+        &self,
+        window: &str,
+        id: Option<&str>,
+        chunk: &str,
+        max_bytes: usize,
+    ) -> Result<String> {
+        let mut uploads = self.lock();
+        let id = match id {
+            Some(id) => {
+                uploads
+                    .get(id)
+                    .filter(|upload| upload.window == window)
+                    .ok_or_else(|| unknown_upload(id))?;
+                id.to_string()
+            }
+            None => {
+                let pending = uploads.values().filter(|u| u.window == window).count();
+                if pending >= MAX_UPLOADS_PER_WINDOW {
+                    return Err(Error::InvalidPayload(format!(
+                        "too many unfinished imports (at most {})",
+                        MAX_UPLOADS_PER_WINDOW
+                    )));
+                }
+                let id = new_upload_id();
+                uploads.insert(
+                    id.clone(),
+                    Upload {
+                        window: window.to_string(),
+                        content: String::new(),
+                    },
+                );
+                id
+            }
+        };
+
+        let upload = uploads.get_mut(&id).expect("upload was checked above");
+        if upload.content.len().saturating_add(chunk.len()) > max_bytes {
+            uploads.remove(&id);
+            return Err(Error::InvalidPayload(format!(
+                "import content exceeds maximum size of {} bytes",
+                max_bytes
+            )));
+        }
+        upload.content.push_str(chunk);
+        Ok(id)
+    }
+
+    /// Removes upload `id` and returns its content.
+    pub(crate) fn take(&self, window: &str, id: &str) -> Result<String> {
+        let mut uploads = self.lock();
+        match uploads.get(id) {
+            Some(upload) if upload.window == window => {
+                Ok(uploads.remove(id).map(|upload| upload.content).unwrap_or_default())
+            }
+            _ => Err(unknown_upload(id)),
+        }
+    }
+
+    /// Drops the unfinished uploads of a closed window.
+    pub(crate) fn discard_window(&self, window: &str) {
+        self.lock().retain(|_, upload| upload.window != window);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Upload>> {
+        self.uploads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn unknown_upload(id: &str) -> Error {
+    Error::InvalidPayload(format!("unknown import upload '{}'", id))
+}
+
+fn new_upload_id() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tauri::ipc::InvokeResponseBody;
+
+    fn collecting_channel() -> (Channel<StreamChunk>, Arc<Mutex<Vec<serde_json::Value>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let channel = Channel::new(move |body| {
+            if let InvokeResponseBody::Json(json) = body {
+                sink.lock().unwrap().push(serde_json::from_str(&json).unwrap());
+            }
+            Ok(())
+        });
+        (channel, received)
+    }
+
+    fn send_text(channel: &Channel<StreamChunk>, text: &str) {
+        let mut writer = ChunkWriter::new(channel);
+        writer.write_all(text.as_bytes()).unwrap();
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn text_is_streamed_in_bounded_chunks_on_char_boundaries() {
+        let (channel, received) = collecting_channel();
+        // Multi-byte characters straddle every chunk boundary.
+        let text = "é".repeat(CHUNK_BYTES);
+        send_text(&channel, &text);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        let mut joined = String::new();
+        for (index, chunk) in received.iter().enumerate() {
+            assert_eq!(chunk["index"], index);
+            assert_eq!(chunk["last"], index == received.len() - 1);
+            let data = chunk["data"].as_str().unwrap();
+            assert!(data.len() <= CHUNK_BYTES);
+            joined.push_str(data);
+        }
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn json_is_streamed_while_it_is_serialized() {
+        let (channel, received) = collecting_channel();
+        let value = serde_json::json!({ "items": vec!["ü".repeat(1000); 600] });
+        send_json(&channel, &value).unwrap();
+
+        let received = received.lock().unwrap();
+        assert!(received.len() > 1);
+        let joined: String = received
+            .iter()
+            .map(|chunk| {
+                let data = chunk["data"].as_str().unwrap();
+                assert!(data.len() <= CHUNK_BYTES);
+                data
+            })
+            .collect();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&joined).unwrap(),
+            value
+        );
+        assert_eq!(received.last().unwrap()["last"], true);
+    }
+
+    #[test]
+    fn empty_text_still_ends_the_stream() {
+        let (channel, received) = collecting_channel();
+        send_text(&channel, "");
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["last"], true);
+    }
+
+    #[test]
+    fn uploads_enforce_the_limit_per_chunk_and_belong_to_their_window() {
+        let uploads = ImportUploads::default();
+        let id = uploads.append("main", None, "{\"a\":", 10).unwrap();
+        uploads.append("main", Some(&id), "1}", 10).unwrap();
+        assert!(uploads.take("other", &id).is_err());
+        assert_eq!(uploads.take("main", &id).unwrap(), "{\"a\":1}");
+        assert!(uploads.take("main", &id).is_err());
+
+        let id = uploads.append("main", None, "12345", 10).unwrap();
+        let err = uploads.append("main", Some(&id), "678901", 10).unwrap_err();
+        assert!(err.to_string().contains("maximum size"));
+        // The oversized upload is gone.
+        assert!(uploads.append("main", Some(&id), "", 10).is_err());
+
+        for _ in 0..MAX_UPLOADS_PER_WINDOW {
+            uploads.append("main", None, "", 10).unwrap();
+        }
+        assert!(uploads.append("main", None, "", 10).is_err());
+        uploads.discard_window("main");
+        uploads.append("main", None, "", 10).unwrap();
+    }
+}
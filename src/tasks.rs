@@ -0,0 +1,368 @@
+/// Runs command bodies off the async executor.
+///
+/// Commands do synchronous file system and keyring I/O, which can stall for a
+/// long time (a big file, a keyring unlock prompt).  Their bodies therefore
+/// run on the blocking thread pool, so other IPC calls keep being served.
+/// Waiting for a per-file lock happens on the async side and is abandoned
+/// with error kind `cancelled` when the invoking window is closed; work that
+/// already started on the blocking pool runs to completion so a write is
+/// never left half done.
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::hash::Hash;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+
+use tauri::{Manager, Runtime, Webview};
+use tokio::sync::{watch, Semaphore};
+
+use crate::error::{Error, Result};
+use crate::locker::{FileGuard, FileLock};
+
+/// Cancellation signals of the windows that invoked commands, in Tauri state.
+#[derive(Default)]
+pub(crate) struct TaskRegistry {
+    windows: Mutex<HashMap<String, watch::Sender<bool>>>,
+}
+
+impl TaskRegistry {
+    fn token(&self, window: &str) -> CancelToken {
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let sender = windows
+            .entry(window.to_string())
+            .or_insert_with(|| watch::channel(false).0);
+        CancelToken(Some(sender.subscribe()))
+    }
+
+    /// Cancels the pending commands of `window`; called when it is destroyed.
+    pub(crate) fn cancel(&self, window: &str) {
+        let sender = self
+            .windows
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(window);
+        if let Some(sender) = sender {
+            sender.send_replace(true);
+        }
+    }
+}
+
+/// Cancellation signal of one command invocation.
+#[derive(Clone)]
+pub(crate) struct CancelToken(Option<watch::Receiver<bool>>);
+
+impl CancelToken {
+    /// A token that is never cancelled, for calls not tied to a window.
+    pub(crate) fn none() -> Self {
+        Self(None)
+    }
+
+    /// The token of the window hosting `webview`.
+    pub(crate) fn for_webview<R: Runtime>(webview: &Webview<R>) -> Self {
+        match webview.try_state::<TaskRegistry>() {
+            Some(registry) => registry.token(webview.window().label()),
+            None => Self::none(),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.as_ref().is_some_and(|rx| *rx.borrow())
+    }
+
+    /// Fails with error kind `cancelled` once the window has been closed.
+    pub(crate) fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled(
+                "the invoking window was closed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Waits for `lock` unless the window is closed first.
+    pub(crate) async fn lock(&self, lock: FileLock) -> Result<FileGuard> {
+        let Some(rx) = &self.0 else {
+            return Ok(lock.lock_owned().await);
+        };
+        let mut rx = rx.clone();
+        let mut acquire = pin!(lock.lock_owned());
+        // A dropped sender (app shutting down) counts as cancelled as well.
+        let mut cancelled = pin!(async move {
+            let _ = rx.wait_for(|cancelled| *cancelled).await;
+        });
+        poll_fn(|cx| {
+            if let Poll::Ready(guard) = acquire.as_mut().poll(cx) {
+                return Poll::Ready(Ok(guard));
+            }
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(Error::Cancelled(
+                    "the invoking window was closed".to_string(),
+                )));
+            }
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+/// Runs `f` on the blocking thread pool.
+pub(crate) async fn blocking<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| Error::Storage(format!("blocking task failed: {}", e)))?
+}
+
+/// Runs `f` on the blocking thread pool while holding every lock in `locks`
+/// (an `Option` for a single file).  Locks are taken in the order given, so
+/// callers holding several must pass them in a consistent order.
+pub(crate) async fn run_locked<T, F>(
+    locks: impl IntoIterator<Item = FileLock>,
+    cancel: &CancelToken,
+    f: F,
+) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let mut guards = Vec::new();
+    for lock in locks {
+        guards.push(cancel.lock(lock).await?);
+    }
+    cancel.check()?;
+    blocking(move || {
+        let _guards = guards;
+        f()
+    })
+    .await
+}
+
+/// Runs `run` for every item and returns the outputs in input order.
+///
+/// Items with the same key run one after another in input order; items
+/// without a key run on their own.  Up to `parallelism` such groups run at
+/// the same time.
+pub(crate) async fn run_grouped<K, I, T, F, Fut>(
+    items: Vec<(Option<K>, I)>,
+    parallelism: usize,
+    run: F,
+) -> Result<Vec<T>>
+where
+    K: Hash + Eq,
+    I: Send + 'static,
+    T: Send + 'static,
+    F: Fn(I) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+{
+    let mut groups: Vec<Vec<(usize, I)>> = Vec::new();
+    let mut group_of_key = HashMap::new();
+    for (index, (key, item)) in items.into_iter().enumerate() {
+        let group = match key {
+            Some(key) => *group_of_key.entry(key).or_insert(groups.len()),
+            None => groups.len(),
+        };
+        if group == groups.len() {
+            groups.push(Vec::new());
+        }
+        groups[group].push((index, item));
+    }
+
+    let permits = Arc::new(Semaphore::new(parallelism.max(1)));
+    let tasks: Vec<_> = groups
+        .into_iter()
+        .map(|group| {
+            let (run, permits) = (run.clone(), Arc::clone(&permits));
+            tauri::async_runtime::spawn(async move {
+                // The semaphore is never closed, so acquiring cannot fail.
+                let _permit = permits.acquire_owned().await;
+                let mut outputs = Vec::with_capacity(group.len());
+                for (index, item) in group {
+                    outputs.push((index, run(item).await));
+                }
+                outputs
+            })
+        })
+        .collect();
+
+    let mut outputs = Vec::new();
+    for task in tasks {
+        outputs.extend(
+            task.await
+                .map_err(|e| Error::Storage(format!("batch task failed: {}", e)))?,
+        );
+    }
+    outputs.sort_by_key(|(index, _)| *index);
+    Ok(outputs.into_iter().map(|(_, output)| output).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locker::FileLockRegistry;
+    use crate::models::NormalizedProvider;
+    use crate::storage;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+    use std::sync::{Arc, Condvar};
+    use std::time::Duration;
+
+    /// Blocks until `count` callers are inside at the same time, or times out.
+    #[derive(Default)]
+    struct Rendezvous {
+        arrived: Mutex<usize>,
+        all_in: Condvar,
+    }
+
+    impl Rendezvous {
+        fn meet(&self, count: usize) -> bool {
+            let mut arrived = self.arrived.lock().unwrap();
+            *arrived += 1;
+            self.all_in.notify_all();
+            let (arrived, timeout) = self
+                .all_in
+                .wait_timeout_while(arrived, Duration::from_secs(5), |n| *n < count)
+                .unwrap();
+            drop(arrived);
+            !timeout.timed_out()
+        }
+    }
+
+    fn load(
+        registry: &FileLockRegistry,
+        path: PathBuf,
+        rendezvous: Arc<Rendezvous>,
+    ) -> tauri::async_runtime::JoinHandle<Result<(Value, bool)>> {
+        let lock = registry.acquire(path.clone());
+        tauri::async_runtime::spawn(async move {
+            run_locked(Some(lock), &CancelToken::none(), move || {
+                let met = rendezvous.meet(2);
+                let backend = storage::file_backend_for(
+                    &NormalizedProvider::Json,
+                    false,
+                    storage::read_only_registry(),
+                    1024,
+                )?;
+                Ok((backend.read(&path)?, met))
+            })
+            .await
+        })
+    }
+
+    #[test]
+    fn loads_of_different_files_run_in_parallel() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (a, b) = (dir.path().join("a.json"), dir.path().join("b.json"));
+        std::fs::write(&a, r#"{"file":"a"}"#).unwrap();
+        std::fs::write(&b, r#"{"file":"b"}"#).unwrap();
+        let registry = FileLockRegistry::new();
+        let rendezvous = Arc::new(Rendezvous::default());
+
+        let first = load(&registry, a, rendezvous.clone());
+        let second = load(&registry, b, rendezvous);
+        let (first, second) = tauri::async_runtime::block_on(async {
+            (first.await.unwrap(), second.await.unwrap())
+        });
+
+        // Each load only finishes promptly if the other one is running too.
+        assert_eq!(first.unwrap(), (json!({"file": "a"}), true));
+        assert_eq!(second.unwrap(), (json!({"file": "b"}), true));
+    }
+
+    #[test]
+    fn waiting_for_a_lock_is_cancelled_when_the_window_closes() {
+        let registry = FileLockRegistry::new();
+        let tasks = TaskRegistry::default();
+        let lock = registry.acquire(PathBuf::from("busy.json"));
+        let cancel = tasks.token("main");
+
+        let result = tauri::async_runtime::block_on(async {
+            let _held = lock.clone().lock_owned().await;
+            let waiting = tauri::async_runtime::spawn(async move {
+                run_locked(Some(lock), &cancel, || Ok(())).await
+            });
+            tasks.cancel("main");
+            waiting.await.unwrap()
+        });
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+
+        // Other windows are unaffected.
+        tasks.token("other").check().unwrap();
+    }
+
+    #[test]
+    fn every_lock_is_held_while_the_body_runs() {
+        let registry = FileLockRegistry::new();
+        let locks = vec![
+            registry.acquire(PathBuf::from("a.json")),
+            registry.acquire(PathBuf::from("b.json")),
+        ];
+        let probe = locks.clone();
+        let held = tauri::async_runtime::block_on(run_locked(
+            locks,
+            &CancelToken::none(),
+            move || Ok(probe.iter().all(|lock| lock.try_lock().is_err())),
+        ))
+        .unwrap();
+        assert!(held);
+    }
+
+    #[test]
+    fn grouped_items_keep_order_per_key_within_the_parallelism_bound() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let items = vec![
+            (Some("a"), ("a", 1)),
+            (Some("b"), ("b", 1)),
+            (Some("a"), ("a", 2)),
+            (None, ("-", 1)),
+            (Some("c"), ("c", 1)),
+            (Some("a"), ("a", 3)),
+        ];
+
+        let outputs = tauri::async_runtime::block_on(run_grouped(items, 2, {
+            let (running, peak, log) = (running.clone(), peak.clone(), log.clone());
+            move |(key, n): (&'static str, u32)| {
+                let (running, peak, log) = (running.clone(), peak.clone(), log.clone());
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    blocking(move || {
+                        std::thread::sleep(Duration::from_millis(20));
+                        log.lock().unwrap().push((key, n));
+                        Ok(())
+                    })
+                    .await
+                    .unwrap();
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    format!("{}{}", key, n)
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(outputs, ["a1", "b1", "a2", "-1", "c1", "a3"]);
+        let log = log.lock().unwrap();
+        let a: Vec<u32> = log.iter().filter(|(k, _)| *k == "a").map(|(_, n)| *n).collect();
+        assert_eq!(a, [1, 2, 3]);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn grouped_items_with_distinct_keys_run_in_parallel() {
+        let rendezvous = Arc::new(Rendezvous::default());
+        let items = vec![(Some(1), ()), (Some(2), ()), (Some(3), ())];
+        let met = tauri::async_runtime::block_on(run_grouped(items, 3, move |()| {
+            let rendezvous = rendezvous.clone();
+            blocking(move || Ok(rendezvous.meet(3)))
+        }))
+        .unwrap();
+        assert!(met.into_iter().all(|met| met.unwrap()));
+    }
+}
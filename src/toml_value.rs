@@ -0,0 +1,347 @@
+/// Lossless conversion between TOML documents and config data.
+///
+/// TOML values that JSON (or a JavaScript number) cannot hold are tagged:
+///
+/// - datetimes of all four kinds become `{"$datetime": "<TOML text>"}`, e.g.
+///   `"1979-05-27T07:32:00Z"` (offset datetime), `"1979-05-27T07:32:00"`
+///   (local datetime), `"1979-05-27"` (local date) or `"07:32:00"` (local
+///   time); the text decides the kind on write;
+/// - integers outside ±(2^53 − 1), which JavaScript numbers cannot represent
+///   exactly, become `{"$integer": "<decimal>"}`;
+/// - `inf`, `-inf` and `nan` become `{"$float": "inf" | "-inf" | "nan"}`.
+///
+/// Other values map directly.  JavaScript cannot tell `1.0` from `1`, so a
+/// plain number written where the existing document holds a float stays a
+/// float; `{"$float": 1}` forces a float and `{"$integer": …}` an integer.
+/// `null` object fields are omitted because TOML has no null type, and `null`
+/// array elements are rejected.
+use std::str::FromStr;
+
+use serde_json::{Map, Number, Value};
+
+use crate::error::{Error, Result};
+
+/// Object key marking a TOML datetime, local date or local time.
+pub(crate) const DATETIME_KEY: &str = "$datetime";
+/// Object key marking an integer JavaScript cannot represent exactly.
+pub(crate) const INTEGER_KEY: &str = "$integer";
+/// Object key marking a float that must not be written as an integer.
+pub(crate) const FLOAT_KEY: &str = "$float";
+
+/// Key `toml` uses when a datetime is serialized through serde.  Data loaded
+/// before datetimes were tagged carries it, so it is still accepted on write.
+const SERDE_DATETIME_KEY: &str = "$__toml_private_datetime";
+
+/// Largest integer a JavaScript number holds exactly (2^53 − 1).
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+/// Parses a TOML document into config data.
+pub(crate) fn parse(text: &str) -> Result<Value> {
+    Ok(to_json(parse_document(text)?))
+}
+
+/// Renders config data as a TOML document.  Numbers keep the float kind of
+/// the matching values in `existing`, when given.
+pub(crate) fn render(value: &Value, existing: Option<&toml::Value>) -> Result<String> {
+    let toml_val = from_json(value, existing)?;
+    if !matches!(toml_val, toml::Value::Table(_)) {
+        return Err(Error::Storage(
+            "TOML top-level value must be a table (object)".to_string(),
+        ));
+    }
+    toml::to_string_pretty(&toml_val).map_err(|e| Error::Storage(e.to_string()))
+}
+
+/// Parses `text` into a `toml::Value`, for use as the `existing` document.
+pub(crate) fn parse_document(text: &str) -> Result<toml::Value> {
+    toml::from_str(text).map_err(|e| Error::Storage(e.to_string()))
+}
+
+fn to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Integer(i) if (-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&i) => {
+            Value::from(i)
+        }
+        toml::Value::Integer(i) => tagged(INTEGER_KEY, Value::String(i.to_string())),
+        toml::Value::Float(f) => match Number::from_f64(f) {
+            Some(n) => Value::Number(n),
+            None => tagged(FLOAT_KEY, Value::String(non_finite_text(f).to_string())),
+        },
+        toml::Value::Datetime(datetime) => {
+            tagged(DATETIME_KEY, Value::String(datetime.to_string()))
+        }
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(to_json).collect()),
+        toml::Value::Table(table) => {
+            Value::Object(table.into_iter().map(|(k, v)| (k, to_json(v))).collect())
+        }
+    }
+}
+
+fn from_json(value: &Value, existing: Option<&toml::Value>) -> Result<toml::Value> {
+    match value {
+        Value::Null => Err(Error::Storage(
+            "TOML does not support null values in this position".to_string(),
+        )),
+        Value::Bool(b) => Ok(toml::Value::Boolean(*b)),
+        Value::Number(n) => {
+            let keep_float = matches!(existing, Some(toml::Value::Float(_)));
+            if let Some(i) = n.as_i64() {
+                Ok(match keep_float {
+                    true => toml::Value::Float(i as f64),
+                    false => toml::Value::Integer(i),
+                })
+            } else if let Some(u) = n.as_u64() {
+                Err(Error::Storage(format!(
+                    "TOML cannot represent unsigned integer {} (exceeds i64::MAX)",
+                    u
+                )))
+            } else {
+                n.as_f64()
+                    .filter(|f| f.is_finite())
+                    .map(toml::Value::Float)
+                    .ok_or_else(|| {
+                        Error::Storage("TOML does not support this numeric value".to_string())
+                    })
+            }
+        }
+        Value::String(s) => Ok(toml::Value::String(s.clone())),
+        Value::Array(arr) => {
+            let mut items = Vec::with_capacity(arr.len());
+            for (index, value) in arr.iter().enumerate() {
+                if value.is_null() {
+                    return Err(Error::Storage(
+                        "TOML does not support null values inside arrays".to_string(),
+                    ));
+                }
+                let hint = match existing {
+                    Some(toml::Value::Array(items)) => items.get(index),
+                    _ => None,
+                };
+                items.push(from_json(value, hint)?);
+            }
+            Ok(toml::Value::Array(items))
+        }
+        Value::Object(map) => {
+            if let Some(tagged) = from_tagged(map)? {
+                return Ok(tagged);
+            }
+            let mut toml_map = toml::map::Map::new();
+            for (k, v) in map {
+                if v.is_null() {
+                    continue;
+                }
+                let hint = match existing {
+                    Some(toml::Value::Table(table)) => table.get(k),
+                    _ => None,
+                };
+                toml_map.insert(k.clone(), from_json(v, hint)?);
+            }
+            Ok(toml::Value::Table(toml_map))
+        }
+    }
+}
+
+/// The TOML value of a single-key tagged object, if `map` is one.
+fn from_tagged(map: &Map<String, Value>) -> Result<Option<toml::Value>> {
+    let mut entries = map.iter();
+    let (Some((key, value)), None) = (entries.next(), entries.next()) else {
+        return Ok(None);
+    };
+    let invalid = |expected: &str| {
+        Err(Error::InvalidPayload(format!(
+            "'{}' must be {}, got {}",
+            key, expected, value
+        )))
+    };
+    match key.as_str() {
+        DATETIME_KEY | SERDE_DATETIME_KEY => {
+            let Value::String(text) = value else {
+                return invalid("a TOML datetime string");
+            };
+            match toml::value::Datetime::from_str(text) {
+                Ok(datetime) => Ok(Some(toml::Value::Datetime(datetime))),
+                Err(_) => invalid("a TOML datetime, local date or local time"),
+            }
+        }
+        INTEGER_KEY => {
+            let parsed = match value {
+                Value::String(text) => text.parse::<i64>().ok(),
+                Value::Number(n) => n.as_i64(),
+                _ => None,
+            };
+            match parsed {
+                Some(i) => Ok(Some(toml::Value::Integer(i))),
+                None => invalid("a decimal integer within the 64-bit signed range"),
+            }
+        }
+        FLOAT_KEY => {
+            let parsed = match value {
+                Value::String(text) => match text.as_str() {
+                    "inf" | "+inf" => Some(f64::INFINITY),
+                    "-inf" => Some(f64::NEG_INFINITY),
+                    "nan" | "+nan" | "-nan" => Some(f64::NAN),
+                    text => text.parse::<f64>().ok(),
+                },
+                Value::Number(n) => n.as_f64(),
+                _ => None,
+            };
+            match parsed {
+                Some(f) => Ok(Some(toml::Value::Float(f))),
+                None => invalid("a number, \"inf\", \"-inf\" or \"nan\""),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn tagged(key: &str, value: Value) -> Value {
+    let mut map = Map::new();
+    map.insert(key.to_string(), value);
+    Value::Object(map)
+}
+
+fn non_finite_text(f: f64) -> &'static str {
+    if f.is_nan() {
+        "nan"
+    } else if f.is_sign_negative() {
+        "-inf"
+    } else {
+        "inf"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DOCUMENT: &str = r#"title = "TOML"
+enabled = true
+port = 8080
+negative = -17
+big = 9007199254740993
+min = -9223372036854775808
+ratio = 1.0
+half = 0.5
+infinite = inf
+negative_infinite = -inf
+not_a_number = nan
+offset = 1979-05-27T07:32:00.999-07:00
+local_datetime = 1979-05-27T07:32:00
+local_date = 1979-05-27
+local_time = 07:32:00
+mixed = [1, 2.0, "three"]
+
+[server]
+host = "localhost"
+
+[[plugins]]
+name = "a"
+"#;
+
+    fn parsed() -> Value {
+        parse(DOCUMENT).unwrap()
+    }
+
+    #[test]
+    fn strings_booleans_tables_and_arrays_map_directly() {
+        let value = parsed();
+        assert_eq!(value["title"], json!("TOML"));
+        assert_eq!(value["enabled"], json!(true));
+        assert_eq!(value["server"], json!({"host": "localhost"}));
+        assert_eq!(value["plugins"], json!([{"name": "a"}]));
+        assert_eq!(value["mixed"], json!([1, 2.0, "three"]));
+        assert!(value["mixed"][1].is_f64());
+    }
+
+    #[test]
+    fn integers_outside_the_safe_range_are_tagged() {
+        let value = parsed();
+        assert_eq!(value["port"], json!(8080));
+        assert_eq!(value["negative"], json!(-17));
+        assert_eq!(value["big"], json!({"$integer": "9007199254740993"}));
+        assert_eq!(value["min"], json!({"$integer": "-9223372036854775808"}));
+    }
+
+    #[test]
+    fn floats_keep_their_kind_and_non_finite_values_are_tagged() {
+        let value = parsed();
+        assert!(value["ratio"].is_f64());
+        assert_eq!(value["ratio"], json!(1.0));
+        assert_eq!(value["half"], json!(0.5));
+        assert_eq!(value["infinite"], json!({"$float": "inf"}));
+        assert_eq!(value["negative_infinite"], json!({"$float": "-inf"}));
+        assert_eq!(value["not_a_number"], json!({"$float": "nan"}));
+    }
+
+    #[test]
+    fn every_datetime_kind_is_tagged_with_its_text() {
+        let value = parsed();
+        assert_eq!(
+            value["offset"],
+            json!({"$datetime": "1979-05-27T07:32:00.999-07:00"})
+        );
+        assert_eq!(
+            value["local_datetime"],
+            json!({"$datetime": "1979-05-27T07:32:00"})
+        );
+        assert_eq!(value["local_date"], json!({"$datetime": "1979-05-27"}));
+        assert_eq!(value["local_time"], json!({"$datetime": "07:32:00"}));
+    }
+
+    #[test]
+    fn rendered_documents_read_back_unchanged() {
+        let value = parsed();
+        let text = render(&value, None).unwrap();
+        assert_eq!(parse(&text).unwrap(), value, "{}", text);
+        for line in [
+            "big = 9007199254740993",
+            "ratio = 1.0",
+            "infinite = inf",
+            "local_date = 1979-05-27",
+            "local_time = 07:32:00",
+        ] {
+            assert!(text.contains(line), "{} missing from\n{}", line, text);
+        }
+    }
+
+    #[test]
+    fn plain_numbers_keep_the_existing_float_kind() {
+        let existing = parse_document(DOCUMENT).unwrap();
+        // What the webview sends back after a load and an edit.
+        let mut value = parsed();
+        value["ratio"] = json!(2);
+        value["port"] = json!(9090);
+        value["mixed"] = json!([1, 3, "three"]);
+        value["half"] = json!({"$integer": "1"});
+        value["added"] = json!({"$float": 4});
+
+        let text = render(&value, Some(&existing)).unwrap();
+        let reread = parse(&text).unwrap();
+        assert_eq!(reread["ratio"], json!(2.0));
+        assert!(reread["ratio"].is_f64());
+        assert!(reread["port"].is_i64());
+        assert!(reread["mixed"][0].is_i64());
+        assert!(reread["mixed"][1].is_f64());
+        assert!(reread["half"].is_i64());
+        assert!(reread["added"].is_f64());
+    }
+
+    #[test]
+    fn invalid_tags_and_out_of_range_values_are_rejected() {
+        for value in [
+            json!({"a": {"$datetime": "yesterday"}}),
+            json!({"a": {"$integer": "9223372036854775808"}}),
+            json!({"a": {"$float": "lots"}}),
+            json!({"a": u64::MAX}),
+            json!({"a": [1, null]}),
+        ] {
+            assert!(render(&value, None).is_err(), "{}", value);
+        }
+        // Objects that merely contain a tag key are ordinary tables.
+        let value = json!({"a": {"$integer": "1", "b": 2}});
+        assert_eq!(parse(&render(&value, None).unwrap()).unwrap(), value);
+    }
+}
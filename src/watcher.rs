@@ -103,6 +103,12 @@ impl WatcherState {
                     Err(_) => return,
                 };
 
+                if let Some(cache) = crate::config::read_cache(&app) {
+                    for event in &events {
+                        cache.invalidate(&event.path);
+                    }
+                }
+
                 let emitted_events = {
                     let registry = callback_registry.lock().unwrap_or_else(|e| e.into_inner());
                     events
@@ -167,6 +173,7 @@ mod tests {
             file_name: "settings.json".into(),
             operation: "external_change".into(),
             target_id: target_id.into(),
+            path: None,
         }
     }
 
@@ -0,0 +1,592 @@
+/// Simple XML documents.
+///
+/// The config is the content of the document's root element, mapped to JSON
+/// as follows:
+///
+/// - An element without attributes or child elements is a scalar.  Its text
+///   follows the `keyvalue` typing rules (`true` / `false`, plain decimal
+///   numbers, otherwise a string); text in a `<![CDATA[…]]>` section is
+///   always a string, and an empty element is `""`.
+/// - Any other element is an object: attributes become `"@name"` keys with
+///   string values, child elements become keys by name, and non-blank text
+///   becomes `"#text"` (trimmed, typed like a scalar).  Repeated child
+///   elements become an array in document order.
+/// - Comments, processing instructions and a `<!DOCTYPE>` without internal
+///   subset are skipped, so only the predefined entities and character
+///   references are known.  Namespace prefixes are kept as part of the name.
+///   Parsing is done by `quick-xml`; this module only maps the elements.
+///
+/// On write, `null` values are omitted, arrays become repeated elements
+/// (nested arrays are rejected, and a one-element array reads back as its
+/// element), attribute values are written as text, and strings that would
+/// read back as something else are wrapped in CDATA.  Two shapes do not
+/// survive a round trip: an empty object is written as an empty element and
+/// reads back as `""`, and `"#text"` next to attributes or children reads
+/// back trimmed.  Control characters other than tab, line feed and carriage
+/// return cannot be represented in XML 1.0 and are rejected.  Writes
+/// regenerate the document; only the root element's name is kept from an
+/// existing file.
+use quick_xml::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::{Reader, XmlVersion};
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+use crate::keyvalue;
+
+/// Root element name used when there is no existing document.
+const DEFAULT_ROOT: &str = "config";
+/// Object key holding an element's text next to attributes or children.
+const TEXT_KEY: &str = "#text";
+/// Prefix marking attribute keys.
+const ATTRIBUTE_PREFIX: char = '@';
+/// Guards the recursive JSON mapping against deeply nested input.
+const MAX_DEPTH: usize = 128;
+
+/// Parses an XML document into a JSON value (see the module docs).
+pub(crate) fn parse(text: &str) -> Result<Value> {
+    let root = read_document(text)?;
+    Ok(match element_to_value(&root) {
+        Value::Object(map) => Value::Object(map),
+        Value::String(s) if s.trim().is_empty() => Value::Object(Map::new()),
+        text => {
+            let mut map = Map::new();
+            map.insert(TEXT_KEY.to_string(), text);
+            Value::Object(map)
+        }
+    })
+}
+
+/// Renders `value` as a new XML document with a `<config>` root.
+pub(crate) fn render(value: &Value) -> Result<String> {
+    render_with_root(value, DEFAULT_ROOT)
+}
+
+/// Renders `value` using the root element name of the `existing` document.
+pub(crate) fn rewrite(existing: &str, value: &Value) -> Result<String> {
+    let root = read_document(existing)?;
+    render_with_root(value, &root.name)
+}
+
+fn render_with_root(value: &Value, root: &str) -> Result<String> {
+    let Value::Object(map) = value else {
+        return Err(Error::Storage(
+            "XML top-level value must be an object".to_string(),
+        ));
+    };
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    write_element(&mut out, root, map, 0)?;
+    Ok(out)
+}
+
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+enum Node {
+    Element(Element),
+    Text(String),
+    CData(String),
+}
+
+fn element_to_value(element: &Element) -> Value {
+    let has_elements = element
+        .children
+        .iter()
+        .any(|node| matches!(node, Node::Element(_)));
+    if element.attributes.is_empty() && !has_elements {
+        return text_value(&element.children, false);
+    }
+
+    let mut map = Map::new();
+    for (name, value) in &element.attributes {
+        map.insert(
+            format!("{}{}", ATTRIBUTE_PREFIX, name),
+            Value::String(value.clone()),
+        );
+    }
+    for node in &element.children {
+        let Node::Element(child) = node else { continue };
+        let value = element_to_value(child);
+        match map.get_mut(&child.name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                map.insert(child.name.clone(), value);
+            }
+        }
+    }
+    let text = text_value(&element.children, true);
+    if text.as_str().is_none_or(|s| !s.is_empty()) {
+        map.insert(TEXT_KEY.to_string(), text);
+    }
+    Value::Object(map)
+}
+
+/// The typed text of `nodes`, ignoring child elements.  Text next to
+/// attributes or children is `trim`med of its indentation.
+fn text_value(nodes: &[Node], trim: bool) -> Value {
+    let mut text = String::new();
+    let mut literal = false;
+    for node in nodes {
+        match node {
+            Node::Text(s) => text.push_str(s),
+            Node::CData(s) => {
+                text.push_str(s);
+                literal = true;
+            }
+            Node::Element(_) => {}
+        }
+    }
+    let text = if trim { text.trim() } else { text.as_str() };
+    if literal {
+        Value::String(text.to_string())
+    } else {
+        keyvalue::typed(text)
+    }
+}
+
+fn write_element(
+    out: &mut String,
+    name: &str,
+    map: &Map<String, Value>,
+    depth: usize,
+) -> Result<()> {
+    validate_name(name)?;
+    let indent = "  ".repeat(depth);
+    out.push_str(&indent);
+    out.push('<');
+    out.push_str(name);
+
+    let mut text = None;
+    let mut children = Vec::new();
+    for (key, value) in map {
+        if value.is_null() {
+            continue;
+        }
+        if key == TEXT_KEY {
+            text = Some(value);
+        } else if let Some(attribute) = key.strip_prefix(ATTRIBUTE_PREFIX) {
+            validate_name(attribute)?;
+            let value = match value {
+                Value::String(s) => s.clone(),
+                Value::Bool(_) | Value::Number(_) => value.to_string(),
+                _ => {
+                    return Err(Error::Storage(format!(
+                        "XML attribute '{}' must be a scalar",
+                        key
+                    )))
+                }
+            };
+            validate_chars(key, &value)?;
+            out.push(' ');
+            out.push_str(attribute);
+            out.push_str("=\"");
+            out.push_str(&escape(&value, true));
+            out.push('"');
+        } else {
+            children.push((key, value));
+        }
+    }
+
+    if children.is_empty() {
+        match text {
+            Some(text) => {
+                out.push('>');
+                write_text(out, TEXT_KEY, text)?;
+                out.push_str("</");
+                out.push_str(name);
+                out.push_str(">\n");
+            }
+            None => out.push_str("/>\n"),
+        }
+        return Ok(());
+    }
+
+    out.push_str(">\n");
+    if let Some(text) = text {
+        out.push_str(&indent);
+        out.push_str("  ");
+        write_text(out, TEXT_KEY, text)?;
+        out.push('\n');
+    }
+    for (key, value) in children {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    match item {
+                        Value::Array(_) => {
+                            return Err(Error::Storage(format!(
+                                "XML cannot represent nested arrays: '{}'",
+                                key
+                            )))
+                        }
+                        Value::Null => {
+                            return Err(Error::Storage(format!(
+                                "XML does not support null values inside arrays: '{}'",
+                                key
+                            )))
+                        }
+                        _ => {}
+                    }
+                    write_child(out, key, item, depth + 1)?;
+                }
+            }
+            value => write_child(out, key, value, depth + 1)?,
+        }
+    }
+    out.push_str(&indent);
+    out.push_str("</");
+    out.push_str(name);
+    out.push_str(">\n");
+    Ok(())
+}
+
+fn write_child(out: &mut String, name: &str, value: &Value, depth: usize) -> Result<()> {
+    match value {
+        Value::Null => Ok(()),
+        Value::Object(map) => write_element(out, name, map, depth),
+        scalar => {
+            validate_name(name)?;
+            out.push_str(&"  ".repeat(depth));
+            out.push('<');
+            out.push_str(name);
+            out.push('>');
+            write_text(out, name, scalar)?;
+            out.push_str("</");
+            out.push_str(name);
+            out.push_str(">\n");
+            Ok(())
+        }
+    }
+}
+
+/// Writes a scalar as element text, using CDATA for strings that would not
+/// read back as the same string.
+fn write_text(out: &mut String, name: &str, value: &Value) -> Result<()> {
+    if let Value::String(s) = value {
+        validate_chars(name, s)?;
+    }
+    match value {
+        // Only number- and boolean-like strings get here, so they cannot
+        // contain `]]>`.
+        Value::String(s) if keyvalue::typed(s) != *value => {
+            out.push_str("<![CDATA[");
+            out.push_str(s);
+            out.push_str("]]>");
+        }
+        Value::String(s) => out.push_str(&escape(s, false)),
+        Value::Bool(_) | Value::Number(_) => out.push_str(&value.to_string()),
+        _ => {
+            return Err(Error::Storage(format!(
+                "XML text of '{}' must be a scalar",
+                name
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Rejects characters outside the XML 1.0 `Char` production; no escape or
+/// character reference can represent them.
+fn validate_chars(name: &str, text: &str) -> Result<()> {
+    let invalid = text.chars().find(|&c| {
+        matches!(
+            c,
+            '\u{0}'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}'
+        )
+    });
+    match invalid {
+        Some(c) => Err(Error::InvalidPayload(format!(
+            "XML cannot represent the character U+{:04X} in '{}'",
+            c as u32, name
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\r' => escaped.push_str("&#13;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            '\n' if attribute => escaped.push_str("&#10;"),
+            '\t' if attribute => escaped.push_str("&#9;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == ':'
+}
+
+fn is_name_char(c: char) -> bool {
+    is_name_start(c) || c.is_numeric() || c == '-' || c == '.'
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let mut chars = name.chars();
+    if chars.next().is_some_and(is_name_start) && chars.all(is_name_char) {
+        Ok(())
+    } else {
+        Err(Error::Storage(format!(
+            "'{}' is not a valid XML element or attribute name",
+            name
+        )))
+    }
+}
+
+/// Reads the root element of `text`.  Comments, processing instructions and
+/// the declaration are skipped; entities are limited to the predefined ones.
+fn read_document(text: &str) -> Result<Element> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut reader = Reader::from_str(text);
+    let mut open: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| syntax_error(text, reader.error_position(), e))?;
+        let here = reader.buffer_position();
+        let outside = || syntax_error(text, here, "unexpected content outside the root element");
+        match event {
+            Event::Start(start) => {
+                if open.len() >= MAX_DEPTH {
+                    return Err(syntax_error(
+                        text,
+                        here,
+                        format_args!("elements nest deeper than {}", MAX_DEPTH),
+                    ));
+                }
+                if root.is_some() {
+                    return Err(outside());
+                }
+                open.push(new_element(&start).map_err(|e| syntax_error(text, here, e))?);
+            }
+            Event::Empty(start) => {
+                let element = new_element(&start).map_err(|e| syntax_error(text, here, e))?;
+                close_element(&mut open, &mut root, element).map_err(|_| outside())?;
+            }
+            Event::End(_) => {
+                // The reader has already matched the end tag to its start tag.
+                let element = open.pop().ok_or_else(outside)?;
+                close_element(&mut open, &mut root, element).map_err(|_| outside())?;
+            }
+            Event::Text(raw) => match open.last_mut() {
+                Some(element) => push_text(element, &raw.xml10_content()),
+                None if raw.trim().is_empty() => {}
+                None => return Err(outside()),
+            },
+            Event::GeneralRef(reference) => {
+                let resolved = match reference
+                    .resolve_char_ref()
+                    .map_err(|e| syntax_error(text, here, e))?
+                {
+                    Some(c) => c.to_string(),
+                    None => escape::resolve_xml_entity(&reference)
+                        .ok_or_else(|| {
+                            syntax_error(
+                                text,
+                                here,
+                                format_args!("unknown entity '&{};'", &*reference),
+                            )
+                        })?
+                        .to_string(),
+                };
+                push_text(open.last_mut().ok_or_else(outside)?, &resolved);
+            }
+            Event::CData(raw) => open
+                .last_mut()
+                .ok_or_else(outside)?
+                .children
+                .push(Node::CData(raw.xml10_content().into_owned())),
+            Event::DocType(declaration) if declaration.contains('[') => {
+                return Err(syntax_error(
+                    text,
+                    here,
+                    "DTD internal subsets are not supported",
+                ));
+            }
+            Event::Eof => break,
+            Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::DocType(_) => {}
+        }
+    }
+    if let Some(element) = open.last() {
+        return Err(syntax_error(
+            text,
+            text.len() as u64,
+            format_args!("unclosed element <{}>", element.name),
+        ));
+    }
+    root.ok_or_else(|| syntax_error(text, 0, "expected a root element"))
+}
+
+fn new_element(start: &BytesStart<'_>) -> std::result::Result<Element, quick_xml::Error> {
+    let mut attributes = Vec::new();
+    for attribute in start.attributes() {
+        let attribute = attribute?;
+        let value = attribute.normalized_value(XmlVersion::Implicit1_0)?;
+        attributes.push((attribute.key.into_inner().to_string(), value.into_owned()));
+    }
+    Ok(Element {
+        name: start.name().into_inner().to_string(),
+        attributes,
+        children: Vec::new(),
+    })
+}
+
+/// Attaches a finished element to its parent, or makes it the root.  Fails
+/// when the document already has a root.
+fn close_element(
+    open: &mut [Element],
+    root: &mut Option<Element>,
+    element: Element,
+) -> std::result::Result<(), ()> {
+    match open.last_mut() {
+        Some(parent) => parent.children.push(Node::Element(element)),
+        None if root.is_none() => *root = Some(element),
+        None => return Err(()),
+    }
+    Ok(())
+}
+
+/// Appends text, joining it with the text node before it (entity references
+/// arrive separately from the text around them).
+fn push_text(element: &mut Element, text: &str) {
+    match element.children.last_mut() {
+        Some(Node::Text(existing)) => existing.push_str(text),
+        _ => element.children.push(Node::Text(text.to_string())),
+    }
+}
+
+fn syntax_error(text: &str, offset: u64, message: impl std::fmt::Display) -> Error {
+    let offset = usize::try_from(offset).map_or(text.len(), |o| o.min(text.len()));
+    let line = text.as_bytes()[..offset]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count()
+        + 1;
+    Error::Storage(format!("XML line {}: {}", line, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_attributes_children_and_text() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- written by another tool -->
+<!DOCTYPE settings>
+<settings version="2">
+  <name>My &amp; App</name>
+  <port>8080</port>
+  <zip><![CDATA[01234]]></zip>
+  <debug>FALSE</debug>
+  <empty/>
+  <window id="main" width="800">Main window</window>
+  <plugin>a</plugin>
+  <plugin>b</plugin>
+</settings>
+"#;
+        assert_eq!(
+            parse(text).unwrap(),
+            json!({
+                "@version": "2",
+                "name": "My & App",
+                "port": 8080,
+                "zip": "01234",
+                "debug": false,
+                "empty": "",
+                "window": {"@id": "main", "@width": "800", "#text": "Main window"},
+                "plugin": ["a", "b"],
+            })
+        );
+    }
+
+    #[test]
+    fn rendered_documents_read_back_with_the_same_values() {
+        let value = json!({
+            "title": "true",
+            "count": 3,
+            "ratio": 1.0,
+            "text": "a < b & \"c\"",
+            "tricky": "x]]>y",
+            "skipped": null,
+            "server": {"@host": "localhost", "tls": false, "paths": ["/a", "/b"]},
+        });
+        let text = render(&value).unwrap();
+        let mut expected = value.clone();
+        expected.as_object_mut().unwrap().remove("skipped");
+        assert_eq!(parse(&text).unwrap(), expected, "{}", text);
+        assert!(text.contains("<title><![CDATA[true]]></title>"));
+        assert!(text.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<config>\n"));
+    }
+
+    #[test]
+    fn rewrite_keeps_the_root_element_name() {
+        let text = rewrite("<prefs><a>1</a></prefs>", &json!({"a": 2})).unwrap();
+        assert_eq!(
+            text,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<prefs>\n  <a>2</a>\n</prefs>\n"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        for text in [
+            "<a><b></a>",
+            "<a>",
+            "<a x='1' x='2'/>",
+            "<a>&bogus;</a>",
+            "<!DOCTYPE a [<!ENTITY e 'x'>]><a/>",
+            "<a/><b/>",
+            "<a>&#0;</a>",
+            "<a x='&#0;'/>",
+            "<a>x & y</a>",
+            "text<a/>",
+            "",
+        ] {
+            assert!(parse(text).is_err(), "{}", text);
+        }
+        assert!(render(&json!({"bad name": 1})).is_err());
+        assert!(render(&json!({"a": [[1]]})).is_err());
+        assert!(render(&json!({"a": [1, null]})).is_err());
+        assert_eq!(parse("<config/>").unwrap(), json!({}));
+    }
+
+    #[test]
+    fn control_characters_are_rejected() {
+        for value in [
+            json!({"a": "bell\u{7}"}),
+            json!({"a": {"@id": "x\u{1f}"}}),
+            json!({"a": {"@id": "x", "#text": "\u{0}"}}),
+        ] {
+            let err = render(&value).unwrap_err();
+            assert!(matches!(err, Error::InvalidPayload(_)), "{}", err);
+        }
+        let value = json!({"a": "tab\tline\ncr\r"});
+        assert_eq!(parse(&render(&value).unwrap()).unwrap(), value);
+    }
+
+    #[test]
+    fn empty_objects_and_text_next_to_attributes_are_lossy() {
+        let value = json!({"empty": {}, "window": {"@id": "main", "#text": "  Main  "}});
+        assert_eq!(
+            parse(&render(&value).unwrap()).unwrap(),
+            json!({"empty": "", "window": {"@id": "main", "#text": "Main"}})
+        );
+    }
+}